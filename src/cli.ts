@@ -7,6 +7,7 @@ import { buildCommand } from './commands/build';
 import { deployCommand } from './commands/deploy';
 import { devCommand } from './commands/dev';
 import { profileCommand } from './commands/profile';
+import { parityCommand } from './commands/parity';
 import { benchmarkCommand } from './commands/benchmark';
 import { configCommand } from './commands/config';
 
@@ -21,7 +22,8 @@ program
   .command('init')
   .description('Initialize a new Stylus project')
   .option('-n, --name <name>', 'Project name')
-  .option('-t, --template <template>', 'Project template (erc20, erc721, defi, basic)')
+  .option('-t, --template <template>', 'Project template (erc20, erc721, erc721-enumerable, defi, staking, erc4626, erc1155, multisig, upgradeable, compute, eip712, merkle-airdrop, basic)')
+  .option('-e, --extensions <extensions...>', 'Template extensions (erc20: permit, burnable, capped; erc721: enumerable, uri-storage, burnable)')
   .option('--rust-only', 'Initialize Rust-only project')
   .option('--solidity-only', 'Initialize Solidity-only project')
   .action(initCommand);
@@ -44,6 +46,8 @@ program
   .option('--gas-limit <amount>', 'Manual gas limit (overrides automatic estimation)')
   .option('--estimate-only', 'Only estimate gas, do not deploy')
   .option('--no-activate', 'Skip contract activation step')
+  .option('--constructor-args <args...>', 'Constructor arguments (ABI-encoded against the contract constructor)')
+  .option('--init-args <args...>', 'Initializer arguments for upgradeable projects (ABI-encoded against initialize)')
   .action(deployCommand);
 
 program
@@ -61,11 +65,23 @@ program
   .option('-n, --network <network>', 'Network name (arbitrum-sepolia, arbitrum-one, local)', 'local')
   .option('--rust-path <path>', 'Path to Rust contract')
   .option('--solidity-path <path>', 'Path to Solidity contract')
+  .option('--rust-address <address>', 'Deployed Rust contract to measure function gas against')
+  .option('--solidity-address <address>', 'Deployed Solidity contract to measure function gas against')
+  .option('--private-key <key>', 'Private key to measure from (not recommended, use --private-key-path)')
+  .option('--private-key-path <path>', 'Path to file containing the private key to measure from')
   .option('--export <format>', 'Export format (json, csv, html)', 'json')
   .option('--no-compile', 'Skip compilation step')
   .option('--detailed', 'Show detailed gas breakdown')
+  .option('--no-parity-check', 'Profile even if the Rust and Solidity ABIs differ')
   .action(profileCommand);
 
+program
+  .command('parity')
+  .description('Check that the Rust and Solidity implementations expose the same ABI')
+  .option('--rust-path <path>', 'Path to Rust contract')
+  .option('--solidity-path <path>', 'Path to Solidity contract')
+  .action(parityCommand);
+
 program
   .command('benchmark')
   .description('Run comprehensive benchmarks on contracts')
@@ -3,6 +3,10 @@ import { FileSystem } from '../utils/file-system';
 import path from 'path';
 import execa from 'execa';
 import chalk from 'chalk';
+import { ethers } from 'ethers';
+import { RustCompiler } from '../compiler/rust-compiler';
+import { SolidityCompiler } from '../compiler/solidity-compiler';
+import { PROXY_FILE } from '../templates/generator';
 
 interface DeployOptions {
   network?: string;
@@ -12,8 +16,20 @@ interface DeployOptions {
   gasLimit?: string;
   estimateOnly?: boolean;
   noActivate?: boolean;
+  constructorArgs?: string[];
+  initArgs?: string[];
 }
 
+interface PreparedProxy {
+  abi: any[];
+  bytecode: string;
+  initData: string;
+  initializerSignature: string | null;
+}
+
+// Name of the function the proxy calls on the implementation from its constructor
+const INITIALIZER = 'initialize';
+
 export async function deployCommand(options: DeployOptions): Promise<void> {
   logger.header('Stylus Toolkit - Deploy Contract');
 
@@ -117,6 +133,74 @@ export async function deployCommand(options: DeployOptions): Promise<void> {
     });
     logger.newLine();
 
+    // Detect the constructor and ABI-encode any arguments given on the command line
+    const constructorArgs = options.constructorArgs || [];
+    let constructorSignature: string | null = null;
+
+    logger.startSpinner('Detecting contract constructor...');
+    try {
+      constructorSignature = await new RustCompiler(projectRoot).getConstructorSignature(
+        rustProjectPath
+      );
+      logger.succeedSpinner(
+        constructorSignature ? `Constructor: ${constructorSignature}` : 'Contract has no constructor'
+      );
+    } catch {
+      logger.failSpinner('Could not detect constructor');
+      if (constructorArgs.length > 0) {
+        logger.error('Constructor arguments were provided but the constructor signature is unknown.');
+        logger.info('Check that "cargo run --features export-abi -- constructor" works in contracts-rust/');
+        process.exit(1);
+      }
+    }
+
+    if (constructorSignature) {
+      try {
+        const encodedArgs = encodeConstructorArgs(constructorSignature, constructorArgs);
+        logger.table({
+          'Constructor': constructorSignature,
+          'Encoded Args': encodedArgs === '0x' ? 'None' : encodedArgs,
+        });
+        logger.newLine();
+      } catch (error) {
+        logger.error(`Invalid constructor arguments: ${(error as Error).message}`);
+        logger.info(`Expected: ${constructorSignature}`);
+        logger.info('Example: stylus-toolkit deploy --constructor-args 42 --private-key-path=./key.txt');
+        process.exit(1);
+      }
+    } else if (constructorArgs.length > 0) {
+      logger.error('Constructor arguments were provided but the contract has no constructor.');
+      process.exit(1);
+    }
+
+    // Upgradeable projects put the implementation behind an ERC-1967 proxy, deployed
+    // right after it with the encoded initializer call
+    const proxyPath = path.join(projectRoot, PROXY_FILE);
+    const initArgs = options.initArgs || [];
+    let proxy: PreparedProxy | null = null;
+
+    if (await FileSystem.fileExists(proxyPath)) {
+      try {
+        proxy = await prepareProxy(projectRoot, rustProjectPath, proxyPath, initArgs);
+      } catch (error) {
+        logger.failSpinner('Could not prepare the proxy');
+        logger.error((error as Error).message);
+        logger.info('Example: stylus-toolkit deploy --init-args 0x... --private-key-path=./key.txt');
+        process.exit(1);
+      }
+    } else if (initArgs.length > 0) {
+      logger.error('Initializer arguments were provided but the project has no proxy.');
+      process.exit(1);
+    }
+
+    const constructorFlags: string[] = [];
+    if (constructorSignature) {
+      constructorFlags.push('--constructor-signature', constructorSignature);
+      if (constructorArgs.length > 0) {
+        constructorFlags.push('--constructor-args', ...constructorArgs);
+      }
+    }
+
     // Estimate gas if not provided
     let gasLimit = options.gasLimit;
 
@@ -159,6 +243,7 @@ export async function deployCommand(options: DeployOptions): Promise<void> {
           wasmFilePath,
           '--endpoint',
           rpcUrl,
+          ...constructorFlags,
         ];
 
         if (options.privateKeyPath) {
@@ -223,6 +308,7 @@ export async function deployCommand(options: DeployOptions): Promise<void> {
         wasmFilePath,
         '--endpoint',
         rpcUrl,
+        ...constructorFlags,
       ];
 
       if (options.privateKeyPath) {
@@ -288,6 +374,31 @@ export async function deployCommand(options: DeployOptions): Promise<void> {
 
       logger.table(results);
 
+      let proxyAddress: string | undefined;
+      if (proxy) {
+        if (!addressMatch) {
+          throw new Error('Implementation address not found in the deployment output, proxy not deployed');
+        }
+
+        logger.newLine();
+        logger.section('Deploying Proxy');
+        logger.startSpinner('Deploying ERC-1967 proxy...');
+
+        const privateKey = options.privateKeyPath
+          ? (await FileSystem.readFile(options.privateKeyPath)).trim()
+          : (options.privateKey as string);
+        const deployed = await deployProxy(proxy, addressMatch[1], rpcUrl, privateKey);
+        proxyAddress = deployed.address;
+
+        logger.succeedSpinner('Proxy deployed and initialized');
+        logger.table({
+          'Proxy Address': chalk.cyan(deployed.address),
+          'Implementation': addressMatch[1],
+          'Initializer': proxy.initializerSignature || 'none',
+          'Transaction Hash': chalk.dim(deployed.txHash),
+        });
+      }
+
       // Show explorer links for public networks
       if (addressMatch) {
         logger.newLine();
@@ -295,11 +406,17 @@ export async function deployCommand(options: DeployOptions): Promise<void> {
 
         if (network === 'arbitrum-sepolia') {
           logger.info(`Contract: https://sepolia.arbiscan.io/address/${addressMatch[1]}`);
+          if (proxyAddress) {
+            logger.info(`Proxy: https://sepolia.arbiscan.io/address/${proxyAddress}`);
+          }
           if (txHashMatch) {
             logger.info(`Transaction: https://sepolia.arbiscan.io/tx/${txHashMatch[1]}`);
           }
         } else if (network === 'arbitrum-one' || network === 'arbitrum-mainnet') {
           logger.info(`Contract: https://arbiscan.io/address/${addressMatch[1]}`);
+          if (proxyAddress) {
+            logger.info(`Proxy: https://arbiscan.io/address/${proxyAddress}`);
+          }
           if (txHashMatch) {
             logger.info(`Transaction: https://arbiscan.io/tx/${txHashMatch[1]}`);
           }
@@ -312,7 +429,11 @@ export async function deployCommand(options: DeployOptions): Promise<void> {
       // Show next steps
       logger.newLine();
       logger.section('Next Steps');
-      if (addressMatch) {
+      if (proxyAddress) {
+        logger.info('1. Interact with your contract through the proxy address above');
+        logger.info('2. Verify on explorer (if on public network)');
+        logger.info(`3. Test with: cast call ${proxyAddress} "<function>" --rpc-url ${rpcUrl}`);
+      } else if (addressMatch) {
         logger.info('1. Interact with your contract using the address above');
         logger.info('2. Verify on explorer (if on public network)');
         logger.info(`3. Test with: cast call ${addressMatch[1]} "<function>" --rpc-url ${rpcUrl}`);
@@ -364,3 +485,97 @@ export async function deployCommand(options: DeployOptions): Promise<void> {
     process.exit(1);
   }
 }
+
+// ABI-encodes command line arguments against a constructor signature such as
+// "constructor(uint256 initial_number)". Throws if the arguments do not match.
+function encodeConstructorArgs(signature: string, rawArgs: string[]): string {
+  const fragment = ethers.ConstructorFragment.from(signature);
+
+  if (fragment.inputs.length !== rawArgs.length) {
+    throw new Error(`expected ${fragment.inputs.length} argument(s), got ${rawArgs.length}`);
+  }
+
+  const values = fragment.inputs.map((param, i) => parseAbiValue(param, rawArgs[i]));
+  return ethers.AbiCoder.defaultAbiCoder().encode(fragment.inputs, values);
+}
+
+// Compiles the ERC-1967 proxy and encodes the initialize call it makes on the
+// implementation from its constructor. Projects whose implementation has no
+// initialize function get a proxy with empty initializer calldata.
+async function prepareProxy(
+  projectRoot: string,
+  rustProjectPath: string,
+  proxyPath: string,
+  initArgs: string[]
+): Promise<PreparedProxy> {
+  const compilation = await new SolidityCompiler(projectRoot).compile(proxyPath);
+  if (!compilation.success || !compilation.abi) {
+    throw new Error(`Proxy compilation failed: ${(compilation.errors || []).join(', ')}`);
+  }
+
+  logger.startSpinner('Encoding initializer call...');
+
+  const abi = await new RustCompiler(projectRoot).exportAbi(rustProjectPath);
+  const iface = new ethers.Interface(abi);
+  const initializer = iface.getFunction(INITIALIZER);
+
+  let initData = '0x';
+  if (initializer) {
+    if (initializer.inputs.length !== initArgs.length) {
+      throw new Error(
+        `${initializer.format('sighash')} expects ${initializer.inputs.length} argument(s), got ${initArgs.length}`
+      );
+    }
+    const values = initializer.inputs.map((param, i) => parseAbiValue(param, initArgs[i]));
+    initData = iface.encodeFunctionData(initializer, values);
+  } else if (initArgs.length > 0) {
+    throw new Error(`Initializer arguments were provided but the implementation has no ${INITIALIZER} function`);
+  }
+
+  const initializerSignature = initializer ? initializer.format('sighash') : null;
+  logger.succeedSpinner(`Proxy ready (initializer: ${initializerSignature || 'none'})`);
+
+  return {
+    abi: compilation.abi,
+    bytecode: compilation.bytecode,
+    initData,
+    initializerSignature,
+  };
+}
+
+// Deploys the proxy in front of an implementation. The proxy constructor
+// delegates initData to the implementation in the same transaction.
+async function deployProxy(
+  proxy: PreparedProxy,
+  implementation: string,
+  rpcUrl: string,
+  privateKey: string
+): Promise<{ address: string; txHash: string }> {
+  const provider = new ethers.JsonRpcProvider(rpcUrl);
+  const wallet = new ethers.Wallet(privateKey, provider);
+
+  const factory = new ethers.ContractFactory(proxy.abi, proxy.bytecode, wallet);
+  const contract = await factory.deploy(implementation, proxy.initData);
+  await contract.waitForDeployment();
+
+  return {
+    address: await contract.getAddress(),
+    txHash: contract.deploymentTransaction()?.hash || '',
+  };
+}
+
+function parseAbiValue(param: ethers.ParamType, raw: string): any {
+  // Arrays and tuples are passed as JSON, e.g. '["0x...", "0x..."]'
+  if (param.isArray() || param.isTuple()) {
+    return JSON.parse(raw);
+  }
+
+  if (param.baseType === 'bool') {
+    if (raw !== 'true' && raw !== 'false') {
+      throw new Error(`invalid bool "${raw}" for ${param.name || param.type}`);
+    }
+    return raw === 'true';
+  }
+
+  return raw;
+}
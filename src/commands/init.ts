@@ -4,8 +4,9 @@ import { logger } from '../utils/logger';
 import { FileSystem } from '../utils/file-system';
 import { InitOptions, ProjectConfig } from '../types';
 import { TemplateGenerator } from '../templates/generator';
+import { TEMPLATES } from '../templates/templates';
 
-const VALID_TEMPLATES = ['basic', 'erc20', 'erc721', 'defi'];
+const VALID_TEMPLATES = ['basic', 'erc20', 'erc721', 'erc721-enumerable', 'defi', 'staking', 'erc4626', 'erc1155', 'multisig', 'upgradeable', 'compute', 'eip712', 'merkle-airdrop'];
 
 export async function initCommand(options: InitOptions): Promise<void> {
   logger.header('Stylus Toolkit - Initialize New Project');
@@ -55,7 +56,16 @@ export async function initCommand(options: InitOptions): Promise<void> {
         { name: 'Basic (Simple counter)', value: 'basic' },
         { name: 'ERC-20 Token', value: 'erc20' },
         { name: 'ERC-721 NFT', value: 'erc721' },
-        { name: 'DeFi Protocol', value: 'defi' },
+        { name: 'ERC-721 NFT (Enumerable + per-token URIs)', value: 'erc721-enumerable' },
+        { name: 'DeFi (Constant-product AMM)', value: 'defi' },
+        { name: 'Staking Rewards', value: 'staking' },
+        { name: 'ERC-4626 Vault', value: 'erc4626' },
+        { name: 'ERC-1155 Multi-Token', value: 'erc1155' },
+        { name: 'Multisig Wallet', value: 'multisig' },
+        { name: 'Upgradeable (ERC-1967 proxy + Stylus implementation)', value: 'upgradeable' },
+        { name: 'Compute Benchmark', value: 'compute' },
+        { name: 'EIP-712 Order Settlement', value: 'eip712' },
+        { name: 'Merkle Airdrop', value: 'merkle-airdrop' },
       ],
       default: 'basic',
     });
@@ -95,6 +105,44 @@ export async function initCommand(options: InitOptions): Promise<void> {
     template = 'basic';
   }
 
+  // Variants offer their base template's extensions, some of them already included
+  const selected = TEMPLATES[template];
+  const availableExtensions: Record<string, any> =
+    (selected.base ? TEMPLATES[selected.base] : selected).extensions || {};
+  const includedExtensions: string[] = selected.includes || [];
+  let extensions = options.extensions;
+
+  if (includedExtensions.length > 0) {
+    logger.info(`Template "${template}" includes: ${includedExtensions.join(', ')}`);
+  }
+
+  if (extensions) {
+    const unknown = extensions.filter((name) => !(name in availableExtensions));
+    if (unknown.length > 0) {
+      const available = Object.keys(availableExtensions);
+      logger.error(
+        `Invalid extension(s) for template "${template}": ${unknown.join(', ')}. ` +
+          `Available extensions: ${available.length > 0 ? available.join(', ') : 'none'}`
+      );
+      process.exit(1);
+    }
+  } else if (Object.keys(availableExtensions).some((name) => !includedExtensions.includes(name))) {
+    const answers = await inquirer.prompt([
+      {
+        type: 'checkbox',
+        name: 'extensions',
+        message: 'Select extensions to include:',
+        choices: Object.entries(availableExtensions).map(([name, extension]) => ({
+          name: extension.description,
+          value: name,
+          disabled: includedExtensions.includes(name) ? 'included' : false,
+        })),
+      },
+    ]);
+    extensions = answers.extensions as string[];
+  }
+  extensions = [...new Set([...includedExtensions, ...(extensions || [])])];
+
   const hasRust = !options.solidityOnly;
   const hasSolidity = !options.rustOnly;
 
@@ -110,12 +158,13 @@ export async function initCommand(options: InitOptions): Promise<void> {
     logger.updateSpinner('Generating template files...');
 
     const templateGenerator = new TemplateGenerator();
-    await templateGenerator.generate(projectPath, template, hasRust, hasSolidity);
+    await templateGenerator.generate(projectPath, template, hasRust, hasSolidity, extensions);
 
     const projectConfig: ProjectConfig = {
       name: projectName!,
       version: '1.0.0',
       template,
+      extensions,
       hasRust,
       hasSolidity,
       createdAt: new Date().toISOString(),
@@ -133,6 +182,7 @@ export async function initCommand(options: InitOptions): Promise<void> {
     logger.table({
       'Project Name': projectName!,
       'Template': template,
+      'Extensions': extensions.length > 0 ? extensions.join(', ') : 'None',
       'Rust (Stylus)': hasRust ? 'Yes' : 'No',
       'Solidity': hasSolidity ? 'Yes' : 'No',
       'Location': projectPath,
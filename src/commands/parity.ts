@@ -0,0 +1,85 @@
+import path from 'path';
+import Table from 'cli-table3';
+import chalk from 'chalk';
+import { logger } from '../utils/logger';
+import { FileSystem } from '../utils/file-system';
+import { RustCompiler } from '../compiler/rust-compiler';
+import { SolidityCompiler } from '../compiler/solidity-compiler';
+import { FunctionMatcher } from '../profiler/function-matcher';
+import { ParityChecker } from '../profiler/parity-checker';
+import { ParityIssueKind, ParityOptions, ParityReport } from '../types';
+
+const ISSUE_LABELS: Record<ParityIssueKind, string> = {
+  'missing-in-rust': 'Missing in Rust',
+  'missing-in-solidity': 'Missing in Solidity',
+  'signature-mismatch': 'Signature mismatch',
+  'mutability-mismatch': 'Mutability mismatch',
+};
+
+export async function parityCommand(options: ParityOptions): Promise<void> {
+  logger.header('Stylus Toolkit - ABI Parity Check');
+
+  try {
+    logger.startSpinner('Exporting Rust ABI...');
+    const rustCompiler = new RustCompiler();
+    const rustAbi = await rustCompiler.exportAbi(
+      options.rustPath ? path.dirname(options.rustPath) : undefined
+    );
+    logger.succeedSpinner('Rust ABI exported');
+
+    const solidityCompiler = new SolidityCompiler();
+    const solidityResult = await solidityCompiler.compile(options.solidityPath);
+
+    if (!solidityResult.success || !solidityResult.abi) {
+      throw new Error(`Solidity compilation failed: ${(solidityResult.errors || []).join(', ')}`);
+    }
+
+    const mapping = await FunctionMatcher.loadMapping(FileSystem.getProjectRoot());
+    const report = new ParityChecker().check(rustAbi, solidityResult.abi, mapping);
+
+    displayParityReport(report);
+
+    if (!report.equivalent) {
+      process.exit(1);
+    }
+  } catch (error) {
+    logger.failSpinner('Parity check failed');
+    logger.error((error as Error).message);
+    process.exit(1);
+  }
+}
+
+export function displayParityReport(report: ParityReport): void {
+  logger.newLine();
+  logger.section('ABI Parity');
+
+  if (report.equivalent) {
+    logger.success(`Rust and Solidity ABIs match (${report.matched} functions)`);
+    return;
+  }
+
+  const table = new Table({
+    head: ['Issue', 'Rust (Stylus)', 'Solidity'],
+    colWidths: [22, 45, 45],
+    wordWrap: true,
+    style: {
+      head: ['cyan', 'bold'],
+    },
+  });
+
+  for (const issue of report.issues) {
+    table.push([
+      chalk.red(ISSUE_LABELS[issue.kind]),
+      issue.rust || issue.rustFunction || '-',
+      issue.solidity || issue.solidityFunction || '-',
+    ]);
+  }
+
+  console.log(table.toString());
+
+  logger.newLine();
+  logger.error(
+    `Contracts are not equivalent: ${report.issues.length} issue(s), ${report.matched} function(s) matched`
+  );
+  logger.info('Map renamed functions in .stylus-toolkit/function-map.json, e.g. { "number": "getCount" }');
+}
@@ -8,9 +8,12 @@ import { RustCompiler } from '../compiler/rust-compiler';
 import { SolidityCompiler } from '../compiler/solidity-compiler';
 import { GasProfiler } from '../profiler/gas-profiler';
 import { GasComparator } from '../profiler/comparator';
+import { FunctionMatcher } from '../profiler/function-matcher';
+import { ParityChecker } from '../profiler/parity-checker';
 import { ResultsStore } from '../storage/results-store';
 import { ResultExporter } from '../exporter/exporter';
-import { ProfileOptions } from '../types';
+import { displayParityReport } from './parity';
+import { FunctionMatchResult, ProfileOptions } from '../types';
 
 export async function profileCommand(options: ProfileOptions): Promise<void> {
   logger.header('Stylus Toolkit - Gas Profiling');
@@ -90,19 +93,58 @@ export async function profileCommand(options: ProfileOptions): Promise<void> {
       process.exit(1);
     }
 
+    const mapping = await FunctionMatcher.loadMapping(FileSystem.getProjectRoot());
+    const profileConfig = await GasProfiler.loadConfig(FileSystem.getProjectRoot());
+
+    let functionMatches: FunctionMatchResult | undefined;
+    if (rustResult.abi && solidityResult.abi) {
+      if (options.parityCheck) {
+        const parity = new ParityChecker().check(rustResult.abi, solidityResult.abi, mapping);
+        if (!parity.equivalent) {
+          displayParityReport(parity);
+          logger.error('Refusing to compare gas between non-equivalent contracts');
+          logger.info('Fix the differences above, or pass --no-parity-check to profile anyway');
+          process.exit(1);
+        }
+      }
+
+      functionMatches = new FunctionMatcher().match(rustResult.abi, solidityResult.abi, mapping);
+    } else {
+      logger.warn('ABI unavailable for one or more contracts, matching functions by name only');
+    }
+
     logger.newLine();
     logger.section('Gas Profiling Phase');
 
-    const profiler = new GasProfiler(rpcUrl);
+    // Owner-gated functions only succeed when estimated from the deployer's account;
+    // without a key the profiler measures from a random, unfunded one.
+    const privateKey = options.privateKeyPath
+      ? (await FileSystem.readFile(options.privateKeyPath)).trim()
+      : options.privateKey;
+
+    if (!privateKey && (options.rustAddress || options.solidityAddress)) {
+      logger.warn('No private key given, measuring from a random account');
+      logger.info('Owner-only functions will revert; pass --private-key-path to measure them');
+    }
+
+    const profiler = new GasProfiler(rpcUrl, privateKey);
 
-    const rustProfile = await profiler.profileContract(rustResult);
-    const solidityProfile = await profiler.profileContract(solidityResult);
+    const rustProfile = await profiler.profileContract(
+      rustResult,
+      options.rustAddress,
+      profileConfig
+    );
+    const solidityProfile = await profiler.profileContract(
+      solidityResult,
+      options.solidityAddress,
+      GasProfiler.renameConfig(profileConfig, mapping)
+    );
 
     logger.newLine();
     logger.section('Comparison Analysis');
 
     const comparator = new GasComparator();
-    const comparison = comparator.compare(rustProfile, solidityProfile);
+    const comparison = comparator.compare(rustProfile, solidityProfile, functionMatches);
 
     displayResults(comparison, options.detailed);
 
@@ -178,8 +220,12 @@ function displayResults(comparison: any, detailed: boolean): void {
     for (const [functionName, savings] of comparison.savings.functionSavings) {
       const savingsColor = savings.absolute > 0 ? chalk.green : chalk.red;
 
+      const label = savings.solidityFunctionName
+        ? `${functionName} / ${savings.solidityFunctionName}`
+        : functionName;
+
       table.push([
-        `  ${functionName}`,
+        `  ${label}`,
         savings.rustGas.toLocaleString(),
         savings.solidityGas.toLocaleString(),
         savingsColor(savings.absolute.toLocaleString()),
@@ -198,6 +244,19 @@ function displayResults(comparison: any, detailed: boolean): void {
 
   console.log(table.toString());
 
+  const matches = comparison.functionMatches;
+  if (matches && (matches.unmatchedRust.length > 0 || matches.unmatchedSolidity.length > 0)) {
+    logger.newLine();
+    logger.section('Unmatched Functions');
+    if (matches.unmatchedRust.length > 0) {
+      logger.warn(`Rust only: ${matches.unmatchedRust.join(', ')}`);
+    }
+    if (matches.unmatchedSolidity.length > 0) {
+      logger.warn(`Solidity only: ${matches.unmatchedSolidity.join(', ')}`);
+    }
+    logger.info('Map renamed functions in .stylus-toolkit/function-map.json, e.g. { "number": "getCount" }');
+  }
+
   // Display TCO Analysis (Total Cost of Ownership)
   logger.newLine();
   logger.section('Total Cost of Ownership (TCO) Analysis');
@@ -3,6 +3,7 @@ import path from 'path';
 import fs from 'fs-extra';
 import { logger } from '../utils/logger';
 import { FileSystem } from '../utils/file-system';
+import { solidityInterfaceToAbi } from '../utils/abi';
 import { CompilationResult } from '../types';
 
 export class RustCompiler {
@@ -75,6 +76,19 @@ export class RustCompiler {
       const wasmBinary = await fs.readFile(wasmFilePath);
       const wasmSizeKb = wasmBinary.length / 1024;
 
+      const stdWarning = (await this.linksStd(rustProjectPath, wasmBinary))
+        ? 'Contract links the Rust standard library; add #![no_std] to shrink the WASM and its deployment cost'
+        : null;
+
+      logger.updateSpinner('Exporting contract ABI...');
+
+      let abi: any[] | undefined;
+      try {
+        abi = await this.exportAbi(rustProjectPath);
+      } catch (error) {
+        logger.warn(`ABI export failed: ${(error as Error).message}`);
+      }
+
       logger.updateSpinner('Running cargo-stylus check...');
 
       try {
@@ -93,11 +107,17 @@ export class RustCompiler {
 
       const warnings = this.extractWarnings(stderr);
 
+      if (stdWarning) {
+        logger.warn(stdWarning);
+        warnings.push(stdWarning);
+      }
+
       return {
         success: true,
         language: 'rust',
         contractName,
         bytecode: wasmBinary.toString('hex'),
+        abi,
         wasmSize: wasmBinary.length,
         bytecodeSizeKb: wasmSizeKb,
         compilationTime,
@@ -119,6 +139,37 @@ export class RustCompiler {
     }
   }
 
+  // Runs the contract's export-abi binary and converts the Solidity interface it
+  // prints into a JSON ABI, including the constructor when one is declared.
+  async exportAbi(rustProjectPath?: string): Promise<any[]> {
+    const cwd = rustProjectPath || path.join(this.projectPath, 'contracts-rust');
+
+    const { stdout } = await execa(
+      'cargo',
+      ['run', '--quiet', '--features', 'export-abi'],
+      { cwd }
+    );
+
+    const constructorSignature = await this.getConstructorSignature(cwd);
+
+    return solidityInterfaceToAbi(stdout, constructorSignature);
+  }
+
+  // Reads the constructor signature from the contract's export-abi binary.
+  // Returns null when the contract does not declare a #[constructor].
+  async getConstructorSignature(rustProjectPath?: string): Promise<string | null> {
+    const cwd = rustProjectPath || path.join(this.projectPath, 'contracts-rust');
+
+    const { stdout } = await execa(
+      'cargo',
+      ['run', '--quiet', '--features', 'export-abi', '--', 'constructor'],
+      { cwd }
+    );
+
+    const match = stdout.match(/constructor\s*\([^)]*\)(\s*payable)?/);
+    return match ? match[0].trim() : null;
+  }
+
   private async checkCargoInstalled(): Promise<boolean> {
     try {
       await execa('cargo', ['--version']);
@@ -145,6 +196,22 @@ export class RustCompiler {
     return nameMatch ? nameMatch[1] : 'contract';
   }
 
+  // Stylus contracts are expected to be no_std. A crate that links std carries its
+  // allocator and panic machinery into the WASM, which inflates the deployment cost.
+  private async linksStd(projectPath: string, wasmBinary: Buffer): Promise<boolean> {
+    const libPath = path.join(projectPath, 'src', 'lib.rs');
+
+    if (await FileSystem.fileExists(libPath)) {
+      const source = await FileSystem.readFile(libPath);
+      if (!/#!\[\s*(no_std|cfg_attr\([^\]]*\bno_std\b[^\]]*\))\s*\]/.test(source)) {
+        return true;
+      }
+    }
+
+    // Source paths from std's panic messages survive stripping
+    return wasmBinary.includes('library/std/src');
+  }
+
   private extractWarnings(stderr: string): string[] {
     const warnings: string[] = [];
     const warningRegex = /warning: (.+)/g;
@@ -79,7 +79,11 @@ export class SolidityCompiler {
     const output = JSON.parse(stdout);
     const contracts = output.contracts;
 
-    const contractKey = Object.keys(contracts)[0];
+    const contractKey = this.selectContract(
+      Object.keys(contracts),
+      contractFile,
+      (key) => contracts[key].bin
+    );
     const contract = contracts[contractKey];
 
     const bytecode = contract.bin;
@@ -161,8 +165,13 @@ export class SolidityCompiler {
       }
     }
 
-    const contractName = Object.keys(output.contracts[contractFile])[0];
-    const contract = output.contracts[contractFile][contractName];
+    const fileContracts = output.contracts[contractFile];
+    const contractName = this.selectContract(
+      Object.keys(fileContracts),
+      contractFile,
+      (name) => fileContracts[name].evm.bytecode.object
+    );
+    const contract = fileContracts[contractName];
 
     const bytecode = contract.evm.bytecode.object;
     const abi = contract.abi;
@@ -185,6 +194,23 @@ export class SolidityCompiler {
     };
   }
 
+  // A file may declare interfaces or libraries next to its main contract. Prefer the
+  // contract named after the file, then the last one with deployable bytecode.
+  private selectContract(
+    keys: string[],
+    contractFile: string,
+    bytecodeOf: (key: string) => string
+  ): string {
+    const fileName = path.basename(contractFile, '.sol');
+    const named = keys.find((key) => key.split(':').pop() === fileName);
+    if (named) {
+      return named;
+    }
+
+    const deployable = keys.filter((key) => bytecodeOf(key).length > 0);
+    return deployable.length > 0 ? deployable[deployable.length - 1] : keys[0];
+  }
+
   private async checkSolcInstalled(): Promise<boolean> {
     try {
       await execa('solc', ['--version']);
@@ -101,6 +101,7 @@ export class ResultExporter {
       },
       functions: Array.from(comparison.savings.functionSavings.values()).map((f) => ({
         name: f.functionName,
+        solidityName: f.solidityFunctionName,
         rust: f.rustGas,
         solidity: f.solidityGas,
         savings: {
@@ -109,6 +110,12 @@ export class ResultExporter {
         },
       })),
       totalAverage: comparison.savings.totalAvgSavings,
+      unmatchedFunctions: comparison.functionMatches
+        ? {
+            rust: comparison.functionMatches.unmatchedRust,
+            solidity: comparison.functionMatches.unmatchedSolidity,
+          }
+        : undefined,
     };
   }
 
@@ -125,7 +132,9 @@ export class ResultExporter {
 
     for (const [functionName, savings] of comparison.savings.functionSavings) {
       records.push({
-        Metric: functionName,
+        Metric: savings.solidityFunctionName
+          ? `${functionName} / ${savings.solidityFunctionName}`
+          : functionName,
         'Rust (Gas)': savings.rustGas,
         'Solidity (Gas)': savings.solidityGas,
         'Savings (Gas)': savings.absolute,
@@ -161,7 +170,9 @@ export class ResultExporter {
         positive: comparison.savings.deploymentSavings.absolute > 0,
       },
       functions: Array.from(comparison.savings.functionSavings.values()).map((f) => ({
-        name: f.functionName,
+        name: f.solidityFunctionName
+          ? `${f.functionName} / ${f.solidityFunctionName}`
+          : f.functionName,
         rust: f.rustGas.toLocaleString(),
         solidity: f.solidityGas.toLocaleString(),
         savingsGas: f.absolute.toLocaleString(),
@@ -3,8 +3,11 @@ export { RustCompiler } from './compiler/rust-compiler';
 export { SolidityCompiler } from './compiler/solidity-compiler';
 export { GasProfiler } from './profiler/gas-profiler';
 export { GasComparator } from './profiler/comparator';
+export { FunctionMatcher } from './profiler/function-matcher';
+export { ParityChecker } from './profiler/parity-checker';
 export { ResultsStore } from './storage/results-store';
 export { ResultExporter } from './exporter/exporter';
 export { FileSystem } from './utils/file-system';
 export { logger } from './utils/logger';
 export { config } from './utils/config';
+export { solidityInterfaceToAbi, solidityInterfaceToFragments } from './utils/abi';
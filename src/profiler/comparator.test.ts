@@ -0,0 +1,132 @@
+import { GasComparator } from './comparator';
+import { FunctionMatcher } from './function-matcher';
+import { GasProfile } from '../types';
+
+function profile(language: 'rust' | 'solidity', functionGas: Record<string, number>): GasProfile {
+  return {
+    contractName: 'Counter',
+    language,
+    deploymentGas: 100000,
+    functionGas: new Map(
+      Object.entries(functionGas).map(([name, avgGas]) => [name, { avgGas, calls: 100 } as any])
+    ),
+    timestamp: new Date().toISOString(),
+    network: 'local',
+  };
+}
+
+describe('GasComparator', () => {
+  const rustAbi = [
+    'function number() view returns (uint256)',
+    'function setNumber(uint256 newNumber)',
+    'function increment()',
+  ];
+  const solidityAbi = [
+    'function getCount() view returns (uint256)',
+    'function setCount(uint256 newNumber)',
+    'function increment()',
+  ];
+
+  it('compares renamed functions with their mapped counterparts', () => {
+    const matches = new FunctionMatcher().match(rustAbi, solidityAbi, {
+      number: 'getCount',
+      setNumber: 'setCount',
+    });
+    const rust = profile('rust', { number: 5000, setNumber: 12000, increment: 11000 });
+    const solidity = profile('solidity', { getCount: 6000, setCount: 20000, increment: 19000 });
+
+    const comparison = new GasComparator().compare(rust, solidity, matches);
+    const savings = comparison.savings.functionSavings;
+
+    expect(savings.get('number')).toMatchObject({
+      solidityFunctionName: 'getCount',
+      rustGas: 5000,
+      solidityGas: 6000,
+      absolute: 1000,
+    });
+    expect(savings.get('setNumber')).toMatchObject({
+      solidityFunctionName: 'setCount',
+      rustGas: 12000,
+      solidityGas: 20000,
+    });
+    expect(savings.get('increment')).toMatchObject({
+      solidityFunctionName: undefined,
+      rustGas: 11000,
+      solidityGas: 19000,
+    });
+    expect(comparison.tco.functionCount).toBe(3);
+    expect(comparison.functionMatches?.unmatchedRust).toEqual([]);
+  });
+
+  it('leaves renamed functions out without a mapping', () => {
+    const matches = new FunctionMatcher().match(rustAbi, solidityAbi);
+    const rust = profile('rust', { number: 5000, setNumber: 12000, increment: 11000 });
+    const solidity = profile('solidity', { getCount: 6000, setCount: 20000, increment: 19000 });
+
+    const comparison = new GasComparator().compare(rust, solidity, matches);
+
+    expect([...comparison.savings.functionSavings.keys()]).toEqual(['increment']);
+    expect(comparison.functionMatches?.unmatchedRust).toEqual(['number', 'setNumber']);
+    expect(comparison.functionMatches?.unmatchedSolidity).toEqual(['getCount', 'setCount']);
+  });
+
+  it('compares each array length of a function separately', () => {
+    const matches = new FunctionMatcher().match(
+      ['function transferBatch(uint256[] ids)'],
+      ['function safeBatchTransferFrom(uint256[] ids)'],
+      { transferBatch: 'safeBatchTransferFrom' }
+    );
+    const rust = profile('rust', { 'transferBatch[1]': 30000, 'transferBatch[10]': 90000 });
+    const solidity = profile('solidity', {
+      'safeBatchTransferFrom[1]': 35000,
+      'safeBatchTransferFrom[10]': 120000,
+    });
+
+    const savings = new GasComparator().compare(rust, solidity, matches).savings.functionSavings;
+
+    expect(savings.get('transferBatch[1]')).toMatchObject({
+      solidityFunctionName: 'safeBatchTransferFrom[1]',
+      solidityGas: 35000,
+    });
+    expect(savings.get('transferBatch[10]')).toMatchObject({
+      solidityFunctionName: 'safeBatchTransferFrom[10]',
+      solidityGas: 120000,
+    });
+  });
+
+  it('compares the revert path of a function as its own entry', () => {
+    const abi = ['function transfer(address to, uint256 amount) returns (bool)'];
+    const matches = new FunctionMatcher().match(abi, abi);
+    const rust = profile('rust', { transfer: 30000, 'transfer[revert]': 24000 });
+    const solidity = profile('solidity', { transfer: 34000, 'transfer[revert]': 26000 });
+
+    const savings = new GasComparator().compare(rust, solidity, matches).savings.functionSavings;
+
+    expect(savings.get('transfer')).toMatchObject({ rustGas: 30000, solidityGas: 34000 });
+    expect(savings.get('transfer[revert]')).toMatchObject({
+      solidityFunctionName: undefined,
+      rustGas: 24000,
+      solidityGas: 26000,
+    });
+  });
+
+  it('reports the log cost row without counting it in the TCO', () => {
+    const matches = new FunctionMatcher().match(rustAbi, solidityAbi, {
+      setNumber: 'setCount',
+    });
+    const rust = profile('rust', { setNumber: 12000 });
+    const solidity = profile('solidity', { setCount: 20000 });
+    rust.functionGas.set('log', { avgGas: 1500, calls: 100, derived: true } as any);
+    solidity.functionGas.set('log', { avgGas: 1800, calls: 100, derived: true } as any);
+
+    const comparison = new GasComparator().compare(rust, solidity, matches);
+
+    expect(comparison.savings.functionSavings.get('log')).toMatchObject({
+      rustGas: 1500,
+      solidityGas: 1800,
+      absolute: 300,
+    });
+    expect(comparison.tco.functionCount).toBe(1);
+    expect(comparison.tco.rustTCO).toBe(100000 + 12000 * 100);
+  });
+});
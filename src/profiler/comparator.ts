@@ -3,13 +3,19 @@ import {
   ComparisonResult,
   GasSavings,
   FunctionSavings,
+  FunctionMatchResult,
   TCOAnalysis,
 } from '../types';
 
 export class GasComparator {
-  compare(rustProfile: GasProfile, solidityProfile: GasProfile): ComparisonResult {
-    const savings = this.calculateSavings(rustProfile, solidityProfile);
-    const tco = this.calculateTCO(rustProfile, solidityProfile);
+  compare(
+    rustProfile: GasProfile,
+    solidityProfile: GasProfile,
+    functionMatches?: FunctionMatchResult
+  ): ComparisonResult {
+    const counterparts = this.buildCounterparts(functionMatches);
+    const savings = this.calculateSavings(rustProfile, solidityProfile, counterparts);
+    const tco = this.calculateTCO(rustProfile, solidityProfile, counterparts);
 
     return {
       contractName: rustProfile.contractName,
@@ -17,11 +23,37 @@ export class GasComparator {
       solidityProfile,
       savings,
       tco,
+      functionMatches,
       timestamp: new Date().toISOString(),
     };
   }
 
-  private calculateSavings(rustProfile: GasProfile, solidityProfile: GasProfile): GasSavings {
+  // Maps Rust function names to the Solidity function they should be compared with.
+  // Functions without an entry are compared with the Solidity function of the same name.
+  private buildCounterparts(functionMatches?: FunctionMatchResult): Map<string, string> {
+    const counterparts = new Map<string, string>();
+
+    for (const match of functionMatches?.matches || []) {
+      counterparts.set(match.rustFunction, match.solidityFunction);
+    }
+
+    return counterparts;
+  }
+
+  // Profile keys are function names, with a label appended for functions measured
+  // several times: the array length (safeBatchTransferFrom[10]) or the case from
+  // the profile configuration (iteratedKeccak[100]). The label carries over to
+  // the counterpart's key.
+  private counterpartKey(key: string, counterparts: Map<string, string>): string {
+    const [, name, label = ''] = key.match(/^(.*?)(\[[^\]]+\])?$/) || [key, key];
+    return (counterparts.get(name) || name) + label;
+  }
+
+  private calculateSavings(
+    rustProfile: GasProfile,
+    solidityProfile: GasProfile,
+    counterparts: Map<string, string>
+  ): GasSavings {
     const deploymentSavings = this.calculateDeploymentSavings(
       rustProfile.deploymentGas,
       solidityProfile.deploymentGas
@@ -29,7 +61,8 @@ export class GasComparator {
 
     const functionSavings = this.calculateFunctionSavings(
       rustProfile.functionGas,
-      solidityProfile.functionGas
+      solidityProfile.functionGas,
+      counterparts
     );
 
     const totalAvgSavings = this.calculateTotalAvgSavings(functionSavings);
@@ -53,12 +86,14 @@ export class GasComparator {
 
   private calculateFunctionSavings(
     rustFunctions: Map<string, any>,
-    solidityFunctions: Map<string, any>
+    solidityFunctions: Map<string, any>,
+    counterparts: Map<string, string>
   ): Map<string, FunctionSavings> {
     const savingsMap = new Map<string, FunctionSavings>();
 
     for (const [functionName, rustData] of rustFunctions) {
-      const solidityData = solidityFunctions.get(functionName);
+      const solidityFunctionName = this.counterpartKey(functionName, counterparts);
+      const solidityData = solidityFunctions.get(solidityFunctionName);
 
       if (solidityData) {
         const absolute = solidityData.avgGas - rustData.avgGas;
@@ -67,6 +102,8 @@ export class GasComparator {
 
         savingsMap.set(functionName, {
           functionName,
+          solidityFunctionName:
+            solidityFunctionName !== functionName ? solidityFunctionName : undefined,
           absolute,
           percentage,
           rustGas: rustData.avgGas,
@@ -97,7 +134,11 @@ export class GasComparator {
     };
   }
 
-  private calculateTCO(rustProfile: GasProfile, solidityProfile: GasProfile): TCOAnalysis {
+  private calculateTCO(
+    rustProfile: GasProfile,
+    solidityProfile: GasProfile,
+    counterparts: Map<string, string>
+  ): TCOAnalysis {
     // Total Cost of Ownership = Deployment + (Execution × Call Frequency)
     const callFrequency = 100; // Use function call frequency from estimates
 
@@ -106,12 +147,18 @@ export class GasComparator {
     let functionCount = 0;
 
     for (const [functionName, rustData] of rustProfile.functionGas) {
-      const solidityData = solidityProfile.functionGas.get(functionName);
+      const solidityFunctionName = this.counterpartKey(functionName, counterparts);
+      const solidityData = solidityProfile.functionGas.get(solidityFunctionName);
       if (solidityData) {
         // Type assertion for estimation data which has avgGas and calls properties
         const rustDataAny = rustData as any;
         const solidityDataAny = solidityData as any;
 
+        // Differences between two functions aren't calls of their own
+        if (rustDataAny.derived) {
+          continue;
+        }
+
         const rustCalls = rustDataAny.calls || rustData.executions || callFrequency;
         const solidityCalls = solidityDataAny.calls || solidityData.executions || callFrequency;
 
@@ -160,7 +207,10 @@ export class GasComparator {
       lines.push('');
 
       for (const [functionName, savings] of comparison.savings.functionSavings) {
-        lines.push(`  ${functionName}:`);
+        const label = savings.solidityFunctionName
+          ? `${functionName} / ${savings.solidityFunctionName}`
+          : functionName;
+        lines.push(`  ${label}:`);
         lines.push(`    Rust:     ${savings.rustGas.toLocaleString()} gas`);
         lines.push(`    Solidity: ${savings.solidityGas.toLocaleString()} gas`);
         lines.push(
@@ -175,6 +225,18 @@ export class GasComparator {
       );
     }
 
+    const matches = comparison.functionMatches;
+    if (matches && (matches.unmatchedRust.length > 0 || matches.unmatchedSolidity.length > 0)) {
+      lines.push('');
+      lines.push('Unmatched Functions:');
+      if (matches.unmatchedRust.length > 0) {
+        lines.push(`  Rust only:     ${matches.unmatchedRust.join(', ')}`);
+      }
+      if (matches.unmatchedSolidity.length > 0) {
+        lines.push(`  Solidity only: ${matches.unmatchedSolidity.join(', ')}`);
+      }
+    }
+
     return lines.join('\n');
   }
 }
@@ -0,0 +1,87 @@
+import path from 'path';
+import { ethers } from 'ethers';
+import { logger } from '../utils/logger';
+import { FileSystem } from '../utils/file-system';
+import { FunctionMatch, FunctionMatchResult } from '../types';
+
+export const FUNCTION_MAP_FILE = path.join('.stylus-toolkit', 'function-map.json');
+
+export class FunctionMatcher {
+  // Loads the per-project Rust -> Solidity function name mapping, if present.
+  static async loadMapping(projectRoot: string): Promise<Record<string, string>> {
+    const mappingPath = path.join(projectRoot, FUNCTION_MAP_FILE);
+
+    if (!(await FileSystem.fileExists(mappingPath))) {
+      return {};
+    }
+
+    try {
+      return await FileSystem.readJson(mappingPath);
+    } catch (error) {
+      logger.warn(`Could not read ${FUNCTION_MAP_FILE}: ${(error as Error).message}`);
+      return {};
+    }
+  }
+
+  // Pairs each Rust function with its Solidity counterpart. Functions with identical
+  // selectors (same name and argument types) match automatically; the remaining ones
+  // are paired through the mapping file.
+  match(
+    rustAbi: any[],
+    solidityAbi: any[],
+    mapping: Record<string, string> = {}
+  ): FunctionMatchResult {
+    const rustFunctions = this.functionsBySelector(rustAbi);
+    const solidityFunctions = this.functionsBySelector(solidityAbi);
+
+    const matches: FunctionMatch[] = [];
+    const matchedRust = new Set<string>();
+    const matchedSolidity = new Set<string>();
+
+    for (const [selector, rustFunction] of rustFunctions) {
+      const solidityFunction = solidityFunctions.get(selector);
+      if (solidityFunction) {
+        matches.push({ rustFunction, solidityFunction, matchedBy: 'selector' });
+        matchedRust.add(rustFunction);
+        matchedSolidity.add(solidityFunction);
+      }
+    }
+
+    const rustNames = new Set(rustFunctions.values());
+    const solidityNames = new Set(solidityFunctions.values());
+
+    for (const [rustFunction, solidityFunction] of Object.entries(mapping)) {
+      if (!rustNames.has(rustFunction) || !solidityNames.has(solidityFunction)) {
+        logger.warn(
+          `Ignoring function mapping ${rustFunction} -> ${solidityFunction} (not found in ABI)`
+        );
+        continue;
+      }
+
+      if (matchedRust.has(rustFunction) || matchedSolidity.has(solidityFunction)) {
+        continue;
+      }
+
+      matches.push({ rustFunction, solidityFunction, matchedBy: 'mapping' });
+      matchedRust.add(rustFunction);
+      matchedSolidity.add(solidityFunction);
+    }
+
+    return {
+      matches,
+      unmatchedRust: [...rustNames].filter((name) => !matchedRust.has(name)).sort(),
+      unmatchedSolidity: [...solidityNames].filter((name) => !matchedSolidity.has(name)).sort(),
+    };
+  }
+
+  private functionsBySelector(abi: any[]): Map<string, string> {
+    const functions = new Map<string, string>();
+    const iface = new ethers.Interface(abi);
+
+    iface.forEachFunction((fragment) => {
+      functions.set(fragment.selector, fragment.name);
+    });
+
+    return functions;
+  }
+}
@@ -1,9 +1,17 @@
+import path from 'path';
 import { ethers } from 'ethers';
 import { logger } from '../utils/logger';
-import { CompilationResult, GasProfile } from '../types';
+import { FileSystem } from '../utils/file-system';
+import { CompilationResult, GasProfile, ProfileConfig } from '../types';
+
+export const PROFILE_CONFIG_FILE = path.join('.stylus-toolkit', 'profile.json');
+
+// Functions taking dynamic arrays are measured once per array length, since
+// decoding them costs differently in WASM and in the EVM as they grow
+const ARRAY_LENGTHS = [1, 10, 100];
 
 export class GasProfiler {
-  private provider: ethers.Provider;
+  private provider: ethers.JsonRpcProvider;
   private signer: ethers.Signer;
 
   constructor(rpcUrl: string, privateKey?: string) {
@@ -16,9 +24,45 @@ export class GasProfiler {
     }
   }
 
+  // Loads the per-project arguments to measure functions with, if present.
+  // Cases are keyed by the Rust function names, like the function map.
+  static async loadConfig(projectRoot: string): Promise<ProfileConfig> {
+    const configPath = path.join(projectRoot, PROFILE_CONFIG_FILE);
+
+    if (!(await FileSystem.fileExists(configPath))) {
+      return {};
+    }
+
+    try {
+      return await FileSystem.readJson(configPath);
+    } catch (error) {
+      logger.warn(`Could not read ${PROFILE_CONFIG_FILE}: ${(error as Error).message}`);
+      return {};
+    }
+  }
+
+  // The same configuration keyed by the Solidity names of renamed functions
+  static renameConfig(
+    profileConfig: ProfileConfig,
+    mapping: Record<string, string>
+  ): ProfileConfig {
+    const rename = (name: string) => mapping[name] || name;
+    const cases = Object.entries(profileConfig.cases || {}).map(
+      ([name, functionCases]) => [rename(name), functionCases]
+    );
+    const differences = Object.entries(profileConfig.differences || {}).map(
+      ([row, [minuend, subtrahend]]) => [row, [rename(minuend), rename(subtrahend)]]
+    );
+
+    return { cases: Object.fromEntries(cases), differences: Object.fromEntries(differences) };
+  }
+
+  // Profiles each function in the contract's ABI. With the address of a deployed
+  // instance the functions are measured there; otherwise their gas is estimated.
   async profileContract(
     compilation: CompilationResult,
-    _testCases?: Map<string, any[][]>
+    address?: string,
+    profileConfig: ProfileConfig = {}
   ): Promise<GasProfile> {
     logger.startSpinner(`Profiling ${compilation.language} contract...`);
 
@@ -39,17 +83,31 @@ export class GasProfiler {
         return this.estimateGasProfile(compilation);
       }
 
-      // Always use estimation mode (no actual deployment needed)
       const deploymentGas = this.estimateDeploymentGas(compilation);
 
-      logger.updateSpinner('Estimating function gas usage...');
+      let functionGas: Map<string, any>;
+      let skipped: string[] = [];
 
-      const functionGas = this.estimateFunctionGas(compilation);
+      if (address && compilation.abi) {
+        logger.updateSpinner(`Measuring function gas at ${address}...`);
+        ({ functionGas, skipped } = await this.measureFunctionGas(
+          address,
+          compilation.abi,
+          profileConfig
+        ));
+      } else {
+        logger.updateSpinner('Estimating function gas usage...');
+        functionGas = this.estimateFunctionGas(compilation);
+      }
 
       logger.succeedSpinner(
         `${compilation.language} profiling complete (Deployment: ${deploymentGas} gas)`
       );
 
+      if (skipped.length > 0) {
+        logger.warn(`Not measured: ${skipped.join(', ')}`);
+      }
+
       return {
         contractName: compilation.contractName,
         language: compilation.language,
@@ -70,32 +128,18 @@ export class GasProfiler {
     const bytecodeSize = compilation.bytecode.length / 2; // hex to bytes
 
     let estimatedDeploymentGas: number;
-    const functionGasMap = new Map<string, any>();
 
     if (compilation.language === 'rust') {
       // Stylus WASM contracts: Realistic deployment cost
       // Uses 16 gas per byte for compressed WASM (Arbitrum Stylus pricing)
       estimatedDeploymentGas = Math.floor(21000 + (bytecodeSize * 16));
-
-      // Realistic function execution based on Arbitrum Stylus benchmarks
-      // Source: Arbitrum docs, RedStone oracle analysis, WELLDONE Studio testing
-      // Oracle workloads: ~26% savings, Compute: ~40-50% savings
-      functionGasMap.set('read', { avgGas: 5000, calls: 100 });      // Light read operation
-      functionGasMap.set('write', { avgGas: 12000, calls: 100 });    // State write (SSTORE equiv)
-      functionGasMap.set('compute', { avgGas: 8000, calls: 100 });   // Computation workload
-      functionGasMap.set('oracle', { avgGas: 75000, calls: 100 });   // Oracle verification (3 signers)
     } else {
       // Solidity EVM contracts: Standard deployment and execution costs
       estimatedDeploymentGas = Math.floor(21000 + (bytecodeSize * 200));
-
-      // EVM execution costs (baseline from Arbitrum benchmarks)
-      // Source: Arbitrum Stylus documentation, RedStone oracle comparison
-      functionGasMap.set('read', { avgGas: 6000, calls: 100 });      // SLOAD operation
-      functionGasMap.set('write', { avgGas: 20000, calls: 100 });    // SSTORE (warm slot)
-      functionGasMap.set('compute', { avgGas: 15000, calls: 100 });  // Typical computation
-      functionGasMap.set('oracle', { avgGas: 103000, calls: 100 });  // Oracle with 3 signers
     }
 
+    const functionGasMap = this.estimateFunctionGas(compilation);
+
     logger.succeedSpinner(
       `${compilation.language} estimation complete (Est. Deployment: ${estimatedDeploymentGas} gas)`
     );
@@ -125,27 +169,196 @@ export class GasProfiler {
     }
   }
 
+  // Without a deployed instance, each function in the ABI is given the typical
+  // cost of a read (view and pure functions) or a write in its language.
+  // Contracts without an ABI get one row per workload category instead.
   private estimateFunctionGas(compilation: CompilationResult): Map<string, any> {
+    const categoryGas = this.categoryGas(compilation.language);
+
+    if (!compilation.abi) {
+      return categoryGas;
+    }
+
     const functionGasMap = new Map<string, any>();
 
-    if (compilation.language === 'rust') {
+    for (const fragment of this.functions(compilation.abi)) {
+      const category = fragment.constant ? 'read' : 'write';
+      functionGasMap.set(fragment.name, { ...categoryGas.get(category) });
+    }
+
+    return functionGasMap;
+  }
+
+  private categoryGas(language: CompilationResult['language']): Map<string, any> {
+    const functionGasMap = new Map<string, any>();
+
+    if (language === 'rust') {
       // Realistic function execution based on Arbitrum Stylus benchmarks
       // Source: Arbitrum docs, RedStone oracle analysis, WELLDONE Studio testing
       functionGasMap.set('read', { avgGas: 5000, calls: 100 });      // Light read operation
       functionGasMap.set('write', { avgGas: 12000, calls: 100 });    // State write (SSTORE equiv)
-      functionGasMap.set('compute', { avgGas: 8000, calls: 100 });   // Computation workload
-      functionGasMap.set('oracle', { avgGas: 75000, calls: 100 });   // Oracle verification (3 signers)
     } else {
       // EVM execution costs (baseline from Arbitrum benchmarks)
       functionGasMap.set('read', { avgGas: 6000, calls: 100 });      // SLOAD operation
       functionGasMap.set('write', { avgGas: 20000, calls: 100 });    // SSTORE (warm slot)
-      functionGasMap.set('compute', { avgGas: 15000, calls: 100 });  // Typical computation
-      functionGasMap.set('oracle', { avgGas: 103000, calls: 100 });  // Oracle with 3 signers
     }
 
     return functionGasMap;
   }
 
+  // Measures each function of a deployed contract with eth_estimateGas.
+  // Functions with cases in the profile configuration get one entry per case,
+  // keyed like iteratedKeccak[100]. The others are called with placeholder
+  // arguments (zeros, empty values and the signer's address), and those
+  // taking dynamic arrays get one entry per array length, keyed like
+  // safeBatchTransferFrom[10]. Cases marked as reverting, such as
+  // transfer[revert], are measured on top of the placeholder call, through
+  // a trace since eth_estimateGas fails on reverts. Calls that revert when
+  // they shouldn't, or the other way round, are returned in skipped.
+  private async measureFunctionGas(
+    address: string,
+    abi: any[],
+    profileConfig: ProfileConfig
+  ): Promise<{ functionGas: Map<string, any>; skipped: string[] }> {
+    const functionGas = new Map<string, any>();
+    const skipped: string[] = [];
+    const signerAddress = await this.signer.getAddress();
+
+    for (const fragment of this.functions(abi)) {
+      for (const { key, args, reverts } of this.callsFor(fragment, signerAddress, profileConfig)) {
+        if (reverts) {
+          try {
+            const trace = await this.traceCall(address, abi, fragment.format(), args);
+            if (trace.reverted) {
+              functionGas.set(key, { avgGas: trace.gasUsed, calls: 100 });
+            } else {
+              skipped.push(`${key} (did not revert)`);
+            }
+          } catch {
+            skipped.push(`${key} (debug_traceCall unavailable)`);
+          }
+          continue;
+        }
+
+        try {
+          const gas = await this.estimateGas(address, abi, fragment.format(), args);
+          functionGas.set(key, { avgGas: gas, calls: 100 });
+        } catch {
+          skipped.push(`${key} (reverted)`);
+        }
+      }
+    }
+
+    // Derived rows, such as the basic template's log: setNumber less
+    // setNumberUnlogged. They're left out of the TCO, which counts calls.
+    for (const [row, [minuend, subtrahend]] of Object.entries(profileConfig.differences || {})) {
+      const minuendGas = functionGas.get(minuend)?.avgGas;
+      const subtrahendGas = functionGas.get(subtrahend)?.avgGas;
+      if (minuendGas !== undefined && subtrahendGas !== undefined) {
+        functionGas.set(row, { avgGas: minuendGas - subtrahendGas, calls: 100, derived: true });
+      } else {
+        skipped.push(`${row} (needs ${minuend} and ${subtrahend})`);
+      }
+    }
+
+    return { functionGas, skipped };
+  }
+
+  private callsFor(
+    fragment: ethers.FunctionFragment,
+    signerAddress: string,
+    profileConfig: ProfileConfig
+  ): { key: string; args: any[]; reverts?: boolean }[] {
+    const cases = (profileConfig.cases?.[fragment.name] || []).map((profileCase) => ({
+      key: `${fragment.name}[${profileCase.label}]`,
+      args: profileCase.args,
+      reverts: profileCase.reverts,
+    }));
+    if (cases.some((call) => !call.reverts)) {
+      return cases;
+    }
+
+    const takesArrays = fragment.inputs.some((input) => this.hasDynamicArray(input));
+    const placeholderCalls = (takesArrays ? ARRAY_LENGTHS : [1]).map((length) => ({
+      key: takesArrays ? `${fragment.name}[${length}]` : fragment.name,
+      args: fragment.inputs.map((input) => this.placeholder(input, signerAddress, length)),
+    }));
+
+    return [...placeholderCalls, ...cases];
+  }
+
+  // Function fragments of the ABI, keyed by name like the function matcher.
+  // Only the first of several overloads is kept.
+  private functions(abi: any[]): ethers.FunctionFragment[] {
+    const fragments = new Map<string, ethers.FunctionFragment>();
+
+    new ethers.Interface(abi).forEachFunction((fragment) => {
+      if (!fragments.has(fragment.name)) {
+        fragments.set(fragment.name, fragment);
+      }
+    });
+
+    return [...fragments.values()];
+  }
+
+  private hasDynamicArray(param: ethers.ParamType): boolean {
+    if (param.isArray()) {
+      return param.arrayLength < 0 || this.hasDynamicArray(param.arrayChildren);
+    }
+    if (param.isTuple()) {
+      return param.components.some((component) => this.hasDynamicArray(component));
+    }
+    return false;
+  }
+
+  private placeholder(param: ethers.ParamType, signerAddress: string, arrayLength: number): any {
+    if (param.isArray()) {
+      const length = param.arrayLength >= 0 ? param.arrayLength : arrayLength;
+      return Array.from({ length }, () =>
+        this.placeholder(param.arrayChildren, signerAddress, arrayLength)
+      );
+    }
+    if (param.isTuple()) {
+      return param.components.map((component) =>
+        this.placeholder(component, signerAddress, arrayLength)
+      );
+    }
+
+    switch (param.baseType) {
+      case 'address':
+        return signerAddress;
+      case 'bool':
+        return false;
+      case 'string':
+        return '';
+      case 'bytes':
+        return '0x';
+      default:
+        // Fixed-size bytesN, or an integer
+        return param.baseType.startsWith('bytes')
+          ? ethers.zeroPadValue('0x', Number(param.baseType.slice(5)))
+          : 0;
+    }
+  }
+
+  // Gas used by a call that reverts, from a callTracer trace of it. The
+  // top-level frame's gasUsed includes the intrinsic cost, as estimates do.
+  async traceCall(
+    contractAddress: string,
+    abi: any[],
+    functionName: string,
+    args: any[]
+  ): Promise<{ gasUsed: number; reverted: boolean }> {
+    const data = new ethers.Interface(abi).encodeFunctionData(functionName, args);
+    const trace = await this.provider.send('debug_traceCall', [
+      { from: await this.signer.getAddress(), to: contractAddress, data },
+      'latest',
+      { tracer: 'callTracer' },
+    ]);
+
+    return { gasUsed: Number(trace.gasUsed), reverted: Boolean(trace.error) };
+  }
+
   async estimateGas(
     contractAddress: string,
     abi: any[],
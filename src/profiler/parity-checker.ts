@@ -0,0 +1,153 @@
+import { ethers } from 'ethers';
+import { ParityIssue, ParityReport } from '../types';
+
+export class ParityChecker {
+  // Compares the public interfaces of the Rust and Solidity implementations. Functions are
+  // paired by name, going through the function map for ones renamed on the Solidity side.
+  // Overloads are paired on identical argument types first.
+  check(
+    rustAbi: any[],
+    solidityAbi: any[],
+    mapping: Record<string, string> = {}
+  ): ParityReport {
+    const rust = new ethers.Interface(rustAbi);
+    const solidity = new ethers.Interface(solidityAbi);
+
+    const issues: ParityIssue[] = [...this.compareConstructors(rust.deploy, solidity.deploy)];
+
+    const rustFunctions = this.functions(rust);
+    const solidityFunctions = this.functions(solidity);
+    const pairs = new Map<ethers.FunctionFragment, ethers.FunctionFragment>();
+    const paired = new Set<ethers.FunctionFragment>();
+
+    const pair = (exactInputs: boolean) => {
+      for (const rustFunction of rustFunctions) {
+        if (pairs.has(rustFunction)) {
+          continue;
+        }
+
+        const solidityName = mapping[rustFunction.name] || rustFunction.name;
+        const solidityFunction = solidityFunctions.find(
+          (candidate) =>
+            !paired.has(candidate) &&
+            candidate.name === solidityName &&
+            (!exactInputs || this.inputTypes(candidate) === this.inputTypes(rustFunction))
+        );
+
+        if (solidityFunction) {
+          pairs.set(rustFunction, solidityFunction);
+          paired.add(solidityFunction);
+        }
+      }
+    };
+
+    pair(true);
+    pair(false);
+
+    for (const rustFunction of rustFunctions) {
+      const solidityFunction = pairs.get(rustFunction);
+      if (solidityFunction) {
+        issues.push(...this.compareFunctions(rustFunction, solidityFunction));
+      } else {
+        issues.push({ kind: 'missing-in-solidity', rustFunction: this.describe(rustFunction) });
+      }
+    }
+
+    for (const solidityFunction of solidityFunctions) {
+      if (!paired.has(solidityFunction)) {
+        issues.push({ kind: 'missing-in-rust', solidityFunction: this.describe(solidityFunction) });
+      }
+    }
+
+    return {
+      equivalent: issues.length === 0,
+      matched: pairs.size,
+      issues,
+    };
+  }
+
+  private compareFunctions(
+    rustFunction: ethers.FunctionFragment,
+    solidityFunction: ethers.FunctionFragment
+  ): ParityIssue[] {
+    const issues: ParityIssue[] = [];
+    const names = {
+      rustFunction: rustFunction.name,
+      solidityFunction: solidityFunction.name,
+    };
+
+    if (
+      this.inputTypes(rustFunction) !== this.inputTypes(solidityFunction) ||
+      this.outputTypes(rustFunction) !== this.outputTypes(solidityFunction)
+    ) {
+      issues.push({
+        kind: 'signature-mismatch',
+        ...names,
+        rust: this.describe(rustFunction),
+        solidity: this.describe(solidityFunction),
+      });
+    }
+
+    if (rustFunction.stateMutability !== solidityFunction.stateMutability) {
+      issues.push({
+        kind: 'mutability-mismatch',
+        ...names,
+        rust: `${rustFunction.name}: ${rustFunction.stateMutability}`,
+        solidity: `${solidityFunction.name}: ${solidityFunction.stateMutability}`,
+      });
+    }
+
+    return issues;
+  }
+
+  private compareConstructors(
+    rustConstructor: ethers.ConstructorFragment,
+    solidityConstructor: ethers.ConstructorFragment
+  ): ParityIssue[] {
+    const issues: ParityIssue[] = [];
+    const names = { rustFunction: 'constructor', solidityFunction: 'constructor' };
+    const rustInputs = rustConstructor.inputs.map((input) => input.format('sighash')).join(',');
+    const solidityInputs = solidityConstructor.inputs
+      .map((input) => input.format('sighash'))
+      .join(',');
+
+    if (rustInputs !== solidityInputs) {
+      issues.push({
+        kind: 'signature-mismatch',
+        ...names,
+        rust: `constructor(${rustInputs})`,
+        solidity: `constructor(${solidityInputs})`,
+      });
+    }
+
+    if (rustConstructor.payable !== solidityConstructor.payable) {
+      issues.push({
+        kind: 'mutability-mismatch',
+        ...names,
+        rust: `constructor: ${rustConstructor.payable ? 'payable' : 'nonpayable'}`,
+        solidity: `constructor: ${solidityConstructor.payable ? 'payable' : 'nonpayable'}`,
+      });
+    }
+
+    return issues;
+  }
+
+  private functions(iface: ethers.Interface): ethers.FunctionFragment[] {
+    const functions: ethers.FunctionFragment[] = [];
+    iface.forEachFunction((fragment) => functions.push(fragment));
+    return functions;
+  }
+
+  private inputTypes(fragment: ethers.FunctionFragment): string {
+    return fragment.inputs.map((input) => input.format('sighash')).join(',');
+  }
+
+  private outputTypes(fragment: ethers.FunctionFragment): string {
+    return fragment.outputs.map((output) => output.format('sighash')).join(',');
+  }
+
+  private describe(fragment: ethers.FunctionFragment): string {
+    const outputs = this.outputTypes(fragment);
+    return `${fragment.name}(${this.inputTypes(fragment)})${outputs ? ` returns (${outputs})` : ''}`;
+  }
+}
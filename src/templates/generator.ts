@@ -1,34 +1,102 @@
 import path from 'path';
 import { FileSystem } from '../utils/file-system';
 import { TEMPLATES } from './templates';
+import { FUNCTION_MAP_FILE } from '../profiler/function-matcher';
+import { PROFILE_CONFIG_FILE } from '../profiler/gas-profiler';
+
+// ERC-1967 proxy that upgradeable templates put in front of the Rust implementation.
+// Kept out of the top level of contracts-solidity so profiling still compiles the
+// Solidity implementation.
+export const PROXY_FILE = path.join('contracts-solidity', 'proxy', 'ERC1967Proxy.sol');
 
 export class TemplateGenerator {
   async generate(
     projectPath: string,
     templateName: string,
     hasRust: boolean,
-    hasSolidity: boolean
+    hasSolidity: boolean,
+    extensionNames: string[] = []
   ): Promise<void> {
-    const template = TEMPLATES[templateName] || TEMPLATES.basic;
+    const selected = TEMPLATES[templateName] || TEMPLATES.basic;
+
+    // Variants generate their base template with some of its extensions included
+    const template = selected.base ? this.variantOf(selected) : selected;
+    extensionNames = [...new Set([...(selected.includes || []), ...extensionNames])];
+
+    // Keep the template's declaration order so generated code is stable
+    const extensions = Object.keys(template.extensions || {})
+      .filter((name) => extensionNames.includes(name))
+      .map((name) => template.extensions[name]);
 
     if (hasRust) {
-      await this.generateRustFiles(projectPath, template);
+      await this.generateRustFiles(projectPath, template, extensions);
     }
 
     if (hasSolidity) {
-      await this.generateSolidityFiles(projectPath, template);
+      await this.generateSolidityFiles(projectPath, template, extensions);
+    }
+
+    // The proxy is needed to deploy the Rust implementation, so it is written
+    // even for Rust-only projects
+    if (template.proxy) {
+      const proxyPath = path.join(projectPath, PROXY_FILE);
+      await FileSystem.ensureDir(path.dirname(proxyPath));
+      await FileSystem.writeFile(proxyPath, template.proxy);
     }
 
-    await this.generateCommonFiles(projectPath, templateName, hasRust, hasSolidity);
+    await this.generateCommonFiles(
+      projectPath,
+      templateName,
+      template,
+      hasRust,
+      hasSolidity,
+      extensionNames
+    );
   }
 
-  private async generateRustFiles(projectPath: string, template: any): Promise<void> {
+  // A variant is its base template under a name of its own. The Solidity
+  // contract is renamed too, so it still matches its file.
+  private variantOf(variant: any): any {
+    const base = TEMPLATES[variant.base];
+    return {
+      ...base,
+      name: variant.name,
+      solidity: base.solidity.replace(
+        new RegExp(`^contract ${base.name}\\b`, 'm'),
+        `contract ${variant.name}`
+      ),
+    };
+  }
+
+  // Replaces every `// @extension <slot>` marker line with the code the selected
+  // extensions contribute to that slot. Markers no extension fills are dropped.
+  // Snippets may carry markers of their own, which are filled the same way.
+  private applyExtensions(source: string, snippets: Record<string, string>[]): string {
+    let previous: string;
+    do {
+      previous = source;
+      source = source.replace(/^[ \t]*\/\/ @extension ([\w-]+)\n/gm, (_marker, slot: string) =>
+        snippets.map((snippet) => snippet[slot] || '').join('')
+      );
+    } while (source !== previous);
+    return source;
+  }
+
+  private async generateRustFiles(
+    projectPath: string,
+    template: any,
+    extensions: any[]
+  ): Promise<void> {
     const rustContractPath = path.join(projectPath, 'contracts-rust', 'src', 'lib.rs');
-    await FileSystem.writeFile(rustContractPath, template.rust);
+    const rustSource = this.applyExtensions(
+      template.rust,
+      extensions.map((extension) => extension.rust)
+    );
+    await FileSystem.writeFile(rustContractPath, rustSource);
 
     // Generate main.rs for bin target (required for cargo-stylus constructor detection)
     const mainRsPath = path.join(projectPath, 'contracts-rust', 'src', 'main.rs');
-    await FileSystem.writeFile(mainRsPath, this.generateMainRs());
+    await FileSystem.writeFile(mainRsPath, this.generateMainRs(template.name));
 
     const cargoToml = path.join(projectPath, 'contracts-rust', 'Cargo.toml');
     await FileSystem.writeFile(cargoToml, this.generateCargoToml(template.name));
@@ -53,22 +121,49 @@ export class TemplateGenerator {
     await FileSystem.writeFile(path.join(projectPath, 'contracts-rust', 'Stylus.toml'), stylusToml);
   }
 
-  private async generateSolidityFiles(projectPath: string, template: any): Promise<void> {
+  private async generateSolidityFiles(
+    projectPath: string,
+    template: any,
+    extensions: any[]
+  ): Promise<void> {
     const solidityContractPath = path.join(
       projectPath,
       'contracts-solidity',
       `${template.name}.sol`
     );
-    await FileSystem.writeFile(solidityContractPath, template.solidity);
+    const soliditySource = this.applyExtensions(
+      template.solidity,
+      extensions.map((extension) => extension.solidity)
+    );
+    await FileSystem.writeFile(solidityContractPath, soliditySource);
+
+    // Foundry tests that assert the same results as the Rust unit tests
+    if (template.solidityTest) {
+      const testDir = path.join(projectPath, 'contracts-solidity', 'test');
+      await FileSystem.ensureDir(testDir);
+      await FileSystem.writeFile(
+        path.join(testDir, `${template.name}.t.sol`),
+        template.solidityTest
+      );
+    }
   }
 
   private async generateCommonFiles(
     projectPath: string,
     templateName: string,
+    template: any,
     hasRust: boolean,
-    hasSolidity: boolean
+    hasSolidity: boolean,
+    extensionNames: string[]
   ): Promise<void> {
-    const readme = this.generateReadme(templateName, hasRust, hasSolidity);
+    const readme = this.generateReadme(
+      templateName,
+      hasRust,
+      hasSolidity,
+      extensionNames,
+      hasSolidity && Boolean(template.solidityTest),
+      hasRust && hasSolidity && template.rust.includes('tx_origin()')
+    );
     await FileSystem.writeFile(path.join(projectPath, 'README.md'), readme);
 
     const gitignore = this.generateGitignore();
@@ -76,6 +171,19 @@ export class TemplateGenerator {
 
     const envExample = this.generateEnvExample();
     await FileSystem.writeFile(path.join(projectPath, '.env.example'), envExample);
+
+    // Pair renamed Rust and Solidity functions for gas comparisons
+    if (hasRust && hasSolidity && template.functionMap) {
+      await FileSystem.writeJson(path.join(projectPath, FUNCTION_MAP_FILE), template.functionMap);
+    }
+
+    // Arguments for functions that placeholder values can't measure
+    if (template.profileCases) {
+      await FileSystem.writeJson(
+        path.join(projectPath, PROFILE_CONFIG_FILE),
+        template.profileCases
+      );
+    }
   }
 
   private generateCargoToml(contractName: string): string {
@@ -145,14 +253,17 @@ rustflags = [
   private generateReadme(
     templateName: string,
     hasRust: boolean,
-    hasSolidity: boolean
+    hasSolidity: boolean,
+    extensionNames: string[],
+    hasSolidityTests: boolean,
+    ownerFromTxOrigin: boolean
   ): string {
     return `# Stylus Project - ${templateName}
 
 ## Overview
 
 This project was generated using the Stylus Toolkit CLI.
-
+${extensionNames.length > 0 ? `\nExtensions: ${extensionNames.join(', ')}\n` : ''}
 ${hasRust ? '### Rust (Stylus)\n\nLocation: `contracts-rust/src/lib.rs`\n' : ''}
 ${hasSolidity ? '### Solidity\n\nLocation: `contracts-solidity/`\n' : ''}
 
@@ -170,6 +281,8 @@ stylus-toolkit profile
 
 ${hasRust ? '#### Rust (Stylus)\n```bash\ncd contracts-rust\ncargo build --release --target wasm32-unknown-unknown\n```\n' : ''}
 ${hasSolidity ? '#### Solidity\n```bash\ncd contracts-solidity\nforge build\n```\n' : ''}
+${hasRust ? '### Run Tests\n\nThe Rust contract ships with unit tests that run against the Stylus SDK test VM (no node required):\n\n```bash\ncd contracts-rust\ncargo test\n```\n' : ''}
+${hasSolidityTests ? '### Run Solidity Tests\n\nThe Solidity contract ships with Foundry tests that assert the same results as the Rust tests:\n\n```bash\ncd contracts-solidity\nforge test\n```\n' : ''}${ownerFromTxOrigin ? `\n${this.generateOwnershipNote()}` : ''}
 
 ## Documentation
 
@@ -182,6 +295,14 @@ MIT
 `;
   }
 
+  // Why templates whose Rust constructor reads tx_origin can end up with a
+  // different owner than their Solidity twin
+  private generateOwnershipNote(): string {
+    return `## Ownership
+
+The Rust constructor runs through the StylusDeployer contract, so it makes \`tx_origin\`, the account that signed the deployment, the owner. The Solidity contract makes \`msg.sender\` the owner. Both are the deploying account when an account deploys directly. Deployed through a factory or a multisig, the Solidity owner is that contract while the Rust owner is the account that sent the transaction; where the contract has \`transferOwnership\`, call it after deploying to line them up.`;
+  }
+
   private generateGitignore(): string {
     return `# Rust
 target/
@@ -229,12 +350,22 @@ networks = {}
 `;
   }
 
-  private generateMainRs(): string {
-    return `// Binary target for cargo-stylus constructor detection
-// This file allows cargo-stylus to check for constructors without errors
+  private generateMainRs(contractName: string): string {
+    const crateName = contractName.toLowerCase().replace(/-/g, '_');
+
+    return `// Binary target for cargo-stylus constructor detection and ABI export
+// Run with the export-abi feature to print the contract interface, or pass
+// "constructor" to print the constructor signature.
+
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+
+#[cfg(not(any(test, feature = "export-abi")))]
+#[no_mangle]
+pub extern "C" fn main() {}
 
+#[cfg(feature = "export-abi")]
 fn main() {
-    println!("This contract has no constructor.");
+    ${crateName}::print_from_args();
 }
 `;
   }
@@ -1,6 +1,18 @@
 export const TEMPLATES: Record<string, any> = {
   basic: {
     name: 'Counter',
+    functionMap: {
+      number: 'getCount',
+      setNumber: 'setCount',
+      setNumberUnlogged: 'setCountUnlogged',
+    },
+    // Logging gets its own row: setNumber's gas less that of the same write
+    // without the NumberSet event
+    profileCases: {
+      differences: {
+        log: ['setNumber', 'setNumberUnlogged'],
+      },
+    },
     rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
 #![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
 
@@ -8,28 +20,394 @@ export const TEMPLATES: Record<string, any> = {
 extern crate alloc;
 
 use alloc::vec::Vec;
-use stylus_sdk::{alloy_primitives::U256, prelude::*};
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    prelude::*,
+    stylus_core::log,
+};
 
 sol_storage! {
     #[entrypoint]
     pub struct Counter {
         uint256 number;
+        address owner;
     }
 }
 
+sol! {
+    #![sol(all_derives)]
+
+    event NumberSet(uint256 oldValue, uint256 newValue);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    error CounterOverflow(uint256 value);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+}
+
+#[derive(SolidityError, Debug)]
+pub enum CounterError {
+    CounterOverflow(CounterOverflow),
+    Unauthorized(Unauthorized),
+    InvalidOwner(InvalidOwner),
+}
+
 #[public]
 impl Counter {
+    /// Sets the initial number and makes the deploying account the owner.
+    #[constructor]
+    pub fn constructor(&mut self, initial_number: U256) {
+        self.number.set(initial_number);
+        let deployer = self.vm().tx_origin();
+        self.write_owner(deployer);
+    }
+
     pub fn number(&self) -> U256 {
         self.number.get()
     }
 
-    pub fn set_number(&mut self, new_number: U256) {
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    pub fn set_number(&mut self, new_number: U256) -> Result<(), CounterError> {
+        self.only_owner()?;
+        self.write_number(new_number);
+        Ok(())
+    }
+
+    /// set_number without the NumberSet event. Profiling the two measures
+    /// what the log costs.
+    pub fn set_number_unlogged(&mut self, new_number: U256) -> Result<(), CounterError> {
+        self.only_owner()?;
         self.number.set(new_number);
+        Ok(())
     }
 
-    pub fn increment(&mut self) {
+    pub fn increment(&mut self) -> Result<(), CounterError> {
+        self.add_number(U256::from(1))
+    }
+
+    pub fn add_number(&mut self, new_number: U256) -> Result<(), CounterError> {
+        let number = self.number.get();
+        let next = number
+            .checked_add(new_number)
+            .ok_or(CounterError::CounterOverflow(CounterOverflow { value: number }))?;
+        self.write_number(next);
+        Ok(())
+    }
+
+    pub fn mul_number(&mut self, new_number: U256) -> Result<(), CounterError> {
         let number = self.number.get();
-        self.set_number(number + U256::from(1));
+        let next = number
+            .checked_mul(new_number)
+            .ok_or(CounterError::CounterOverflow(CounterOverflow { value: number }))?;
+        self.write_number(next);
+        Ok(())
+    }
+
+    #[payable]
+    pub fn add_from_msg_value(&mut self) -> Result<(), CounterError> {
+        let value = self.vm().msg_value();
+        self.add_number(value)
+    }
+
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), CounterError> {
+        self.only_owner()?;
+        if new_owner == Address::ZERO {
+            return Err(CounterError::InvalidOwner(InvalidOwner { owner: new_owner }));
+        }
+        self.write_owner(new_owner);
+        Ok(())
+    }
+
+    pub fn renounce_ownership(&mut self) -> Result<(), CounterError> {
+        self.only_owner()?;
+        self.write_owner(Address::ZERO);
+        Ok(())
+    }
+}
+
+impl Counter {
+    fn only_owner(&self) -> Result<(), CounterError> {
+        let sender = self.vm().msg_sender();
+        if sender != self.owner.get() {
+            return Err(CounterError::Unauthorized(Unauthorized { account: sender }));
+        }
+        Ok(())
+    }
+
+    fn write_number(&mut self, new_number: U256) {
+        let old_number = self.number.get();
+        self.number.set(new_number);
+        log(
+            self.vm(),
+            NumberSet {
+                oldValue: old_number,
+                newValue: new_number,
+            },
+        );
+    }
+
+    fn write_owner(&mut self, new_owner: Address) {
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+        log(
+            self.vm(),
+            OwnershipTransferred {
+                previousOwner: previous_owner,
+                newOwner: new_owner,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use stylus_sdk::testing::*;
+
+    const OWNER: Address = Address::repeat_byte(0x11);
+    const ALICE: Address = Address::repeat_byte(0xa1);
+
+    /// Deploys a counter owned by OWNER, with OWNER as the caller.
+    fn deploy(vm: &TestVM, initial_number: U256) -> Counter {
+        let mut contract = Counter::from(vm);
+        contract.constructor(initial_number);
+        vm.set_sender(contract.owner());
+        contract.transfer_ownership(OWNER).unwrap();
+        vm.set_sender(OWNER);
+        contract
+    }
+
+    #[test]
+    fn test_number_starts_at_zero() {
+        let vm = TestVM::default();
+        let contract = Counter::from(&vm);
+
+        assert_eq!(U256::ZERO, contract.number());
+    }
+
+    #[test]
+    fn test_constructor_sets_initial_number() {
+        let vm = TestVM::default();
+        let mut contract = Counter::from(&vm);
+
+        contract.constructor(U256::from(10));
+        assert_eq!(U256::from(10), contract.number());
+    }
+
+    #[test]
+    fn test_set_number() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+
+        contract.set_number(U256::from(42)).unwrap();
+        assert_eq!(U256::from(42), contract.number());
+
+        contract.set_number(U256::ZERO).unwrap();
+        assert_eq!(U256::ZERO, contract.number());
+    }
+
+    #[test]
+    fn test_increment() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+
+        contract.increment().unwrap();
+        assert_eq!(U256::from(1), contract.number());
+
+        contract.set_number(U256::from(99)).unwrap();
+        contract.increment().unwrap();
+        assert_eq!(U256::from(100), contract.number());
+    }
+
+    #[test]
+    fn test_add_number() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1));
+
+        contract.add_number(U256::from(3)).unwrap();
+        assert_eq!(U256::from(4), contract.number());
+
+        let err = contract.add_number(U256::MAX).unwrap_err();
+        assert!(matches!(err, CounterError::CounterOverflow(_)));
+        assert_eq!(U256::from(4), contract.number());
+    }
+
+    #[test]
+    fn test_mul_number() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(4));
+
+        contract.mul_number(U256::from(2)).unwrap();
+        assert_eq!(U256::from(8), contract.number());
+
+        let err = contract.mul_number(U256::MAX).unwrap_err();
+        assert!(matches!(err, CounterError::CounterOverflow(_)));
+        assert_eq!(U256::from(8), contract.number());
+
+        contract.mul_number(U256::ZERO).unwrap();
+        assert_eq!(U256::ZERO, contract.number());
+    }
+
+    #[test]
+    fn test_add_from_msg_value() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(100));
+
+        vm.set_value(U256::from(2));
+        contract.add_from_msg_value().unwrap();
+        assert_eq!(U256::from(102), contract.number());
+
+        // any caller may pay into the counter
+        vm.set_sender(ALICE);
+        vm.set_value(U256::from(8));
+        contract.add_from_msg_value().unwrap();
+        assert_eq!(U256::from(110), contract.number());
+    }
+
+    #[test]
+    fn test_set_number_emits_number_set() {
+        use alloy_sol_types::SolEvent;
+
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+        let logs_before = vm.get_emitted_logs().len();
+
+        contract.set_number(U256::from(7)).unwrap();
+        contract.increment().unwrap();
+
+        let logs = vm.get_emitted_logs();
+        assert_eq!(logs_before + 2, logs.len());
+
+        let expected = [
+            NumberSet { oldValue: U256::ZERO, newValue: U256::from(7) },
+            NumberSet { oldValue: U256::from(7), newValue: U256::from(8) },
+        ];
+        for ((topics, data), event) in logs[logs_before..].iter().zip(expected.iter()) {
+            assert_eq!(NumberSet::SIGNATURE_HASH, topics[0]);
+            assert_eq!(event.encode_data(), *data);
+        }
+    }
+
+    #[test]
+    fn test_set_number_unlogged() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+        let logs_before = vm.get_emitted_logs().len();
+
+        contract.set_number_unlogged(U256::from(7)).unwrap();
+        assert_eq!(U256::from(7), contract.number());
+        assert_eq!(logs_before, vm.get_emitted_logs().len());
+
+        vm.set_sender(ALICE);
+        let err = contract.set_number_unlogged(U256::from(1)).unwrap_err();
+        assert!(matches!(err, CounterError::Unauthorized(_)));
+        assert_eq!(U256::from(7), contract.number());
+    }
+
+    #[test]
+    fn test_increment_at_max_reverts() {
+        use alloy_sol_types::SolError;
+
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::MAX);
+
+        let logs_before = vm.get_emitted_logs().len();
+        let err = contract.increment().unwrap_err();
+        assert_eq!(U256::MAX, contract.number());
+        assert_eq!(logs_before, vm.get_emitted_logs().len());
+
+        let revert_data: Vec<u8> = err.into();
+        assert_eq!(
+            CounterOverflow { value: U256::MAX }.abi_encode(),
+            revert_data
+        );
+    }
+
+    #[test]
+    fn test_constructor_emits_ownership_transferred() {
+        use alloy_sol_types::SolEvent;
+
+        let vm = TestVM::default();
+        let mut contract = Counter::from(&vm);
+
+        contract.constructor(U256::ZERO);
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(OwnershipTransferred::SIGNATURE_HASH, topics[0]);
+        assert_eq!(Address::ZERO.into_word(), topics[1]);
+        assert_eq!(contract.owner().into_word(), topics[2]);
+    }
+
+    #[test]
+    fn test_set_number_rejects_non_owner() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(5));
+
+        vm.set_sender(ALICE);
+        let err = contract.set_number(U256::from(42)).unwrap_err();
+        assert!(matches!(
+            err,
+            CounterError::Unauthorized(Unauthorized { account }) if account == ALICE
+        ));
+        assert_eq!(U256::from(5), contract.number());
+
+        // increment stays open to everyone
+        contract.increment().unwrap();
+        assert_eq!(U256::from(6), contract.number());
+    }
+
+    #[test]
+    fn test_transfer_ownership() {
+        use alloy_sol_types::SolEvent;
+
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+
+        contract.transfer_ownership(ALICE).unwrap();
+        assert_eq!(ALICE, contract.owner());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(OwnershipTransferred::SIGNATURE_HASH, topics[0]);
+        assert_eq!(OWNER.into_word(), topics[1]);
+        assert_eq!(ALICE.into_word(), topics[2]);
+
+        // the previous owner is locked out
+        assert!(contract.set_number(U256::from(1)).is_err());
+        assert!(contract.transfer_ownership(OWNER).is_err());
+
+        vm.set_sender(ALICE);
+        contract.set_number(U256::from(1)).unwrap();
+        assert_eq!(U256::from(1), contract.number());
+    }
+
+    #[test]
+    fn test_transfer_ownership_rejects_zero_address() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+
+        let err = contract.transfer_ownership(Address::ZERO).unwrap_err();
+        assert!(matches!(err, CounterError::InvalidOwner(_)));
+        assert_eq!(OWNER, contract.owner());
+    }
+
+    #[test]
+    fn test_renounce_ownership() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+
+        vm.set_sender(ALICE);
+        assert!(contract.renounce_ownership().is_err());
+
+        vm.set_sender(OWNER);
+        contract.renounce_ownership().unwrap();
+        assert_eq!(Address::ZERO, contract.owner());
+        assert!(contract.set_number(U256::from(1)).is_err());
     }
 }
 `,
@@ -38,249 +416,9658 @@ pragma solidity ^0.8.20;
 
 contract Counter {
     uint256 private count;
+    address private _owner;
+
+    event NumberSet(uint256 oldValue, uint256 newValue);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    error CounterOverflow(uint256 value);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+
+    modifier onlyOwner() {
+        if (msg.sender != _owner) {
+            revert Unauthorized(msg.sender);
+        }
+        _;
+    }
+
+    constructor(uint256 initialNumber) {
+        count = initialNumber;
+        _transferOwnership(msg.sender);
+    }
 
     function increment() public {
-        count += 1;
+        addNumber(1);
+    }
+
+    function addNumber(uint256 newNumber) public {
+        if (newNumber > type(uint256).max - count) {
+            revert CounterOverflow(count);
+        }
+        _setCount(count + newNumber);
+    }
+
+    function mulNumber(uint256 newNumber) public {
+        if (newNumber != 0 && count > type(uint256).max / newNumber) {
+            revert CounterOverflow(count);
+        }
+        _setCount(count * newNumber);
+    }
+
+    function addFromMsgValue() public payable {
+        addNumber(msg.value);
     }
 
     function getCount() public view returns (uint256) {
         return count;
     }
 
-    function setCount(uint256 _count) public {
+    function setCount(uint256 _count) public onlyOwner {
+        _setCount(_count);
+    }
+
+    // setCount without the NumberSet event. Profiling the two measures what
+    // the log costs.
+    function setCountUnlogged(uint256 _count) public onlyOwner {
+        count = _count;
+    }
+
+    function owner() public view returns (address) {
+        return _owner;
+    }
+
+    function transferOwnership(address newOwner) public onlyOwner {
+        if (newOwner == address(0)) {
+            revert InvalidOwner(newOwner);
+        }
+        _transferOwnership(newOwner);
+    }
+
+    function renounceOwnership() public onlyOwner {
+        _transferOwnership(address(0));
+    }
+
+    function _setCount(uint256 _count) internal {
+        emit NumberSet(count, _count);
         count = _count;
     }
+
+    function _transferOwnership(address newOwner) internal {
+        address previousOwner = _owner;
+        _owner = newOwner;
+        emit OwnershipTransferred(previousOwner, newOwner);
+    }
 }
 `,
   },
   erc20: {
     name: 'ERC20Token',
-    rust: `#![cfg_attr(not(feature = "export-abi"), no_main)]
+    // The InsufficientBalance revert, measured next to a successful transfer
+    profileCases: {
+      cases: {
+        transfer: [
+          {
+            label: 'revert',
+            // More than any balance, 2^256 - 1
+            args: [
+              '0x000000000000000000000000000000000000dEaD',
+              '115792089237316195423570985008687907853269984665640564039457584007913129639935',
+            ],
+            reverts: true,
+          },
+        ],
+      },
+    },
+    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
 extern crate alloc;
 
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::sol;
 use stylus_sdk::{
     alloy_primitives::{Address, U256},
     prelude::*,
-    storage::{StorageMap, StorageU256},
+    storage::{StorageAddress, StorageMap, StorageString, StorageU256},
+    stylus_core::log,
 };
+// @extension imports
 
 #[storage]
 #[entrypoint]
 pub struct ERC20 {
     balances: StorageMap<Address, StorageU256>,
+    allowances: StorageMap<Address, StorageMap<Address, StorageU256>>,
     total_supply: StorageU256,
+    name: StorageString,
+    symbol: StorageString,
+    owner: StorageAddress,
+    // @extension storage
+}
+
+sol! {
+    #![sol(all_derives)]
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    error InsufficientBalance(address sender, uint256 balance, uint256 needed);
+    error InsufficientAllowance(address spender, uint256 allowance, uint256 needed);
+    error InvalidReceiver(address receiver);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+    error SupplyOverflow(uint256 totalSupply, uint256 amount);
+    // @extension sol
+}
+
+#[derive(SolidityError, Debug)]
+pub enum Erc20Error {
+    InsufficientBalance(InsufficientBalance),
+    InsufficientAllowance(InsufficientAllowance),
+    InvalidReceiver(InvalidReceiver),
+    Unauthorized(Unauthorized),
+    InvalidOwner(InvalidOwner),
+    SupplyOverflow(SupplyOverflow),
+    // @extension errors
 }
 
 #[public]
 impl ERC20 {
-    pub fn balance_of(&self, account: Address) -> U256 {
-        self.balances.get(account)
+    /// Sets the token metadata, mints the initial supply to the deploying
+    /// account and makes it the owner.
+    #[constructor]
+    pub fn constructor(
+        &mut self,
+        name: String,
+        symbol: String,
+        // @extension constructor-params
+        initial_supply: U256,
+    ) -> Result<(), Erc20Error> {
+        self.name.set_str(&name);
+        self.symbol.set_str(&symbol);
+        // @extension constructor-body
+
+        let deployer = self.vm().tx_origin();
+        self.write_owner(deployer);
+        self.mint_tokens(deployer, initial_supply)
+    }
+
+    pub fn name(&self) -> String {
+        self.name.get_string()
+    }
+
+    pub fn symbol(&self) -> String {
+        self.symbol.get_string()
+    }
+
+    pub fn decimals(&self) -> u8 {
+        18
     }
 
     pub fn total_supply(&self) -> U256 {
         self.total_supply.get()
     }
 
-    pub fn transfer(&mut self, to: Address, amount: U256) -> bool {
-        let sender = self.vm().msg_sender();
-        let sender_balance = self.balances.get(sender);
+    pub fn balance_of(&self, account: Address) -> U256 {
+        self.balances.get(account)
+    }
 
-        if sender_balance < amount {
-            return false;
-        }
+    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        self.allowances.getter(owner).get(spender)
+    }
 
-        self.balances.insert(sender, sender_balance - amount);
-        let to_balance = self.balances.get(to);
-        self.balances.insert(to, to_balance + amount);
+    pub fn transfer(&mut self, to: Address, amount: U256) -> Result<bool, Erc20Error> {
+        let sender = self.vm().msg_sender();
+        self.move_tokens(sender, to, amount)?;
+        Ok(true)
+    }
 
+    pub fn approve(&mut self, spender: Address, amount: U256) -> bool {
+        let owner = self.vm().msg_sender();
+        self.allowances.setter(owner).insert(spender, amount);
+        log(
+            self.vm(),
+            Approval {
+                owner,
+                spender,
+                value: amount,
+            },
+        );
         true
     }
 
-    pub fn mint(&mut self, to: Address, amount: U256) {
-        let balance = self.balances.get(to);
-        self.balances.insert(to, balance + amount);
-        let supply = self.total_supply.get();
-        self.total_supply.set(supply + amount);
-    }
-}
-`,
-    solidity: `// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.20;
+    pub fn transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<bool, Erc20Error> {
+        let spender = self.vm().msg_sender();
+        let allowance = self.allowances.getter(from).get(spender);
 
-contract ERC20Token {
-    mapping(address => uint256) private balances;
-    mapping(address => mapping(address => uint256)) private allowances;
-    uint256 private totalSupply;
+        if allowance < amount {
+            return Err(Erc20Error::InsufficientAllowance(InsufficientAllowance {
+                spender,
+                allowance,
+                needed: amount,
+            }));
+        }
 
-    string public name = "MyToken";
-    string public symbol = "MTK";
-    uint8 public decimals = 18;
+        self.move_tokens(from, to, amount)?;
 
-    function balanceOf(address account) public view returns (uint256) {
-        return balances[account];
+        // An allowance of U256::MAX is treated as unlimited and never decreases
+        if allowance != U256::MAX {
+            self.allowances.setter(from).insert(spender, allowance - amount);
+        }
+        Ok(true)
     }
 
-    function transfer(address to, uint256 amount) public returns (bool) {
-        require(balances[msg.sender] >= amount, "Insufficient balance");
-
-        balances[msg.sender] -= amount;
-        balances[to] += amount;
-
-        return true;
+    pub fn mint(&mut self, to: Address, amount: U256) -> Result<(), Erc20Error> {
+        self.only_owner()?;
+        self.mint_tokens(to, amount)
     }
 
-    function approve(address spender, uint256 amount) public returns (bool) {
-        allowances[msg.sender][spender] = amount;
-        return true;
+    pub fn owner(&self) -> Address {
+        self.owner.get()
     }
 
-    function allowance(address owner, address spender) public view returns (uint256) {
-        return allowances[owner][spender];
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), Erc20Error> {
+        self.only_owner()?;
+        if new_owner == Address::ZERO {
+            return Err(Erc20Error::InvalidOwner(InvalidOwner { owner: new_owner }));
+        }
+        self.write_owner(new_owner);
+        Ok(())
     }
 
-    function getTotalSupply() public view returns (uint256) {
-        return totalSupply;
+    pub fn renounce_ownership(&mut self) -> Result<(), Erc20Error> {
+        self.only_owner()?;
+        self.write_owner(Address::ZERO);
+        Ok(())
     }
+    // @extension public
 }
-`,
-  },
-  erc721: {
-    name: 'NFTCounter',
-    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
-#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
 
-#[macro_use]
-extern crate alloc;
+impl ERC20 {
+    fn only_owner(&self) -> Result<(), Erc20Error> {
+        let sender = self.vm().msg_sender();
+        if sender != self.owner.get() {
+            return Err(Erc20Error::Unauthorized(Unauthorized { account: sender }));
+        }
+        Ok(())
+    }
 
-use alloc::vec::Vec;
-use stylus_sdk::{alloy_primitives::U256, prelude::*};
+    fn move_tokens(&mut self, from: Address, to: Address, amount: U256) -> Result<(), Erc20Error> {
+        if to == Address::ZERO {
+            return Err(Erc20Error::InvalidReceiver(InvalidReceiver { receiver: to }));
+        }
 
-sol_storage! {
-    #[entrypoint]
-    pub struct NFTCounter {
-        uint256 total_minted;
+        let from_balance = self.balances.get(from);
+        if from_balance < amount {
+            return Err(Erc20Error::InsufficientBalance(InsufficientBalance {
+                sender: from,
+                balance: from_balance,
+                needed: amount,
+            }));
+        }
+
+        self.balances.insert(from, from_balance - amount);
+        // Cannot overflow: the sum of all balances is bounded by the total supply
+        let to_balance = self.balances.get(to);
+        self.balances.insert(to, to_balance + amount);
+
+        log(
+            self.vm(),
+            Transfer {
+                from,
+                to,
+                value: amount,
+            },
+        );
+        Ok(())
     }
-}
 
-#[public]
-impl NFTCounter {
-    pub fn total_minted(&self) -> U256 {
-        self.total_minted.get()
+    fn mint_tokens(&mut self, to: Address, amount: U256) -> Result<(), Erc20Error> {
+        if to == Address::ZERO {
+            return Err(Erc20Error::InvalidReceiver(InvalidReceiver { receiver: to }));
+        }
+
+        let supply = self.total_supply.get();
+        let new_supply = supply
+            .checked_add(amount)
+            .ok_or(Erc20Error::SupplyOverflow(SupplyOverflow {
+                totalSupply: supply,
+                amount,
+            }))?;
+        // @extension mint
+
+        self.total_supply.set(new_supply);
+        let balance = self.balances.get(to);
+        self.balances.insert(to, balance + amount);
+
+        log(
+            self.vm(),
+            Transfer {
+                from: Address::ZERO,
+                to,
+                value: amount,
+            },
+        );
+        Ok(())
     }
 
-    pub fn mint(&mut self) -> U256 {
-        let token_id = self.total_minted.get();
-        self.total_minted.set(token_id + U256::from(1));
-        token_id
+    fn write_owner(&mut self, new_owner: Address) {
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+        log(
+            self.vm(),
+            OwnershipTransferred {
+                previousOwner: previous_owner,
+                newOwner: new_owner,
+            },
+        );
     }
+    // @extension private
 }
-`,
-    solidity: `// SPDX-License-Identifier: MIT
-pragma solidity ^0.8.20;
 
-contract ERC721NFT {
-    mapping(uint256 => address) private owners;
-    mapping(address => uint256) private balances;
-    mapping(uint256 => address) private tokenApprovals;
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_sol_types::SolEvent;
+    use stylus_sdk::testing::*;
 
-    uint256 private nextTokenId;
+    const OWNER: Address = Address::repeat_byte(0x11);
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
 
-    function ownerOf(uint256 tokenId) public view returns (address) {
-        return owners[tokenId];
+    /// Initializes the token the way the constructor does, with OWNER as the
+    /// deployer and the caller.
+    fn deploy(vm: &TestVM, initial_supply: U256) -> ERC20 {
+        let mut contract = ERC20::from(vm);
+        contract.name.set_str("MyToken");
+        contract.symbol.set_str("MTK");
+        contract.write_owner(OWNER);
+        // @extension test-deploy
+        contract.mint_tokens(OWNER, initial_supply).unwrap();
+        vm.set_sender(OWNER);
+        contract
     }
 
-    function balanceOf(address owner) public view returns (uint256) {
-        return balances[owner];
+    #[test]
+    fn test_metadata() {
+        let vm = TestVM::default();
+        let contract = deploy(&vm, U256::from(1000));
+
+        assert_eq!("MyToken", contract.name());
+        assert_eq!("MTK", contract.symbol());
+        assert_eq!(18, contract.decimals());
+        assert_eq!(U256::from(1000), contract.total_supply());
+        assert_eq!(U256::from(1000), contract.balance_of(OWNER));
     }
 
-    function mint(address to) public returns (uint256) {
-        uint256 tokenId = nextTokenId;
+    #[test]
+    fn test_transfer() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
 
-        owners[tokenId] = to;
-        balances[to] += 1;
-        nextTokenId += 1;
+        assert!(contract.transfer(ALICE, U256::from(250)).unwrap());
+        assert_eq!(U256::from(750), contract.balance_of(OWNER));
+        assert_eq!(U256::from(250), contract.balance_of(ALICE));
 
-        return tokenId;
+        let logs = vm.get_emitted_logs();
+        let (topics, data) = logs.last().unwrap();
+        assert_eq!(Transfer::SIGNATURE_HASH, topics[0]);
+        assert_eq!(OWNER.into_word(), topics[1]);
+        assert_eq!(ALICE.into_word(), topics[2]);
+        assert_eq!(U256::from(250).to_be_bytes_vec(), *data);
     }
 
-    function transferFrom(address from, address to, uint256 tokenId) public returns (bool) {
-        require(owners[tokenId] == from, "Not owner");
+    #[test]
+    fn test_transfer_insufficient_balance_reverts() {
+        use alloy_sol_types::SolError;
 
-        owners[tokenId] = to;
-        balances[from] -= 1;
-        balances[to] += 1;
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
 
-        return true;
+        let err = contract.transfer(ALICE, U256::from(1001)).unwrap_err();
+        assert_eq!(U256::from(1000), contract.balance_of(OWNER));
+        assert_eq!(U256::ZERO, contract.balance_of(ALICE));
+
+        let revert_data: Vec<u8> = err.into();
+        let expected = InsufficientBalance {
+            sender: OWNER,
+            balance: U256::from(1000),
+            needed: U256::from(1001),
+        };
+        assert_eq!(expected.abi_encode(), revert_data);
     }
-}
-`,
-  },
-  defi: {
-    name: 'LiquidityPool',
-    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
-#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
 
-#[macro_use]
-extern crate alloc;
+    #[test]
+    fn test_transfer_to_zero_address_reverts() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
 
-use alloc::vec::Vec;
-use stylus_sdk::{alloy_primitives::U256, prelude::*};
+        let err = contract.transfer(Address::ZERO, U256::from(1)).unwrap_err();
+        assert!(matches!(err, Erc20Error::InvalidReceiver(_)));
 
-sol_storage! {
-    #[entrypoint]
-    pub struct LiquidityPool {
-        uint256 total_liquidity;
+        let err = contract.mint(Address::ZERO, U256::from(1)).unwrap_err();
+        assert!(matches!(err, Erc20Error::InvalidReceiver(_)));
+        assert_eq!(U256::from(1000), contract.total_supply());
+    }
+
+    #[test]
+    fn test_approve_and_transfer_from() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
+
+        assert!(contract.approve(ALICE, U256::from(300)));
+        assert_eq!(U256::from(300), contract.allowance(OWNER, ALICE));
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Approval::SIGNATURE_HASH, topics[0]);
+
+        vm.set_sender(ALICE);
+        assert!(contract.transfer_from(OWNER, BOB, U256::from(100)).unwrap());
+        assert_eq!(U256::from(200), contract.allowance(OWNER, ALICE));
+        assert_eq!(U256::from(900), contract.balance_of(OWNER));
+        assert_eq!(U256::from(100), contract.balance_of(BOB));
+
+        let err = contract.transfer_from(OWNER, BOB, U256::from(201)).unwrap_err();
+        assert!(matches!(
+            err,
+            Erc20Error::InsufficientAllowance(InsufficientAllowance { spender, .. }) if spender == ALICE
+        ));
+        assert_eq!(U256::from(200), contract.allowance(OWNER, ALICE));
+        assert_eq!(U256::from(100), contract.balance_of(BOB));
+    }
+
+    #[test]
+    fn test_transfer_from_insufficient_balance_keeps_allowance() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
+
+        contract.approve(ALICE, U256::from(5000));
+
+        vm.set_sender(ALICE);
+        let err = contract.transfer_from(OWNER, BOB, U256::from(2000)).unwrap_err();
+        assert!(matches!(err, Erc20Error::InsufficientBalance(_)));
+        assert_eq!(U256::from(5000), contract.allowance(OWNER, ALICE));
+    }
+
+    #[test]
+    fn test_unlimited_allowance_is_not_spent() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
+
+        contract.approve(ALICE, U256::MAX);
+
+        vm.set_sender(ALICE);
+        assert!(contract.transfer_from(OWNER, BOB, U256::from(400)).unwrap());
+        assert_eq!(U256::MAX, contract.allowance(OWNER, ALICE));
+    }
+
+    #[test]
+    fn test_mint() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
+
+        contract.mint(ALICE, U256::from(50)).unwrap();
+        assert_eq!(U256::from(1050), contract.total_supply());
+        assert_eq!(U256::from(50), contract.balance_of(ALICE));
+
+        let err = contract.mint(ALICE, U256::MAX).unwrap_err();
+        assert!(matches!(err, Erc20Error::SupplyOverflow(_)));
+        assert_eq!(U256::from(1050), contract.total_supply());
+    }
+
+    #[test]
+    fn test_mint_rejects_non_owner() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
+
+        vm.set_sender(ALICE);
+        let err = contract.mint(ALICE, U256::from(50)).unwrap_err();
+        assert!(matches!(
+            err,
+            Erc20Error::Unauthorized(Unauthorized { account }) if account == ALICE
+        ));
+        assert_eq!(U256::from(1000), contract.total_supply());
+    }
+    // @extension tests
+}
+`,
+    solidity: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+contract ERC20Token {
+    mapping(address => uint256) private balances;
+    mapping(address => mapping(address => uint256)) private allowances;
+    uint256 private _totalSupply;
+
+    string private _name;
+    string private _symbol;
+
+    address private _owner;
+    // @extension state
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    error InsufficientBalance(address sender, uint256 balance, uint256 needed);
+    error InsufficientAllowance(address spender, uint256 allowance, uint256 needed);
+    error InvalidReceiver(address receiver);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+    error SupplyOverflow(uint256 totalSupply, uint256 amount);
+    // @extension errors
+
+    modifier onlyOwner() {
+        if (msg.sender != _owner) {
+            revert Unauthorized(msg.sender);
+        }
+        _;
+    }
+
+    constructor(
+        string memory name_,
+        string memory symbol_,
+        // @extension constructor-params
+        uint256 initialSupply
+    ) {
+        _name = name_;
+        _symbol = symbol_;
+        // @extension constructor-body
+        _transferOwnership(msg.sender);
+        _mint(msg.sender, initialSupply);
+    }
+
+    function name() public view returns (string memory) {
+        return _name;
+    }
+
+    function symbol() public view returns (string memory) {
+        return _symbol;
+    }
+
+    function decimals() public view virtual returns (uint8) {
+        return 18;
+    }
+
+    function totalSupply() public view returns (uint256) {
+        return _totalSupply;
+    }
+
+    function balanceOf(address account) public view returns (uint256) {
+        return balances[account];
+    }
+
+    function allowance(address owner_, address spender) public view returns (uint256) {
+        return allowances[owner_][spender];
+    }
+
+    function transfer(address to, uint256 amount) public returns (bool) {
+        _transfer(msg.sender, to, amount);
+        return true;
+    }
+
+    function approve(address spender, uint256 amount) public returns (bool) {
+        allowances[msg.sender][spender] = amount;
+        emit Approval(msg.sender, spender, amount);
+        return true;
+    }
+
+    function transferFrom(address from, address to, uint256 amount) public returns (bool) {
+        uint256 currentAllowance = allowances[from][msg.sender];
+        if (currentAllowance < amount) {
+            revert InsufficientAllowance(msg.sender, currentAllowance, amount);
+        }
+
+        _transfer(from, to, amount);
+
+        // An allowance of type(uint256).max is treated as unlimited and never decreases
+        if (currentAllowance != type(uint256).max) {
+            allowances[from][msg.sender] = currentAllowance - amount;
+        }
+        return true;
+    }
+
+    function mint(address to, uint256 amount) public onlyOwner {
+        _mint(to, amount);
+    }
+
+    function owner() public view returns (address) {
+        return _owner;
+    }
+
+    function transferOwnership(address newOwner) public onlyOwner {
+        if (newOwner == address(0)) {
+            revert InvalidOwner(newOwner);
+        }
+        _transferOwnership(newOwner);
+    }
+
+    function renounceOwnership() public onlyOwner {
+        _transferOwnership(address(0));
+    }
+    // @extension public
+
+    function _transfer(address from, address to, uint256 amount) internal {
+        if (to == address(0)) {
+            revert InvalidReceiver(to);
+        }
+
+        uint256 fromBalance = balances[from];
+        if (fromBalance < amount) {
+            revert InsufficientBalance(from, fromBalance, amount);
+        }
+
+        unchecked {
+            balances[from] = fromBalance - amount;
+            // Cannot overflow: the sum of all balances is bounded by the total supply
+            balances[to] += amount;
+        }
+        emit Transfer(from, to, amount);
+    }
+
+    function _mint(address to, uint256 amount) internal {
+        if (to == address(0)) {
+            revert InvalidReceiver(to);
+        }
+        if (amount > type(uint256).max - _totalSupply) {
+            revert SupplyOverflow(_totalSupply, amount);
+        }
+        // @extension mint
+        unchecked {
+            _totalSupply += amount;
+            balances[to] += amount;
+        }
+        emit Transfer(address(0), to, amount);
+    }
+
+    function _transferOwnership(address newOwner) internal {
+        address previousOwner = _owner;
+        _owner = newOwner;
+        emit OwnershipTransferred(previousOwner, newOwner);
+    }
+    // @extension internal
+}
+`,
+    // Opt-in modules selected with `init --extensions`. Each one contributes code to the
+    // `// @extension <slot>` markers in the Rust and Solidity sources above.
+    extensions: {
+      permit: {
+        description: 'EIP-2612 permit (approvals via EIP-712 signatures)',
+        rust: {
+          imports: `use alloy_sol_types::SolValue;
+use stylus_sdk::{
+    alloy_primitives::{b256, B256},
+    call::Call,
+};
+
+/// keccak256("Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)")
+const PERMIT_TYPEHASH: B256 =
+    b256!("6e71edae12b1b97f4d1f60370fef10105fa2faae0126114a169c64845d6126c9");
+/// keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")
+const DOMAIN_TYPEHASH: B256 =
+    b256!("8b73c3c69bb8fe3d512ecc4cf759cc79239f7b179b0ffacaa9a75d522b39400f");
+/// keccak256("1"), the EIP-712 domain version
+const VERSION_HASH: B256 =
+    b256!("c89efdaa54c0f20c7adf612882df0950f5a951637e0307cdcb4c672f298b8bc6");
+/// Largest s value of a non-malleable signature (secp256k1n / 2)
+const MAX_S: B256 = b256!("7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0");
+/// The ecrecover precompile
+const ECRECOVER: Address = Address::with_last_byte(1);
+`,
+          storage: `    nonces: StorageMap<Address, StorageU256>,
+`,
+          sol: `    error ERC2612ExpiredSignature(uint256 deadline);
+    error ERC2612InvalidSigner(address signer, address owner);
+`,
+          errors: `    ERC2612ExpiredSignature(ERC2612ExpiredSignature),
+    ERC2612InvalidSigner(ERC2612InvalidSigner),
+`,
+          public: `
+    pub fn nonces(&self, owner: Address) -> U256 {
+        self.nonces.get(owner)
+    }
+
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    pub fn domain_separator(&self) -> B256 {
+        let name_hash = self.vm().native_keccak256(self.name.get_string().as_bytes());
+        let encoded = (
+            DOMAIN_TYPEHASH,
+            name_hash,
+            VERSION_HASH,
+            U256::from(self.vm().chain_id()),
+            self.vm().contract_address(),
+        )
+            .abi_encode();
+        self.vm().native_keccak256(&encoded)
+    }
+
+    /// Sets the allowance of spender over owner's tokens from an EIP-712
+    /// signature, so the owner does not have to send the approval themselves.
+    #[allow(clippy::too_many_arguments)]
+    pub fn permit(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        deadline: U256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Erc20Error> {
+        if U256::from(self.vm().block_timestamp()) > deadline {
+            return Err(Erc20Error::ERC2612ExpiredSignature(
+                ERC2612ExpiredSignature { deadline },
+            ));
+        }
+
+        let nonce = self.nonces.get(owner);
+        let digest = self.permit_digest(owner, spender, value, nonce, deadline);
+        let signer = self.recover_signer(digest, v, r, s);
+        if signer == Address::ZERO || signer != owner {
+            return Err(Erc20Error::ERC2612InvalidSigner(ERC2612InvalidSigner {
+                signer,
+                owner,
+            }));
+        }
+
+        self.nonces.insert(owner, nonce + U256::from(1));
+        self.allowances.setter(owner).insert(spender, value);
+        log(
+            self.vm(),
+            Approval {
+                owner,
+                spender,
+                value,
+            },
+        );
+        Ok(())
+    }
+`,
+          private: `
+    fn permit_digest(
+        &self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        nonce: U256,
+        deadline: U256,
+    ) -> B256 {
+        let struct_hash = self.vm().native_keccak256(
+            &(PERMIT_TYPEHASH, owner, spender, value, nonce, deadline).abi_encode(),
+        );
+
+        let mut message = Vec::with_capacity(66);
+        message.extend_from_slice(&[0x19, 0x01]);
+        message.extend_from_slice(self.domain_separator().as_slice());
+        message.extend_from_slice(struct_hash.as_slice());
+        self.vm().native_keccak256(&message)
+    }
+
+    /// Recovers the signer of digest through the ecrecover precompile. Invalid
+    /// and malleable signatures recover to the zero address.
+    fn recover_signer(&self, digest: B256, v: u8, r: B256, s: B256) -> Address {
+        if s > MAX_S {
+            return Address::ZERO;
+        }
+
+        let input = (digest, U256::from(v), r, s).abi_encode();
+        match self.vm().static_call(&Call::new(), ECRECOVER, &input) {
+            Ok(output) if output.len() == 32 => Address::from_word(B256::from_slice(&output)),
+            _ => Address::ZERO,
+        }
+    }
+`,
+          tests: `
+    /// Mocks the ecrecover precompile so that OWNER's next permit for spender
+    /// and value recovers signer, and returns the signature to submit.
+    fn mock_permit_signature(
+        vm: &TestVM,
+        contract: &ERC20,
+        signer: Address,
+        spender: Address,
+        value: U256,
+        deadline: U256,
+    ) -> (u8, B256, B256) {
+        let (v, r, s) = (27, B256::repeat_byte(0x0a), B256::repeat_byte(0x0b));
+        let nonce = contract.nonces(OWNER);
+        let digest = contract.permit_digest(OWNER, spender, value, nonce, deadline);
+        vm.mock_static_call(
+            ECRECOVER,
+            (digest, U256::from(v), r, s).abi_encode(),
+            Ok(signer.into_word().to_vec()),
+        );
+        (v, r, s)
+    }
+
+    #[test]
+    fn test_permit() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
+        let (v, r, s) =
+            mock_permit_signature(&vm, &contract, OWNER, ALICE, U256::from(300), U256::MAX);
+
+        // anyone may submit the owner's signature
+        vm.set_sender(BOB);
+        contract
+            .permit(OWNER, ALICE, U256::from(300), U256::MAX, v, r, s)
+            .unwrap();
+        assert_eq!(U256::from(300), contract.allowance(OWNER, ALICE));
+        assert_eq!(U256::from(1), contract.nonces(OWNER));
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Approval::SIGNATURE_HASH, topics[0]);
+        assert_eq!(OWNER.into_word(), topics[1]);
+        assert_eq!(ALICE.into_word(), topics[2]);
+    }
+
+    #[test]
+    fn test_permit_rejects_wrong_signer() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
+        let (v, r, s) =
+            mock_permit_signature(&vm, &contract, ALICE, ALICE, U256::from(300), U256::MAX);
+
+        let err = contract
+            .permit(OWNER, ALICE, U256::from(300), U256::MAX, v, r, s)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Erc20Error::ERC2612InvalidSigner(ERC2612InvalidSigner { signer, .. }) if signer == ALICE
+        ));
+        assert_eq!(U256::ZERO, contract.allowance(OWNER, ALICE));
+        assert_eq!(U256::ZERO, contract.nonces(OWNER));
+    }
+
+    #[test]
+    fn test_permit_rejects_expired_deadline() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
+        vm.set_block_timestamp(1_000);
+        let (v, r, s) =
+            mock_permit_signature(&vm, &contract, OWNER, ALICE, U256::from(300), U256::from(999));
+
+        let err = contract
+            .permit(OWNER, ALICE, U256::from(300), U256::from(999), v, r, s)
+            .unwrap_err();
+        assert!(matches!(err, Erc20Error::ERC2612ExpiredSignature(_)));
+        assert_eq!(U256::ZERO, contract.allowance(OWNER, ALICE));
+    }
+
+    #[test]
+    fn test_recover_signer_rejects_malleable_signature() {
+        let vm = TestVM::default();
+        let contract = deploy(&vm, U256::from(1000));
+
+        let signer = contract.recover_signer(B256::ZERO, 27, B256::ZERO, B256::repeat_byte(0xff));
+        assert_eq!(Address::ZERO, signer);
+    }
+`,
+        },
+        solidity: {
+          state: `
+    mapping(address => uint256) public nonces;
+
+    bytes32 private constant PERMIT_TYPEHASH =
+        keccak256("Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)");
+`,
+          errors: `    error ERC2612ExpiredSignature(uint256 deadline);
+    error ERC2612InvalidSigner(address signer, address owner);
+`,
+          public: `
+    function DOMAIN_SEPARATOR() public view returns (bytes32) {
+        return keccak256(
+            abi.encode(
+                keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"),
+                keccak256(bytes(_name)),
+                keccak256("1"),
+                block.chainid,
+                address(this)
+            )
+        );
+    }
+
+    function permit(
+        address owner_,
+        address spender,
+        uint256 value,
+        uint256 deadline,
+        uint8 v,
+        bytes32 r,
+        bytes32 s
+    ) public {
+        if (block.timestamp > deadline) {
+            revert ERC2612ExpiredSignature(deadline);
+        }
+
+        bytes32 structHash = keccak256(
+            abi.encode(PERMIT_TYPEHASH, owner_, spender, value, nonces[owner_], deadline)
+        );
+        bytes32 digest = keccak256(abi.encodePacked(bytes2(0x1901), DOMAIN_SEPARATOR(), structHash));
+
+        address signer = _recover(digest, v, r, s);
+        if (signer == address(0) || signer != owner_) {
+            revert ERC2612InvalidSigner(signer, owner_);
+        }
+
+        unchecked {
+            nonces[owner_]++;
+        }
+        allowances[owner_][spender] = value;
+        emit Approval(owner_, spender, value);
+    }
+`,
+          internal: `
+    function _recover(bytes32 digest, uint8 v, bytes32 r, bytes32 s) internal pure returns (address) {
+        // Malleable signatures (s in the upper half of the curve order) are rejected
+        if (uint256(s) > 0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF5D576E7357A4501DDFE92F46681B20A0) {
+            return address(0);
+        }
+        return ecrecover(digest, v, r, s);
+    }
+`,
+        },
+      },
+      burnable: {
+        description: 'Burnable (burn, burnFrom)',
+        rust: {
+          public: `
+    pub fn burn(&mut self, amount: U256) -> Result<(), Erc20Error> {
+        let sender = self.vm().msg_sender();
+        self.burn_tokens(sender, amount)
+    }
+
+    pub fn burn_from(&mut self, account: Address, amount: U256) -> Result<(), Erc20Error> {
+        let spender = self.vm().msg_sender();
+        let allowance = self.allowances.getter(account).get(spender);
+
+        if allowance < amount {
+            return Err(Erc20Error::InsufficientAllowance(InsufficientAllowance {
+                spender,
+                allowance,
+                needed: amount,
+            }));
+        }
+
+        self.burn_tokens(account, amount)?;
+
+        if allowance != U256::MAX {
+            self.allowances.setter(account).insert(spender, allowance - amount);
+        }
+        Ok(())
+    }
+`,
+          private: `
+    fn burn_tokens(&mut self, from: Address, amount: U256) -> Result<(), Erc20Error> {
+        let balance = self.balances.get(from);
+        if balance < amount {
+            return Err(Erc20Error::InsufficientBalance(InsufficientBalance {
+                sender: from,
+                balance,
+                needed: amount,
+            }));
+        }
+
+        self.balances.insert(from, balance - amount);
+        // Cannot underflow: the total supply is at least any single balance
+        let supply = self.total_supply.get();
+        self.total_supply.set(supply - amount);
+
+        log(
+            self.vm(),
+            Transfer {
+                from,
+                to: Address::ZERO,
+                value: amount,
+            },
+        );
+        Ok(())
+    }
+`,
+          tests: `
+    #[test]
+    fn test_burn() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
+
+        contract.burn(U256::from(400)).unwrap();
+        assert_eq!(U256::from(600), contract.balance_of(OWNER));
+        assert_eq!(U256::from(600), contract.total_supply());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Transfer::SIGNATURE_HASH, topics[0]);
+        assert_eq!(Address::ZERO.into_word(), topics[2]);
+
+        let err = contract.burn(U256::from(601)).unwrap_err();
+        assert!(matches!(err, Erc20Error::InsufficientBalance(_)));
+        assert_eq!(U256::from(600), contract.total_supply());
+    }
+
+    #[test]
+    fn test_burn_from() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
+
+        contract.approve(ALICE, U256::from(300));
+
+        vm.set_sender(ALICE);
+        contract.burn_from(OWNER, U256::from(100)).unwrap();
+        assert_eq!(U256::from(200), contract.allowance(OWNER, ALICE));
+        assert_eq!(U256::from(900), contract.balance_of(OWNER));
+        assert_eq!(U256::from(900), contract.total_supply());
+
+        let err = contract.burn_from(OWNER, U256::from(201)).unwrap_err();
+        assert!(matches!(err, Erc20Error::InsufficientAllowance(_)));
+        assert_eq!(U256::from(900), contract.total_supply());
+    }
+`,
+        },
+        solidity: {
+          public: `
+    function burn(uint256 amount) public {
+        _burn(msg.sender, amount);
+    }
+
+    function burnFrom(address account, uint256 amount) public {
+        uint256 currentAllowance = allowances[account][msg.sender];
+        if (currentAllowance < amount) {
+            revert InsufficientAllowance(msg.sender, currentAllowance, amount);
+        }
+
+        _burn(account, amount);
+
+        if (currentAllowance != type(uint256).max) {
+            allowances[account][msg.sender] = currentAllowance - amount;
+        }
+    }
+`,
+          internal: `
+    function _burn(address from, uint256 amount) internal {
+        uint256 fromBalance = balances[from];
+        if (fromBalance < amount) {
+            revert InsufficientBalance(from, fromBalance, amount);
+        }
+
+        unchecked {
+            balances[from] = fromBalance - amount;
+            // Cannot underflow: the total supply is at least any single balance
+            _totalSupply -= amount;
+        }
+        emit Transfer(from, address(0), amount);
+    }
+`,
+        },
+      },
+      capped: {
+        description: 'Capped supply (cap set at deployment)',
+        rust: {
+          storage: `    cap: StorageU256,
+`,
+          sol: `    error ERC20ExceededCap(uint256 increasedSupply, uint256 cap);
+    error ERC20InvalidCap(uint256 cap);
+`,
+          errors: `    ERC20ExceededCap(ERC20ExceededCap),
+    ERC20InvalidCap(ERC20InvalidCap),
+`,
+          'constructor-params': `        cap: U256,
+`,
+          'constructor-body': `        if cap.is_zero() {
+            return Err(Erc20Error::ERC20InvalidCap(ERC20InvalidCap { cap }));
+        }
+        self.cap.set(cap);
+`,
+          public: `
+    pub fn cap(&self) -> U256 {
+        self.cap.get()
+    }
+`,
+          mint: `
+        let cap = self.cap.get();
+        if new_supply > cap {
+            return Err(Erc20Error::ERC20ExceededCap(ERC20ExceededCap {
+                increasedSupply: new_supply,
+                cap,
+            }));
+        }
+`,
+          'test-deploy': `        contract.cap.set(U256::from(1_000_000));
+`,
+          tests: `
+    #[test]
+    fn test_mint_respects_cap() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1000));
+        assert_eq!(U256::from(1_000_000), contract.cap());
+
+        contract.mint(ALICE, U256::from(999_000)).unwrap();
+        assert_eq!(contract.cap(), contract.total_supply());
+
+        let err = contract.mint(ALICE, U256::from(1)).unwrap_err();
+        assert!(matches!(
+            err,
+            Erc20Error::ERC20ExceededCap(ERC20ExceededCap { increasedSupply: supply, .. })
+                if supply == U256::from(1_000_001)
+        ));
+        assert_eq!(U256::from(1_000_000), contract.total_supply());
+    }
+`,
+        },
+        solidity: {
+          state: `
+    uint256 private _cap;
+`,
+          errors: `    error ERC20ExceededCap(uint256 increasedSupply, uint256 cap);
+    error ERC20InvalidCap(uint256 cap);
+`,
+          'constructor-params': `        uint256 cap_,
+`,
+          'constructor-body': `        if (cap_ == 0) {
+            revert ERC20InvalidCap(cap_);
+        }
+        _cap = cap_;
+`,
+          public: `
+    function cap() public view returns (uint256) {
+        return _cap;
+    }
+`,
+          mint: `        if (_totalSupply + amount > _cap) {
+            revert ERC20ExceededCap(_totalSupply + amount, _cap);
+        }
+`,
+        },
+      },
+    },
+  },
+  erc721: {
+    name: 'ERC721NFT',
+    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, U256},
+    call::Call,
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageMap, StorageString, StorageU256},
+    stylus_core::log,
+};
+// @extension imports
+
+/// Value a receiver contract must return from onERC721Received (its selector)
+const ERC721_RECEIVED: FixedBytes<4> = FixedBytes::new([0x15, 0x0b, 0x7a, 0x02]);
+const INTERFACE_ID_ERC165: FixedBytes<4> = FixedBytes::new([0x01, 0xff, 0xc9, 0xa7]);
+const INTERFACE_ID_ERC721: FixedBytes<4> = FixedBytes::new([0x80, 0xac, 0x58, 0xcd]);
+const INTERFACE_ID_ERC721_METADATA: FixedBytes<4> = FixedBytes::new([0x5b, 0x5e, 0x13, 0x9f]);
+// @extension constants
+
+sol_interface! {
+    interface IERC721Receiver {
+        function onERC721Received(address operator, address from, uint256 token_id, bytes data) external returns (bytes4);
+    }
+}
+
+#[storage]
+#[entrypoint]
+pub struct ERC721 {
+    owners: StorageMap<U256, StorageAddress>,
+    balances: StorageMap<Address, StorageU256>,
+    token_approvals: StorageMap<U256, StorageAddress>,
+    operator_approvals: StorageMap<Address, StorageMap<Address, StorageBool>>,
+    next_token_id: StorageU256,
+    name: StorageString,
+    symbol: StorageString,
+    base_uri: StorageString,
+    // @extension storage
+    owner: StorageAddress,
+}
+
+sol! {
+    #![sol(all_derives)]
+
+    event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+    event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId);
+    event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    error ERC721InvalidOwner(address owner);
+    error ERC721NonexistentToken(uint256 tokenId);
+    error ERC721IncorrectOwner(address sender, uint256 tokenId, address owner);
+    error ERC721InvalidReceiver(address receiver);
+    error ERC721InsufficientApproval(address operator, uint256 tokenId);
+    error ERC721InvalidApprover(address approver);
+    error ERC721InvalidOperator(address operator);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+    // @extension sol
+}
+
+#[derive(SolidityError, Debug)]
+pub enum Erc721Error {
+    ERC721InvalidOwner(ERC721InvalidOwner),
+    ERC721NonexistentToken(ERC721NonexistentToken),
+    ERC721IncorrectOwner(ERC721IncorrectOwner),
+    ERC721InvalidReceiver(ERC721InvalidReceiver),
+    ERC721InsufficientApproval(ERC721InsufficientApproval),
+    ERC721InvalidApprover(ERC721InvalidApprover),
+    ERC721InvalidOperator(ERC721InvalidOperator),
+    Unauthorized(Unauthorized),
+    InvalidOwner(InvalidOwner),
+    // @extension errors
+}
+
+#[public]
+impl ERC721 {
+    /// Sets the collection metadata and makes the deploying account the
+    /// owner.
+    #[constructor]
+    pub fn constructor(&mut self, name: String, symbol: String, base_uri: String) {
+        self.name.set_str(&name);
+        self.symbol.set_str(&symbol);
+        self.base_uri.set_str(&base_uri);
+
+        let deployer = self.vm().tx_origin();
+        self.write_owner(deployer);
+    }
+
+    pub fn name(&self) -> String {
+        self.name.get_string()
+    }
+
+    pub fn symbol(&self) -> String {
+        self.symbol.get_string()
+    }
+
+    #[selector(name = "tokenURI")]
+    pub fn token_uri(&self, token_id: U256) -> Result<String, Erc721Error> {
+        self.require_owned(token_id)?;
+
+        let base_uri = self.base_uri.get_string();
+        // @extension token-uri
+        if base_uri.is_empty() {
+            return Ok(String::new());
+        }
+        Ok(format!("{}{}", base_uri, token_id))
+    }
+
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        interface_id == INTERFACE_ID_ERC165
+            || interface_id == INTERFACE_ID_ERC721
+            || interface_id == INTERFACE_ID_ERC721_METADATA
+            // @extension interfaces
+    }
+
+    pub fn balance_of(&self, owner: Address) -> Result<U256, Erc721Error> {
+        if owner == Address::ZERO {
+            return Err(Erc721Error::ERC721InvalidOwner(ERC721InvalidOwner { owner }));
+        }
+        Ok(self.balances.get(owner))
+    }
+
+    pub fn owner_of(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        self.require_owned(token_id)
+    }
+
+    pub fn approve(&mut self, to: Address, token_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.require_owned(token_id)?;
+        let sender = self.vm().msg_sender();
+
+        if sender != owner && !self.is_approved_for_all(owner, sender) {
+            return Err(Erc721Error::ERC721InvalidApprover(ERC721InvalidApprover {
+                approver: sender,
+            }));
+        }
+
+        self.token_approvals.insert(token_id, to);
+        log(
+            self.vm(),
+            Approval {
+                owner,
+                approved: to,
+                tokenId: token_id,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get_approved(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        self.require_owned(token_id)?;
+        Ok(self.token_approvals.get(token_id))
+    }
+
+    pub fn set_approval_for_all(
+        &mut self,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), Erc721Error> {
+        if operator == Address::ZERO {
+            return Err(Erc721Error::ERC721InvalidOperator(ERC721InvalidOperator { operator }));
+        }
+
+        let owner = self.vm().msg_sender();
+        self.operator_approvals.setter(owner).insert(operator, approved);
+        log(
+            self.vm(),
+            ApprovalForAll {
+                owner,
+                operator,
+                approved,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn is_approved_for_all(&self, owner: Address, operator: Address) -> bool {
+        self.operator_approvals.getter(owner).get(operator)
+    }
+
+    pub fn transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        let spender = self.vm().msg_sender();
+        self.transfer(spender, from, to, token_id)
+    }
+
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        self.safe_transfer_from_with_data(from, to, token_id, Bytes(Vec::new()))
+    }
+
+    /// Transfers the token and, when the receiver is a contract, requires it to
+    /// accept the token through onERC721Received.
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from_with_data(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<(), Erc721Error> {
+        let spender = self.vm().msg_sender();
+        self.transfer(spender, from, to, token_id)?;
+        self.check_on_erc721_received(spender, from, to, token_id, data)
+    }
+
+    pub fn mint(&mut self, to: Address) -> Result<U256, Erc721Error> {
+        self.only_owner()?;
+        if to == Address::ZERO {
+            return Err(Erc721Error::ERC721InvalidReceiver(ERC721InvalidReceiver { receiver: to }));
+        }
+
+        let token_id = self.next_token_id.get();
+        self.next_token_id.set(token_id + U256::from(1));
+        // @extension mint
+
+        let balance = self.balances.get(to);
+        self.balances.insert(to, balance + U256::from(1));
+        self.owners.insert(token_id, to);
+
+        log(
+            self.vm(),
+            Transfer {
+                from: Address::ZERO,
+                to,
+                tokenId: token_id,
+            },
+        );
+        Ok(token_id)
+    }
+
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), Erc721Error> {
+        self.only_owner()?;
+        if new_owner == Address::ZERO {
+            return Err(Erc721Error::InvalidOwner(InvalidOwner { owner: new_owner }));
+        }
+        self.write_owner(new_owner);
+        Ok(())
+    }
+
+    pub fn renounce_ownership(&mut self) -> Result<(), Erc721Error> {
+        self.only_owner()?;
+        self.write_owner(Address::ZERO);
+        Ok(())
+    }
+    // @extension public
+}
+
+impl ERC721 {
+    fn only_owner(&self) -> Result<(), Erc721Error> {
+        let sender = self.vm().msg_sender();
+        if sender != self.owner.get() {
+            return Err(Erc721Error::Unauthorized(Unauthorized { account: sender }));
+        }
+        Ok(())
+    }
+
+    fn require_owned(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        let owner = self.owners.get(token_id);
+        if owner == Address::ZERO {
+            return Err(Erc721Error::ERC721NonexistentToken(ERC721NonexistentToken {
+                tokenId: token_id,
+            }));
+        }
+        Ok(owner)
+    }
+
+    fn is_authorized(&self, owner: Address, spender: Address, token_id: U256) -> bool {
+        spender == owner
+            || self.is_approved_for_all(owner, spender)
+            || self.token_approvals.get(token_id) == spender
+    }
+
+    fn transfer(
+        &mut self,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        if to == Address::ZERO {
+            return Err(Erc721Error::ERC721InvalidReceiver(ERC721InvalidReceiver { receiver: to }));
+        }
+
+        let owner = self.require_owned(token_id)?;
+        if !self.is_authorized(owner, spender, token_id) {
+            return Err(Erc721Error::ERC721InsufficientApproval(
+                ERC721InsufficientApproval {
+                    operator: spender,
+                    tokenId: token_id,
+                },
+            ));
+        }
+        if owner != from {
+            return Err(Erc721Error::ERC721IncorrectOwner(ERC721IncorrectOwner {
+                sender: from,
+                tokenId: token_id,
+                owner,
+            }));
+        }
+
+        // Approvals are per owner, so they do not survive a transfer
+        self.token_approvals.delete(token_id);
+        // @extension transfer
+
+        // Cannot underflow or overflow: from owns the token, and there are
+        // fewer tokens than U256::MAX
+        let from_balance = self.balances.get(from);
+        self.balances.insert(from, from_balance - U256::from(1));
+        let to_balance = self.balances.get(to);
+        self.balances.insert(to, to_balance + U256::from(1));
+        self.owners.insert(token_id, to);
+
+        log(
+            self.vm(),
+            Transfer {
+                from,
+                to,
+                tokenId: token_id,
+            },
+        );
+        Ok(())
+    }
+
+    /// Accounts without code always accept tokens. Contracts must implement
+    /// IERC721Receiver and return its selector.
+    fn check_on_erc721_received(
+        &mut self,
+        operator: Address,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<(), Erc721Error> {
+        if self.vm().code_size(to) == 0 {
+            return Ok(());
+        }
+
+        let receiver = IERC721Receiver::new(to);
+        let call = Call::new_mutating(self);
+        let received =
+            receiver.on_erc_721_received(self.vm(), call, operator, from, token_id, data.0.into());
+
+        match received {
+            Ok(retval) if retval == ERC721_RECEIVED => Ok(()),
+            _ => Err(Erc721Error::ERC721InvalidReceiver(ERC721InvalidReceiver { receiver: to })),
+        }
+    }
+
+    fn write_owner(&mut self, new_owner: Address) {
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+        log(
+            self.vm(),
+            OwnershipTransferred {
+                previousOwner: previous_owner,
+                newOwner: new_owner,
+            },
+        );
+    }
+    // @extension private
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_sol_types::{SolCall, SolEvent};
+    use stylus_sdk::testing::*;
+
+    const OWNER: Address = Address::repeat_byte(0x11);
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+    const RECEIVER: Address = Address::repeat_byte(0x7e);
+
+    sol! {
+        function onERC721Received(address operator, address from, uint256 tokenId, bytes data) external returns (bytes4);
+    }
+
+    /// Initializes the collection the way the constructor does, with OWNER as
+    /// the deployer and the caller.
+    fn deploy(vm: &TestVM) -> ERC721 {
+        let mut contract = ERC721::from(vm);
+        contract.name.set_str("MyNFT");
+        contract.symbol.set_str("MNFT");
+        contract.base_uri.set_str("ipfs://collection/");
+        contract.write_owner(OWNER);
+        vm.set_sender(OWNER);
+        contract
+    }
+
+    #[test]
+    fn test_metadata() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        assert_eq!("MyNFT", contract.name());
+        assert_eq!("MNFT", contract.symbol());
+
+        let token_id = contract.mint(ALICE).unwrap();
+        assert_eq!("ipfs://collection/0", contract.token_uri(token_id).unwrap());
+
+        let err = contract.token_uri(U256::from(1)).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721NonexistentToken(_)));
+    }
+
+    #[test]
+    fn test_supports_interface() {
+        let vm = TestVM::default();
+        let contract = deploy(&vm);
+
+        assert!(contract.supports_interface(INTERFACE_ID_ERC165));
+        assert!(contract.supports_interface(INTERFACE_ID_ERC721));
+        assert!(contract.supports_interface(INTERFACE_ID_ERC721_METADATA));
+        assert!(!contract.supports_interface(FixedBytes::new([0xff, 0xff, 0xff, 0xff])));
+    }
+
+    #[test]
+    fn test_mint() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        assert_eq!(U256::ZERO, contract.mint(ALICE).unwrap());
+        assert_eq!(U256::from(1), contract.mint(ALICE).unwrap());
+        assert_eq!(U256::from(2), contract.balance_of(ALICE).unwrap());
+        assert_eq!(ALICE, contract.owner_of(U256::from(1)).unwrap());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Transfer::SIGNATURE_HASH, topics[0]);
+        assert_eq!(Address::ZERO.into_word(), topics[1]);
+        assert_eq!(ALICE.into_word(), topics[2]);
+        assert_eq!(U256::from(1).to_be_bytes::<32>(), topics[3].0);
+
+        let err = contract.mint(Address::ZERO).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721InvalidReceiver(_)));
+    }
+
+    #[test]
+    fn test_mint_rejects_non_owner() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        vm.set_sender(ALICE);
+        let err = contract.mint(ALICE).unwrap_err();
+        assert!(matches!(
+            err,
+            Erc721Error::Unauthorized(Unauthorized { account }) if account == ALICE
+        ));
+        assert_eq!(U256::ZERO, contract.balance_of(ALICE).unwrap());
+    }
+
+    #[test]
+    fn test_queries_reject_invalid_input() {
+        let vm = TestVM::default();
+        let contract = deploy(&vm);
+
+        let err = contract.owner_of(U256::from(7)).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721NonexistentToken(_)));
+
+        let err = contract.get_approved(U256::from(7)).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721NonexistentToken(_)));
+
+        let err = contract.balance_of(Address::ZERO).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721InvalidOwner(_)));
+    }
+
+    #[test]
+    fn test_transfer_from() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let token_id = contract.mint(ALICE).unwrap();
+
+        vm.set_sender(ALICE);
+        contract.transfer_from(ALICE, BOB, token_id).unwrap();
+        assert_eq!(BOB, contract.owner_of(token_id).unwrap());
+        assert_eq!(U256::ZERO, contract.balance_of(ALICE).unwrap());
+        assert_eq!(U256::from(1), contract.balance_of(BOB).unwrap());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Transfer::SIGNATURE_HASH, topics[0]);
+        assert_eq!(ALICE.into_word(), topics[1]);
+        assert_eq!(BOB.into_word(), topics[2]);
+    }
+
+    #[test]
+    fn test_transfer_from_rejects_invalid_transfers() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let token_id = contract.mint(ALICE).unwrap();
+
+        // not the owner, not approved
+        vm.set_sender(BOB);
+        let err = contract.transfer_from(ALICE, BOB, token_id).unwrap_err();
+        assert!(matches!(
+            err,
+            Erc721Error::ERC721InsufficientApproval(ERC721InsufficientApproval { operator, .. })
+                if operator == BOB
+        ));
+
+        // from does not own the token
+        vm.set_sender(ALICE);
+        let err = contract.transfer_from(BOB, ALICE, token_id).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721IncorrectOwner(_)));
+
+        let err = contract.transfer_from(ALICE, Address::ZERO, token_id).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721InvalidReceiver(_)));
+
+        assert_eq!(ALICE, contract.owner_of(token_id).unwrap());
+    }
+
+    #[test]
+    fn test_approve_and_transfer_from() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let token_id = contract.mint(ALICE).unwrap();
+
+        vm.set_sender(ALICE);
+        contract.approve(BOB, token_id).unwrap();
+        assert_eq!(BOB, contract.get_approved(token_id).unwrap());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Approval::SIGNATURE_HASH, topics[0]);
+
+        vm.set_sender(BOB);
+        contract.transfer_from(ALICE, BOB, token_id).unwrap();
+        assert_eq!(BOB, contract.owner_of(token_id).unwrap());
+
+        // the approval is cleared by the transfer
+        assert_eq!(Address::ZERO, contract.get_approved(token_id).unwrap());
+    }
+
+    #[test]
+    fn test_approve_rejects_non_owner() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let token_id = contract.mint(ALICE).unwrap();
+
+        vm.set_sender(BOB);
+        let err = contract.approve(BOB, token_id).unwrap_err();
+        assert!(matches!(
+            err,
+            Erc721Error::ERC721InvalidApprover(ERC721InvalidApprover { approver }) if approver == BOB
+        ));
+        assert_eq!(Address::ZERO, contract.get_approved(token_id).unwrap());
+    }
+
+    #[test]
+    fn test_set_approval_for_all() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let first = contract.mint(ALICE).unwrap();
+        let second = contract.mint(ALICE).unwrap();
+
+        vm.set_sender(ALICE);
+        contract.set_approval_for_all(BOB, true).unwrap();
+        assert!(contract.is_approved_for_all(ALICE, BOB));
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(ApprovalForAll::SIGNATURE_HASH, topics[0]);
+
+        // an operator can move every token and approve others
+        vm.set_sender(BOB);
+        contract.transfer_from(ALICE, BOB, first).unwrap();
+        contract.approve(OWNER, second).unwrap();
+        assert_eq!(OWNER, contract.get_approved(second).unwrap());
+
+        vm.set_sender(ALICE);
+        contract.set_approval_for_all(BOB, false).unwrap();
+        assert!(!contract.is_approved_for_all(ALICE, BOB));
+
+        let err = contract.set_approval_for_all(Address::ZERO, true).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721InvalidOperator(_)));
+    }
+
+    #[test]
+    fn test_safe_transfer_from_to_account() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let token_id = contract.mint(ALICE).unwrap();
+
+        // accounts without code accept tokens without a callback
+        vm.set_sender(ALICE);
+        contract.safe_transfer_from(ALICE, BOB, token_id).unwrap();
+        assert_eq!(BOB, contract.owner_of(token_id).unwrap());
+
+        vm.set_sender(BOB);
+        contract
+            .safe_transfer_from_with_data(BOB, ALICE, token_id, Bytes(vec![1, 2, 3]))
+            .unwrap();
+        assert_eq!(ALICE, contract.owner_of(token_id).unwrap());
+    }
+
+    /// Gives RECEIVER code and answers its onERC721Received callback for a
+    /// transfer of token_id by ALICE
+    fn mock_receiver(vm: &TestVM, token_id: U256, result: Result<Vec<u8>, Vec<u8>>) {
+        vm.set_code(RECEIVER, vec![0x00]);
+        let data = onERC721ReceivedCall {
+            operator: ALICE,
+            from: ALICE,
+            tokenId: token_id,
+            data: Vec::new().into(),
+        }
+        .abi_encode();
+        vm.mock_call(RECEIVER, data, U256::ZERO, result);
+    }
+
+    /// A bytes4 return value, padded to a word
+    fn encode_selector(selector: FixedBytes<4>) -> Vec<u8> {
+        let mut word = selector.to_vec();
+        word.resize(32, 0);
+        word
+    }
+
+    #[test]
+    fn test_safe_transfer_from_to_accepting_contract() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let token_id = contract.mint(ALICE).unwrap();
+
+        mock_receiver(&vm, token_id, Ok(encode_selector(ERC721_RECEIVED)));
+        vm.set_sender(ALICE);
+        contract.safe_transfer_from(ALICE, RECEIVER, token_id).unwrap();
+        assert_eq!(RECEIVER, contract.owner_of(token_id).unwrap());
+    }
+
+    #[test]
+    fn test_safe_transfer_from_to_rejecting_contract() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        // a receiver returning anything but the selector rejects the token
+        let token_id = contract.mint(ALICE).unwrap();
+        let wrong_selector = FixedBytes::new([0xde, 0xad, 0xbe, 0xef]);
+        mock_receiver(&vm, token_id, Ok(encode_selector(wrong_selector)));
+        vm.set_sender(ALICE);
+        let err = contract.safe_transfer_from(ALICE, RECEIVER, token_id).unwrap_err();
+        assert!(matches!(
+            err,
+            Erc721Error::ERC721InvalidReceiver(ERC721InvalidReceiver { receiver })
+                if receiver == RECEIVER
+        ));
+
+        // as does one that reverts
+        vm.set_sender(OWNER);
+        let token_id = contract.mint(ALICE).unwrap();
+        mock_receiver(&vm, token_id, Err(Vec::new()));
+        vm.set_sender(ALICE);
+        let err = contract.safe_transfer_from(ALICE, RECEIVER, token_id).unwrap_err();
+        assert!(matches!(
+            err,
+            Erc721Error::ERC721InvalidReceiver(ERC721InvalidReceiver { receiver })
+                if receiver == RECEIVER
+        ));
+    }
+    // @extension tests
+}
+`,
+    solidity: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+interface IERC721Receiver {
+    function onERC721Received(
+        address operator,
+        address from,
+        uint256 tokenId,
+        bytes calldata data
+    ) external returns (bytes4);
+}
+
+contract ERC721NFT {
+    mapping(uint256 => address) private owners;
+    mapping(address => uint256) private balances;
+    mapping(uint256 => address) private tokenApprovals;
+    mapping(address => mapping(address => bool)) private operatorApprovals;
+
+    uint256 private nextTokenId;
+
+    string private _name;
+    string private _symbol;
+    string private _baseURI;
+
+    address private _owner;
+    // @extension state
+
+    event Transfer(address indexed from, address indexed to, uint256 indexed tokenId);
+    event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId);
+    event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+    // @extension events
+
+    error ERC721InvalidOwner(address owner);
+    error ERC721NonexistentToken(uint256 tokenId);
+    error ERC721IncorrectOwner(address sender, uint256 tokenId, address owner);
+    error ERC721InvalidReceiver(address receiver);
+    error ERC721InsufficientApproval(address operator, uint256 tokenId);
+    error ERC721InvalidApprover(address approver);
+    error ERC721InvalidOperator(address operator);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+    // @extension errors
+
+    modifier onlyOwner() {
+        if (msg.sender != _owner) {
+            revert Unauthorized(msg.sender);
+        }
+        _;
+    }
+
+    constructor(string memory name_, string memory symbol_, string memory baseURI_) {
+        _name = name_;
+        _symbol = symbol_;
+        _baseURI = baseURI_;
+        _transferOwnership(msg.sender);
+    }
+
+    function name() public view returns (string memory) {
+        return _name;
+    }
+
+    function symbol() public view returns (string memory) {
+        return _symbol;
+    }
+
+    function tokenURI(uint256 tokenId) public view returns (string memory) {
+        _requireOwned(tokenId);
+        // @extension token-uri
+
+        if (bytes(_baseURI).length == 0) {
+            return "";
+        }
+        return string.concat(_baseURI, _toString(tokenId));
+    }
+
+    function supportsInterface(bytes4 interfaceId) public view returns (bool) {
+        return
+            interfaceId == 0x01ffc9a7 || // ERC-165
+            interfaceId == 0x80ac58cd || // ERC-721
+            // @extension interfaces
+            interfaceId == 0x5b5e139f; // ERC-721 Metadata
+    }
+
+    function balanceOf(address owner_) public view returns (uint256) {
+        if (owner_ == address(0)) {
+            revert ERC721InvalidOwner(owner_);
+        }
+        return balances[owner_];
+    }
+
+    function ownerOf(uint256 tokenId) public view returns (address) {
+        return _requireOwned(tokenId);
+    }
+
+    function approve(address to, uint256 tokenId) public {
+        address tokenOwner = _requireOwned(tokenId);
+
+        if (msg.sender != tokenOwner && !isApprovedForAll(tokenOwner, msg.sender)) {
+            revert ERC721InvalidApprover(msg.sender);
+        }
+
+        tokenApprovals[tokenId] = to;
+        emit Approval(tokenOwner, to, tokenId);
+    }
+
+    function getApproved(uint256 tokenId) public view returns (address) {
+        _requireOwned(tokenId);
+        return tokenApprovals[tokenId];
+    }
+
+    function setApprovalForAll(address operator, bool approved) public {
+        if (operator == address(0)) {
+            revert ERC721InvalidOperator(operator);
+        }
+
+        operatorApprovals[msg.sender][operator] = approved;
+        emit ApprovalForAll(msg.sender, operator, approved);
+    }
+
+    function isApprovedForAll(address owner_, address operator) public view returns (bool) {
+        return operatorApprovals[owner_][operator];
+    }
+
+    function transferFrom(address from, address to, uint256 tokenId) public {
+        _transfer(msg.sender, from, to, tokenId);
+    }
+
+    function safeTransferFrom(address from, address to, uint256 tokenId) public {
+        safeTransferFrom(from, to, tokenId, "");
+    }
+
+    function safeTransferFrom(address from, address to, uint256 tokenId, bytes memory data) public {
+        _transfer(msg.sender, from, to, tokenId);
+        _checkOnERC721Received(msg.sender, from, to, tokenId, data);
+    }
+
+    function mint(address to) public onlyOwner returns (uint256) {
+        if (to == address(0)) {
+            revert ERC721InvalidReceiver(to);
+        }
+
+        uint256 tokenId = nextTokenId;
+        nextTokenId += 1;
+        // @extension mint
+
+        unchecked {
+            balances[to] += 1;
+        }
+        owners[tokenId] = to;
+
+        emit Transfer(address(0), to, tokenId);
+        return tokenId;
+    }
+
+    function owner() public view returns (address) {
+        return _owner;
+    }
+
+    function transferOwnership(address newOwner) public onlyOwner {
+        if (newOwner == address(0)) {
+            revert InvalidOwner(newOwner);
+        }
+        _transferOwnership(newOwner);
+    }
+
+    function renounceOwnership() public onlyOwner {
+        _transferOwnership(address(0));
+    }
+    // @extension public
+
+    function _requireOwned(uint256 tokenId) internal view returns (address) {
+        address tokenOwner = owners[tokenId];
+        if (tokenOwner == address(0)) {
+            revert ERC721NonexistentToken(tokenId);
+        }
+        return tokenOwner;
+    }
+
+    function _isAuthorized(address tokenOwner, address spender, uint256 tokenId) internal view returns (bool) {
+        return
+            spender == tokenOwner ||
+            isApprovedForAll(tokenOwner, spender) ||
+            tokenApprovals[tokenId] == spender;
+    }
+
+    function _transfer(address spender, address from, address to, uint256 tokenId) internal {
+        if (to == address(0)) {
+            revert ERC721InvalidReceiver(to);
+        }
+
+        address tokenOwner = _requireOwned(tokenId);
+        if (!_isAuthorized(tokenOwner, spender, tokenId)) {
+            revert ERC721InsufficientApproval(spender, tokenId);
+        }
+        if (tokenOwner != from) {
+            revert ERC721IncorrectOwner(from, tokenId, tokenOwner);
+        }
+
+        // Approvals are per owner, so they do not survive a transfer
+        delete tokenApprovals[tokenId];
+        // @extension transfer
+
+        unchecked {
+            balances[from] -= 1;
+            balances[to] += 1;
+        }
+        owners[tokenId] = to;
+
+        emit Transfer(from, to, tokenId);
+    }
+
+    // Accounts without code always accept tokens. Contracts must implement
+    // IERC721Receiver and return its selector.
+    function _checkOnERC721Received(
+        address operator,
+        address from,
+        address to,
+        uint256 tokenId,
+        bytes memory data
+    ) internal {
+        if (to.code.length == 0) {
+            return;
+        }
+
+        try IERC721Receiver(to).onERC721Received(operator, from, tokenId, data) returns (bytes4 retval) {
+            if (retval != IERC721Receiver.onERC721Received.selector) {
+                revert ERC721InvalidReceiver(to);
+            }
+        } catch {
+            revert ERC721InvalidReceiver(to);
+        }
+    }
+
+    function _transferOwnership(address newOwner) internal {
+        address previousOwner = _owner;
+        _owner = newOwner;
+        emit OwnershipTransferred(previousOwner, newOwner);
+    }
+
+    function _toString(uint256 value) internal pure returns (string memory) {
+        if (value == 0) {
+            return "0";
+        }
+
+        uint256 digits;
+        for (uint256 temp = value; temp != 0; temp /= 10) {
+            digits++;
+        }
+
+        bytes memory buffer = new bytes(digits);
+        while (value != 0) {
+            digits--;
+            buffer[digits] = bytes1(uint8(48 + (value % 10)));
+            value /= 10;
+        }
+        return string(buffer);
+    }
+    // @extension internal
+}
+`,
+    // Opt-in modules selected with `init --extensions`. Each one contributes code to the
+    // `// @extension <slot>` markers in the Rust and Solidity sources above. burnable has
+    // a burn slot of its own, which the other extensions fill to clean up their storage.
+    extensions: {
+      enumerable: {
+        description: 'Enumerable (totalSupply, tokenByIndex, tokenOfOwnerByIndex)',
+        rust: {
+          imports: `use stylus_sdk::storage::StorageVec;
+`,
+          constants: `const INTERFACE_ID_ERC721_ENUMERABLE: FixedBytes<4> = FixedBytes::new([0x78, 0x0e, 0x9d, 0x63]);
+`,
+          storage: `    /// Tokens of each owner by position, and each token's position in its
+    /// owner's list. The pair allows O(1) swap-and-pop removal.
+    owned_tokens: StorageMap<Address, StorageMap<U256, StorageU256>>,
+    owned_tokens_index: StorageMap<U256, StorageU256>,
+    /// Every existing token, and each token's position in all_tokens
+    all_tokens: StorageVec<StorageU256>,
+    all_tokens_index: StorageMap<U256, StorageU256>,
+`,
+          sol: `    error ERC721OutOfBoundsIndex(address owner, uint256 index);
+`,
+          errors: `    ERC721OutOfBoundsIndex(ERC721OutOfBoundsIndex),
+`,
+          interfaces: `            || interface_id == INTERFACE_ID_ERC721_ENUMERABLE
+`,
+          public: `
+    pub fn total_supply(&self) -> U256 {
+        U256::from(self.all_tokens.len())
+    }
+
+    pub fn token_by_index(&self, index: U256) -> Result<U256, Erc721Error> {
+        self.all_tokens.get(index).ok_or(Erc721Error::ERC721OutOfBoundsIndex(
+            ERC721OutOfBoundsIndex {
+                owner: Address::ZERO,
+                index,
+            },
+        ))
+    }
+
+    pub fn token_of_owner_by_index(&self, owner: Address, index: U256) -> Result<U256, Erc721Error> {
+        if index >= self.balance_of(owner)? {
+            return Err(Erc721Error::ERC721OutOfBoundsIndex(ERC721OutOfBoundsIndex {
+                owner,
+                index,
+            }));
+        }
+        Ok(self.owned_tokens.getter(owner).get(index))
+    }
+`,
+          mint: `
+        self.all_tokens_index.insert(token_id, U256::from(self.all_tokens.len()));
+        self.all_tokens.push(token_id);
+        self.add_token_to_owner_enumeration(to, token_id);
+`,
+          transfer: `
+        if from != to {
+            self.remove_token_from_owner_enumeration(from, token_id);
+            self.add_token_to_owner_enumeration(to, token_id);
+        }
+`,
+          burn: `        self.remove_token_from_owner_enumeration(owner, token_id);
+
+        // Same swap-and-pop as the owner lists, over the global token list
+        let token_index = self.all_tokens_index.get(token_id);
+        let last_token_id = self.all_tokens.pop().unwrap_or_default();
+        if last_token_id != token_id {
+            if let Some(mut slot) = self.all_tokens.setter(token_index) {
+                slot.set(last_token_id);
+            }
+            self.all_tokens_index.insert(last_token_id, token_index);
+        }
+        self.all_tokens_index.delete(token_id);
+`,
+          private: `
+    /// Appends the token to the owner's list. Must run before the owner's
+    /// balance is incremented, since the balance is the list's length.
+    fn add_token_to_owner_enumeration(&mut self, to: Address, token_id: U256) {
+        let length = self.balances.get(to);
+        self.owned_tokens.setter(to).insert(length, token_id);
+        self.owned_tokens_index.insert(token_id, length);
+    }
+
+    /// Moves the owner's last token into the removed token's slot and drops
+    /// the last slot. Must run before the owner's balance is decremented.
+    fn remove_token_from_owner_enumeration(&mut self, from: Address, token_id: U256) {
+        let last_index = self.balances.get(from) - U256::from(1);
+        let token_index = self.owned_tokens_index.get(token_id);
+
+        if token_index != last_index {
+            let last_token_id = self.owned_tokens.getter(from).get(last_index);
+            self.owned_tokens.setter(from).insert(token_index, last_token_id);
+            self.owned_tokens_index.insert(last_token_id, token_index);
+        }
+
+        self.owned_tokens.setter(from).delete(last_index);
+        self.owned_tokens_index.delete(token_id);
+    }
+`,
+          tests: `
+    #[test]
+    fn test_mint_updates_enumeration() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        assert!(contract.supports_interface(INTERFACE_ID_ERC721_ENUMERABLE));
+
+        contract.mint(ALICE).unwrap();
+        contract.mint(ALICE).unwrap();
+        assert_eq!(U256::from(2), contract.total_supply());
+        assert_eq!(U256::from(1), contract.token_by_index(U256::from(1)).unwrap());
+        assert_eq!(U256::ZERO, contract.token_of_owner_by_index(ALICE, U256::ZERO).unwrap());
+        assert_eq!(U256::from(1), contract.token_of_owner_by_index(ALICE, U256::from(1)).unwrap());
+    }
+
+    #[test]
+    fn test_transfer_updates_owner_enumeration() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        for _ in 0..3 {
+            contract.mint(ALICE).unwrap();
+        }
+
+        // moving the first token swaps the last one into its slot
+        vm.set_sender(ALICE);
+        contract.transfer_from(ALICE, BOB, U256::ZERO).unwrap();
+        assert_eq!(U256::from(2), contract.token_of_owner_by_index(ALICE, U256::ZERO).unwrap());
+        assert_eq!(U256::from(1), contract.token_of_owner_by_index(ALICE, U256::from(1)).unwrap());
+        assert_eq!(U256::ZERO, contract.token_of_owner_by_index(BOB, U256::ZERO).unwrap());
+
+        // the global list is unaffected by transfers
+        assert_eq!(U256::from(3), contract.total_supply());
+        assert_eq!(U256::ZERO, contract.token_by_index(U256::ZERO).unwrap());
+    }
+
+    #[test]
+    fn test_enumeration_rejects_out_of_bounds_index() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        contract.mint(ALICE).unwrap();
+
+        let err = contract.token_by_index(U256::from(1)).unwrap_err();
+        assert!(matches!(
+            err,
+            Erc721Error::ERC721OutOfBoundsIndex(ERC721OutOfBoundsIndex { owner, index })
+                if owner == Address::ZERO && index == U256::from(1)
+        ));
+
+        let err = contract.token_of_owner_by_index(ALICE, U256::from(1)).unwrap_err();
+        assert!(matches!(
+            err,
+            Erc721Error::ERC721OutOfBoundsIndex(ERC721OutOfBoundsIndex { owner, .. }) if owner == ALICE
+        ));
+
+        let err = contract.token_of_owner_by_index(BOB, U256::ZERO).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721OutOfBoundsIndex(_)));
+    }
+`,
+          'burn-tests': `
+    #[test]
+    fn test_burn_updates_enumeration() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        for _ in 0..3 {
+            contract.mint(ALICE).unwrap();
+        }
+
+        vm.set_sender(ALICE);
+        contract.burn(U256::ZERO).unwrap();
+
+        // both lists swap the last token into the burned token's slot
+        assert_eq!(U256::from(2), contract.total_supply());
+        assert_eq!(U256::from(2), contract.token_by_index(U256::ZERO).unwrap());
+        assert_eq!(U256::from(1), contract.token_by_index(U256::from(1)).unwrap());
+        assert_eq!(U256::from(2), contract.token_of_owner_by_index(ALICE, U256::ZERO).unwrap());
+
+        // burning the last token needs no swap
+        contract.burn(U256::from(1)).unwrap();
+        assert_eq!(U256::from(1), contract.total_supply());
+        assert_eq!(U256::from(2), contract.token_by_index(U256::ZERO).unwrap());
+    }
+`,
+        },
+        solidity: {
+          state: `
+    // Tokens of each owner by position, and each token's position in its
+    // owner's list. The pair allows O(1) swap-and-pop removal.
+    mapping(address => mapping(uint256 => uint256)) private ownedTokens;
+    mapping(uint256 => uint256) private ownedTokensIndex;
+
+    // Every existing token, and each token's position in allTokens
+    uint256[] private allTokens;
+    mapping(uint256 => uint256) private allTokensIndex;
+`,
+          errors: `    error ERC721OutOfBoundsIndex(address owner, uint256 index);
+`,
+          interfaces: `            interfaceId == 0x780e9d63 || // ERC-721 Enumerable
+`,
+          public: `
+    function totalSupply() public view returns (uint256) {
+        return allTokens.length;
+    }
+
+    function tokenByIndex(uint256 index) public view returns (uint256) {
+        if (index >= allTokens.length) {
+            revert ERC721OutOfBoundsIndex(address(0), index);
+        }
+        return allTokens[index];
+    }
+
+    function tokenOfOwnerByIndex(address owner_, uint256 index) public view returns (uint256) {
+        if (index >= balanceOf(owner_)) {
+            revert ERC721OutOfBoundsIndex(owner_, index);
+        }
+        return ownedTokens[owner_][index];
+    }
+`,
+          mint: `
+        allTokensIndex[tokenId] = allTokens.length;
+        allTokens.push(tokenId);
+        _addTokenToOwnerEnumeration(to, tokenId);
+`,
+          transfer: `
+        if (from != to) {
+            _removeTokenFromOwnerEnumeration(from, tokenId);
+            _addTokenToOwnerEnumeration(to, tokenId);
+        }
+`,
+          burn: `        _removeTokenFromOwnerEnumeration(tokenOwner, tokenId);
+
+        // Same swap-and-pop as the owner lists, over the global token list
+        uint256 tokenIndex = allTokensIndex[tokenId];
+        uint256 lastTokenId = allTokens[allTokens.length - 1];
+        allTokens.pop();
+        if (lastTokenId != tokenId) {
+            allTokens[tokenIndex] = lastTokenId;
+            allTokensIndex[lastTokenId] = tokenIndex;
+        }
+        delete allTokensIndex[tokenId];
+`,
+          internal: `
+    // Appends the token to the owner's list. Must run before the owner's
+    // balance is incremented, since the balance is the list's length.
+    function _addTokenToOwnerEnumeration(address to, uint256 tokenId) internal {
+        uint256 length = balances[to];
+        ownedTokens[to][length] = tokenId;
+        ownedTokensIndex[tokenId] = length;
+    }
+
+    // Moves the owner's last token into the removed token's slot and drops
+    // the last slot. Must run before the owner's balance is decremented.
+    function _removeTokenFromOwnerEnumeration(address from, uint256 tokenId) internal {
+        uint256 lastIndex = balances[from] - 1;
+        uint256 tokenIndex = ownedTokensIndex[tokenId];
+
+        if (tokenIndex != lastIndex) {
+            uint256 lastTokenId = ownedTokens[from][lastIndex];
+            ownedTokens[from][tokenIndex] = lastTokenId;
+            ownedTokensIndex[lastTokenId] = tokenIndex;
+        }
+
+        delete ownedTokens[from][lastIndex];
+        delete ownedTokensIndex[tokenId];
+    }
+`,
+        },
+      },
+      'uri-storage': {
+        description: 'Per-token metadata URIs (setTokenURI, ERC-4906 MetadataUpdate)',
+        rust: {
+          constants: `const INTERFACE_ID_ERC4906: FixedBytes<4> = FixedBytes::new([0x49, 0x06, 0x49, 0x06]);
+`,
+          storage: `    /// Per-token URIs, appended to base_uri when set
+    token_uris: StorageMap<U256, StorageString>,
+`,
+          sol: `    event MetadataUpdate(uint256 tokenId);
+`,
+          'token-uri': `
+        // A token's own URI replaces its id after base_uri
+        let token_uri = self.token_uris.getter(token_id).get_string();
+        if !token_uri.is_empty() {
+            return Ok(format!("{}{}", base_uri, token_uri));
+        }
+
+`,
+          interfaces: `            || interface_id == INTERFACE_ID_ERC4906
+`,
+          public: `
+    #[selector(name = "setTokenURI")]
+    pub fn set_token_uri(&mut self, token_id: U256, token_uri: String) -> Result<(), Erc721Error> {
+        self.only_owner()?;
+        self.require_owned(token_id)?;
+
+        self.token_uris.setter(token_id).set_str(&token_uri);
+        log(self.vm(), MetadataUpdate { tokenId: token_id });
+        Ok(())
+    }
+`,
+          burn: `        self.token_uris.delete(token_id);
+`,
+          tests: `
+    #[test]
+    fn test_set_token_uri() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        assert!(contract.supports_interface(INTERFACE_ID_ERC4906));
+        let token_id = contract.mint(ALICE).unwrap();
+
+        contract.set_token_uri(token_id, "token.json".into()).unwrap();
+        assert_eq!("ipfs://collection/token.json", contract.token_uri(token_id).unwrap());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, data) = logs.last().unwrap();
+        assert_eq!(MetadataUpdate::SIGNATURE_HASH, topics[0]);
+        assert_eq!(token_id.to_be_bytes::<32>().to_vec(), *data);
+
+        // without a base URI the token's URI is returned as is
+        contract.base_uri.set_str("");
+        assert_eq!("token.json", contract.token_uri(token_id).unwrap());
+
+        let err = contract.set_token_uri(U256::from(1), "x".into()).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721NonexistentToken(_)));
+
+        vm.set_sender(ALICE);
+        let err = contract.set_token_uri(token_id, "x".into()).unwrap_err();
+        assert!(matches!(err, Erc721Error::Unauthorized(_)));
+    }
+`,
+          'burn-tests': `
+    #[test]
+    fn test_burn_clears_token_uri() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let token_id = contract.mint(ALICE).unwrap();
+        contract.set_token_uri(token_id, "token.json".into()).unwrap();
+
+        vm.set_sender(ALICE);
+        contract.burn(token_id).unwrap();
+
+        // the URI of a burned token does not resurface
+        assert!(contract.token_uris.getter(token_id).get_string().is_empty());
+    }
+`,
+        },
+        solidity: {
+          state: `
+    // Per-token URIs, appended to _baseURI when set
+    mapping(uint256 => string) private tokenURIs;
+`,
+          events: `    event MetadataUpdate(uint256 tokenId);
+`,
+          'token-uri': `
+        // A token's own URI replaces its id after _baseURI
+        string memory uri = tokenURIs[tokenId];
+        if (bytes(uri).length > 0) {
+            return string.concat(_baseURI, uri);
+        }
+`,
+          interfaces: `            interfaceId == 0x49064906 || // ERC-4906
+`,
+          public: `
+    function setTokenURI(uint256 tokenId, string memory uri) public onlyOwner {
+        _requireOwned(tokenId);
+
+        tokenURIs[tokenId] = uri;
+        emit MetadataUpdate(tokenId);
+    }
+`,
+          burn: `        delete tokenURIs[tokenId];
+`,
+        },
+      },
+      burnable: {
+        description: 'Burnable (burn by the owner or an approved account)',
+        rust: {
+          public: `
+    /// Destroys the token. Only its owner or an approved account may burn it.
+    pub fn burn(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.require_owned(token_id)?;
+        let spender = self.vm().msg_sender();
+
+        if !self.is_authorized(owner, spender, token_id) {
+            return Err(Erc721Error::ERC721InsufficientApproval(
+                ERC721InsufficientApproval {
+                    operator: spender,
+                    tokenId: token_id,
+                },
+            ));
+        }
+
+        self.token_approvals.delete(token_id);
+        // @extension burn
+
+        // Cannot underflow: owner holds the token
+        let balance = self.balances.get(owner);
+        self.balances.insert(owner, balance - U256::from(1));
+        self.owners.delete(token_id);
+
+        log(
+            self.vm(),
+            Transfer {
+                from: owner,
+                to: Address::ZERO,
+                tokenId: token_id,
+            },
+        );
+        Ok(())
+    }
+`,
+          tests: `
+    #[test]
+    fn test_burn() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let token_id = contract.mint(ALICE).unwrap();
+
+        vm.set_sender(ALICE);
+        contract.burn(token_id).unwrap();
+
+        let err = contract.owner_of(token_id).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721NonexistentToken(_)));
+        assert_eq!(U256::ZERO, contract.balance_of(ALICE).unwrap());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Transfer::SIGNATURE_HASH, topics[0]);
+        assert_eq!(ALICE.into_word(), topics[1]);
+        assert_eq!(Address::ZERO.into_word(), topics[2]);
+    }
+
+    #[test]
+    fn test_burn_rejects_unauthorized() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let token_id = contract.mint(ALICE).unwrap();
+
+        vm.set_sender(BOB);
+        let err = contract.burn(token_id).unwrap_err();
+        assert!(matches!(err, Erc721Error::ERC721InsufficientApproval(_)));
+        assert_eq!(ALICE, contract.owner_of(token_id).unwrap());
+
+        vm.set_sender(ALICE);
+        contract.approve(BOB, token_id).unwrap();
+        vm.set_sender(BOB);
+        contract.burn(token_id).unwrap();
+        assert_eq!(U256::ZERO, contract.balance_of(ALICE).unwrap());
+    }
+    // @extension burn-tests
+`,
+        },
+        solidity: {
+          public: `
+    // Destroys the token. Only its owner or an approved account may burn it.
+    function burn(uint256 tokenId) public {
+        address tokenOwner = _requireOwned(tokenId);
+        if (!_isAuthorized(tokenOwner, msg.sender, tokenId)) {
+            revert ERC721InsufficientApproval(msg.sender, tokenId);
+        }
+
+        delete tokenApprovals[tokenId];
+        // @extension burn
+
+        unchecked {
+            balances[tokenOwner] -= 1;
+        }
+        delete owners[tokenId];
+
+        emit Transfer(tokenOwner, address(0), tokenId);
+    }
+`,
+        },
+      },
+    },
+  },
+  // Generated from the erc721 sources with these extensions always included
+  'erc721-enumerable': {
+    name: 'ERC721Enumerable',
+    base: 'erc721',
+    includes: ['enumerable', 'uri-storage', 'burnable'],
+  },
+  defi: {
+    name: 'LiquidityPool',
+    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::vec::Vec;
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    call::Call,
+    prelude::*,
+    storage::{StorageAddress, StorageMap, StorageU256},
+    stylus_core::log,
+};
+
+/// Shares burned on the first deposit, so the share price can never be pushed
+/// high enough to round later deposits down to zero shares
+const MINIMUM_LIQUIDITY: U256 = U256::from_limbs([1000, 0, 0, 0]);
+/// Swaps keep 0.3% of the input amount in the pool for liquidity providers
+const FEE_NUMERATOR: U256 = U256::from_limbs([997, 0, 0, 0]);
+const FEE_DENOMINATOR: U256 = U256::from_limbs([1000, 0, 0, 0]);
+
+sol_interface! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+}
+
+/// Constant-product (x * y = k) pool between two ERC-20 tokens. Reserves are
+/// tracked internally, so tokens sent to the pool directly are ignored.
+#[storage]
+#[entrypoint]
+pub struct LiquidityPool {
+    token0: StorageAddress,
+    token1: StorageAddress,
+    reserve0: StorageU256,
+    reserve1: StorageU256,
+    total_liquidity: StorageU256,
+    liquidity: StorageMap<Address, StorageU256>,
+}
+
+sol! {
+    #![sol(all_derives)]
+
+    event LiquidityAdded(address indexed provider, uint256 amount0, uint256 amount1, uint256 liquidity);
+    event LiquidityRemoved(address indexed provider, uint256 amount0, uint256 amount1, uint256 liquidity);
+    event Swap(address indexed trader, address indexed tokenIn, uint256 amountIn, uint256 amountOut);
+
+    error InvalidToken(address token);
+    error InsufficientInputAmount();
+    error InsufficientReserves();
+    error InsufficientLiquidity(address provider, uint256 liquidity, uint256 needed);
+    error InsufficientLiquidityMinted(uint256 liquidity, uint256 minLiquidity);
+    error InsufficientOutputAmount(uint256 amountOut, uint256 minAmountOut);
+    error TransferFailed(address token);
+    error Overflow();
+}
+
+#[derive(SolidityError, Debug)]
+pub enum PoolError {
+    InvalidToken(InvalidToken),
+    InsufficientInputAmount(InsufficientInputAmount),
+    InsufficientReserves(InsufficientReserves),
+    InsufficientLiquidity(InsufficientLiquidity),
+    InsufficientLiquidityMinted(InsufficientLiquidityMinted),
+    InsufficientOutputAmount(InsufficientOutputAmount),
+    TransferFailed(TransferFailed),
+    Overflow(Overflow),
+}
+
+#[public]
+impl LiquidityPool {
+    #[constructor]
+    pub fn constructor(&mut self, token0: Address, token1: Address) -> Result<(), PoolError> {
+        if token0 == Address::ZERO {
+            return Err(PoolError::InvalidToken(InvalidToken { token: token0 }));
+        }
+        if token1 == Address::ZERO || token1 == token0 {
+            return Err(PoolError::InvalidToken(InvalidToken { token: token1 }));
+        }
+
+        self.token0.set(token0);
+        self.token1.set(token1);
+        Ok(())
+    }
+
+    pub fn token0(&self) -> Address {
+        self.token0.get()
+    }
+
+    pub fn token1(&self) -> Address {
+        self.token1.get()
+    }
+
+    pub fn get_reserves(&self) -> (U256, U256) {
+        (self.reserve0.get(), self.reserve1.get())
     }
-}
 
-#[public]
-impl LiquidityPool {
     pub fn total_liquidity(&self) -> U256 {
         self.total_liquidity.get()
     }
 
-    pub fn add_liquidity(&mut self, amount: U256) {
-        let current = self.total_liquidity.get();
-        self.total_liquidity.set(current + amount);
+    pub fn get_liquidity(&self, provider: Address) -> U256 {
+        self.liquidity.get(provider)
+    }
+
+    /// Amount of the other token a swap of amount_in would return at the
+    /// current reserves, after the fee.
+    pub fn get_amount_out(&self, token_in: Address, amount_in: U256) -> Result<U256, PoolError> {
+        let (reserve_in, reserve_out) = self.reserves_for(token_in)?;
+        Self::amount_out(amount_in, reserve_in, reserve_out)
+    }
+
+    /// Deposits both tokens at the pool's current ratio and mints LP shares to
+    /// the caller. At most amount0 and amount1 are taken; the first deposit
+    /// sets the ratio. Reverts if fewer than min_liquidity shares are minted.
+    pub fn add_liquidity(
+        &mut self,
+        amount0: U256,
+        amount1: U256,
+        min_liquidity: U256,
+    ) -> Result<U256, PoolError> {
+        if amount0.is_zero() || amount1.is_zero() {
+            return Err(PoolError::InsufficientInputAmount(InsufficientInputAmount {}));
+        }
+
+        let (reserve0, reserve1) = self.get_reserves();
+        let total = self.total_liquidity.get();
+
+        let (amount0, amount1, minted, locked) = if total.is_zero() {
+            let root = isqrt(checked_mul(amount0, amount1)?);
+            let minted = root.saturating_sub(MINIMUM_LIQUIDITY);
+            (amount0, amount1, minted, MINIMUM_LIQUIDITY)
+        } else {
+            let amount1_optimal = mul_div(amount0, reserve1, reserve0)?;
+            let (amount0, amount1) = if amount1_optimal <= amount1 {
+                (amount0, amount1_optimal)
+            } else {
+                (mul_div(amount1, reserve0, reserve1)?, amount1)
+            };
+            let minted = mul_div(amount0, total, reserve0)?.min(mul_div(amount1, total, reserve1)?);
+            (amount0, amount1, minted, U256::ZERO)
+        };
+
+        if minted.is_zero() || minted < min_liquidity {
+            return Err(PoolError::InsufficientLiquidityMinted(
+                InsufficientLiquidityMinted {
+                    liquidity: minted,
+                    minLiquidity: min_liquidity,
+                },
+            ));
+        }
+
+        let provider = self.vm().msg_sender();
+        let (token0, token1) = (self.token0.get(), self.token1.get());
+        self.pull_tokens(token0, provider, amount0)?;
+        self.pull_tokens(token1, provider, amount1)?;
+
+        self.total_liquidity.set(checked_add(checked_add(total, locked)?, minted)?);
+        // A provider's shares are part of the total, so this cannot overflow
+        let balance = self.liquidity.get(provider);
+        self.liquidity.insert(provider, balance + minted);
+        self.reserve0.set(checked_add(reserve0, amount0)?);
+        self.reserve1.set(checked_add(reserve1, amount1)?);
+
+        log(
+            self.vm(),
+            LiquidityAdded {
+                provider,
+                amount0,
+                amount1,
+                liquidity: minted,
+            },
+        );
+        Ok(minted)
+    }
+
+    /// Burns the caller's LP shares and returns their part of both reserves.
+    /// Reverts if either amount is below its minimum.
+    pub fn remove_liquidity(
+        &mut self,
+        shares: U256,
+        min_amount0: U256,
+        min_amount1: U256,
+    ) -> Result<(U256, U256), PoolError> {
+        if shares.is_zero() {
+            return Err(PoolError::InsufficientInputAmount(InsufficientInputAmount {}));
+        }
+
+        let provider = self.vm().msg_sender();
+        let balance = self.liquidity.get(provider);
+        if balance < shares {
+            return Err(PoolError::InsufficientLiquidity(InsufficientLiquidity {
+                provider,
+                liquidity: balance,
+                needed: shares,
+            }));
+        }
+
+        let (reserve0, reserve1) = self.get_reserves();
+        let total = self.total_liquidity.get();
+        let amount0 = mul_div(shares, reserve0, total)?;
+        let amount1 = mul_div(shares, reserve1, total)?;
+
+        if amount0 < min_amount0 {
+            return Err(PoolError::InsufficientOutputAmount(InsufficientOutputAmount {
+                amountOut: amount0,
+                minAmountOut: min_amount0,
+            }));
+        }
+        if amount1 < min_amount1 {
+            return Err(PoolError::InsufficientOutputAmount(InsufficientOutputAmount {
+                amountOut: amount1,
+                minAmountOut: min_amount1,
+            }));
+        }
+
+        self.liquidity.insert(provider, balance - shares);
+        self.total_liquidity.set(total - shares);
+        self.reserve0.set(reserve0 - amount0);
+        self.reserve1.set(reserve1 - amount1);
+
+        let (token0, token1) = (self.token0.get(), self.token1.get());
+        self.push_tokens(token0, provider, amount0)?;
+        self.push_tokens(token1, provider, amount1)?;
+
+        log(
+            self.vm(),
+            LiquidityRemoved {
+                provider,
+                amount0,
+                amount1,
+                liquidity: shares,
+            },
+        );
+        Ok((amount0, amount1))
+    }
+
+    /// Sells amount_in of token_in for the other token. Reverts if the output
+    /// is below min_amount_out.
+    pub fn swap(
+        &mut self,
+        token_in: Address,
+        amount_in: U256,
+        min_amount_out: U256,
+    ) -> Result<U256, PoolError> {
+        let (reserve_in, reserve_out) = self.reserves_for(token_in)?;
+        let amount_out = Self::amount_out(amount_in, reserve_in, reserve_out)?;
+
+        if amount_out.is_zero() || amount_out < min_amount_out {
+            return Err(PoolError::InsufficientOutputAmount(InsufficientOutputAmount {
+                amountOut: amount_out,
+                minAmountOut: min_amount_out,
+            }));
+        }
+
+        let trader = self.vm().msg_sender();
+        let token0 = self.token0.get();
+        let token_out = if token_in == token0 {
+            self.token1.get()
+        } else {
+            token0
+        };
+
+        self.pull_tokens(token_in, trader, amount_in)?;
+
+        if token_in == token0 {
+            self.reserve0.set(checked_add(reserve_in, amount_in)?);
+            self.reserve1.set(reserve_out - amount_out);
+        } else {
+            self.reserve1.set(checked_add(reserve_in, amount_in)?);
+            self.reserve0.set(reserve_out - amount_out);
+        }
+
+        self.push_tokens(token_out, trader, amount_out)?;
+
+        log(
+            self.vm(),
+            Swap {
+                trader,
+                tokenIn: token_in,
+                amountIn: amount_in,
+                amountOut: amount_out,
+            },
+        );
+        Ok(amount_out)
+    }
+}
+
+impl LiquidityPool {
+    /// Returns (reserve_in, reserve_out) for a swap selling token_in
+    fn reserves_for(&self, token_in: Address) -> Result<(U256, U256), PoolError> {
+        let (reserve0, reserve1) = self.get_reserves();
+        if token_in == self.token0.get() {
+            Ok((reserve0, reserve1))
+        } else if token_in == self.token1.get() {
+            Ok((reserve1, reserve0))
+        } else {
+            Err(PoolError::InvalidToken(InvalidToken { token: token_in }))
+        }
+    }
+
+    /// out = in * 997 * reserve_out / (reserve_in * 1000 + in * 997), which
+    /// keeps reserve_in * reserve_out from decreasing
+    fn amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256) -> Result<U256, PoolError> {
+        if amount_in.is_zero() {
+            return Err(PoolError::InsufficientInputAmount(InsufficientInputAmount {}));
+        }
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            return Err(PoolError::InsufficientReserves(InsufficientReserves {}));
+        }
+
+        let amount_in_with_fee = checked_mul(amount_in, FEE_NUMERATOR)?;
+        let scaled_reserve_in = checked_mul(reserve_in, FEE_DENOMINATOR)?;
+        let denominator = checked_add(scaled_reserve_in, amount_in_with_fee)?;
+        mul_div(amount_in_with_fee, reserve_out, denominator)
+    }
+
+    fn pull_tokens(&mut self, token: Address, from: Address, amount: U256) -> Result<(), PoolError> {
+        let pool = self.vm().contract_address();
+        let erc20 = IERC20::new(token);
+        let call = Call::new_mutating(self);
+        match erc20.transfer_from(self.vm(), call, from, pool, amount) {
+            Ok(true) => Ok(()),
+            _ => Err(PoolError::TransferFailed(TransferFailed { token })),
+        }
+    }
+
+    fn push_tokens(&mut self, token: Address, to: Address, amount: U256) -> Result<(), PoolError> {
+        let erc20 = IERC20::new(token);
+        let call = Call::new_mutating(self);
+        match erc20.transfer(self.vm(), call, to, amount) {
+            Ok(true) => Ok(()),
+            _ => Err(PoolError::TransferFailed(TransferFailed { token })),
+        }
+    }
+}
+
+/// a * b / denominator, rounded down
+fn mul_div(a: U256, b: U256, denominator: U256) -> Result<U256, PoolError> {
+    Ok(checked_mul(a, b)? / denominator)
+}
+
+/// Sums and products revert with Overflow() instead of wrapping, the same
+/// error the Solidity pool uses
+fn checked_add(a: U256, b: U256) -> Result<U256, PoolError> {
+    a.checked_add(b).ok_or(PoolError::Overflow(Overflow {}))
+}
+
+fn checked_mul(a: U256, b: U256) -> Result<U256, PoolError> {
+    a.checked_mul(b).ok_or(PoolError::Overflow(Overflow {}))
+}
+
+/// Floor of the square root, by the Babylonian method
+fn isqrt(y: U256) -> U256 {
+    if y <= U256::from(3) {
+        return if y.is_zero() { U256::ZERO } else { U256::from(1) };
+    }
+
+    let mut z = y;
+    let mut x = y / U256::from(2) + U256::from(1);
+    while x < z {
+        z = x;
+        x = (y / x + x) / U256::from(2);
+    }
+    z
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_sol_types::{SolCall, SolEvent};
+    use stylus_sdk::testing::*;
+
+    const TOKEN0: Address = Address::repeat_byte(0x0a);
+    const TOKEN1: Address = Address::repeat_byte(0x0b);
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+
+    sol! {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    fn deploy(vm: &TestVM) -> LiquidityPool {
+        let mut contract = LiquidityPool::from(vm);
+        contract.constructor(TOKEN0, TOKEN1).unwrap();
+        contract
+    }
+
+    fn encode_bool(value: bool) -> Vec<u8> {
+        U256::from(value as u8).to_be_bytes::<32>().to_vec()
+    }
+
+    /// Makes token.transferFrom(from, pool, value) succeed
+    fn mock_pull(vm: &TestVM, pool: Address, token: Address, from: Address, value: U256) {
+        let data = transferFromCall { from, to: pool, value }.abi_encode();
+        vm.mock_call(token, data, U256::ZERO, Ok(encode_bool(true)));
+    }
+
+    /// Makes token.transfer(to, value) succeed
+    fn mock_push(vm: &TestVM, token: Address, to: Address, value: U256) {
+        let data = transferCall { to, value }.abi_encode();
+        vm.mock_call(token, data, U256::ZERO, Ok(encode_bool(true)));
+    }
+
+    /// Deploys a pool seeded by ALICE with 1,000,000 TOKEN0 and 4,000,000
+    /// TOKEN1, which mints 2,000,000 shares, 1,000 of them locked.
+    fn seeded(vm: &TestVM) -> LiquidityPool {
+        let mut contract = deploy(vm);
+        let pool = contract.vm().contract_address();
+        mock_pull(vm, pool, TOKEN0, ALICE, U256::from(1_000_000));
+        mock_pull(vm, pool, TOKEN1, ALICE, U256::from(4_000_000));
+
+        vm.set_sender(ALICE);
+        contract
+            .add_liquidity(U256::from(1_000_000), U256::from(4_000_000), U256::ZERO)
+            .unwrap();
+        contract
+    }
+
+    #[test]
+    fn test_constructor_rejects_invalid_tokens() {
+        let vm = TestVM::default();
+        let mut contract = LiquidityPool::from(&vm);
+
+        let err = contract.constructor(Address::ZERO, TOKEN1).unwrap_err();
+        assert!(matches!(err, PoolError::InvalidToken(_)));
+
+        let err = contract.constructor(TOKEN0, TOKEN0).unwrap_err();
+        assert!(matches!(
+            err,
+            PoolError::InvalidToken(InvalidToken { token }) if token == TOKEN0
+        ));
+    }
+
+    #[test]
+    fn test_isqrt() {
+        for (y, root) in [(0u64, 0u64), (1, 1), (3, 1), (4, 2), (15, 3), (16, 4), (17, 4)] {
+            assert_eq!(U256::from(root), isqrt(U256::from(y)));
+        }
+        assert_eq!(U256::from(10).pow(U256::from(18)), isqrt(U256::from(10).pow(U256::from(36))));
+        assert_eq!(U256::from(u128::MAX), isqrt(U256::MAX));
+    }
+
+    #[test]
+    fn test_add_liquidity_first_deposit() {
+        let vm = TestVM::default();
+        let contract = seeded(&vm);
+
+        assert_eq!(
+            (U256::from(1_000_000), U256::from(4_000_000)),
+            contract.get_reserves()
+        );
+        assert_eq!(U256::from(2_000_000), contract.total_liquidity());
+        assert_eq!(U256::from(1_999_000), contract.get_liquidity(ALICE));
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(LiquidityAdded::SIGNATURE_HASH, topics[0]);
+        assert_eq!(ALICE.into_word(), topics[1]);
+    }
+
+    #[test]
+    fn test_add_liquidity_uses_pool_ratio() {
+        let vm = TestVM::default();
+        let mut contract = seeded(&vm);
+        let pool = contract.vm().contract_address();
+
+        // only the 1:4 share of the TOKEN1 amount is taken
+        mock_pull(&vm, pool, TOKEN0, BOB, U256::from(100_000));
+        mock_pull(&vm, pool, TOKEN1, BOB, U256::from(400_000));
+
+        vm.set_sender(BOB);
+        let err = contract
+            .add_liquidity(U256::from(100_000), U256::from(1_000_000), U256::from(200_001))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PoolError::InsufficientLiquidityMinted(InsufficientLiquidityMinted { liquidity, .. })
+                if liquidity == U256::from(200_000)
+        ));
+
+        let minted = contract
+            .add_liquidity(U256::from(100_000), U256::from(1_000_000), U256::from(200_000))
+            .unwrap();
+        assert_eq!(U256::from(200_000), minted);
+        assert_eq!(U256::from(200_000), contract.get_liquidity(BOB));
+        assert_eq!(U256::from(2_200_000), contract.total_liquidity());
+        assert_eq!(
+            (U256::from(1_100_000), U256::from(4_400_000)),
+            contract.get_reserves()
+        );
+    }
+
+    #[test]
+    fn test_add_liquidity_rejects_empty_amounts() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        let err = contract
+            .add_liquidity(U256::ZERO, U256::from(1), U256::ZERO)
+            .unwrap_err();
+        assert!(matches!(err, PoolError::InsufficientInputAmount(_)));
+
+        // the first deposit must mint more than the locked shares
+        let err = contract
+            .add_liquidity(U256::from(1_000), U256::from(1_000), U256::ZERO)
+            .unwrap_err();
+        assert!(matches!(err, PoolError::InsufficientLiquidityMinted(_)));
+    }
+
+    #[test]
+    fn test_add_liquidity_rejects_overflow() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        let err = contract
+            .add_liquidity(U256::MAX, U256::from(2), U256::ZERO)
+            .unwrap_err();
+        assert!(matches!(err, PoolError::Overflow(_)));
+    }
+
+    #[test]
+    fn test_get_amount_out() {
+        let vm = TestVM::default();
+        let contract = seeded(&vm);
+
+        let amount_out = contract.get_amount_out(TOKEN0, U256::from(10_000)).unwrap();
+        assert_eq!(U256::from(39_486), amount_out);
+
+        let err = contract.get_amount_out(ALICE, U256::from(10_000)).unwrap_err();
+        assert!(matches!(err, PoolError::InvalidToken(_)));
+
+        let err = contract.get_amount_out(TOKEN1, U256::ZERO).unwrap_err();
+        assert!(matches!(err, PoolError::InsufficientInputAmount(_)));
+
+        let empty_vm = TestVM::default();
+        let empty = deploy(&empty_vm);
+        let err = empty.get_amount_out(TOKEN0, U256::from(10_000)).unwrap_err();
+        assert!(matches!(err, PoolError::InsufficientReserves(_)));
+    }
+
+    #[test]
+    fn test_swap() {
+        let vm = TestVM::default();
+        let mut contract = seeded(&vm);
+        let pool = contract.vm().contract_address();
+        let (reserve0, reserve1) = contract.get_reserves();
+
+        mock_pull(&vm, pool, TOKEN0, BOB, U256::from(10_000));
+        mock_push(&vm, TOKEN1, BOB, U256::from(39_486));
+
+        vm.set_sender(BOB);
+        let err = contract
+            .swap(TOKEN0, U256::from(10_000), U256::from(39_487))
+            .unwrap_err();
+        assert!(matches!(err, PoolError::InsufficientOutputAmount(_)));
+        assert_eq!((reserve0, reserve1), contract.get_reserves());
+
+        let amount_out = contract
+            .swap(TOKEN0, U256::from(10_000), U256::from(39_486))
+            .unwrap();
+        assert_eq!(U256::from(39_486), amount_out);
+
+        let (new_reserve0, new_reserve1) = contract.get_reserves();
+        assert_eq!(U256::from(1_010_000), new_reserve0);
+        assert_eq!(U256::from(3_960_514), new_reserve1);
+        // the fee stays in the pool, so k grows
+        assert!(new_reserve0 * new_reserve1 > reserve0 * reserve1);
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Swap::SIGNATURE_HASH, topics[0]);
+        assert_eq!(BOB.into_word(), topics[1]);
+        assert_eq!(TOKEN0.into_word(), topics[2]);
+    }
+
+    #[test]
+    fn test_swap_rejects_failed_transfer() {
+        let vm = TestVM::default();
+        let mut contract = seeded(&vm);
+        let pool = contract.vm().contract_address();
+
+        let data = transferFromCall {
+            from: BOB,
+            to: pool,
+            value: U256::from(10_000),
+        }
+        .abi_encode();
+        vm.mock_call(TOKEN0, data, U256::ZERO, Ok(encode_bool(false)));
+
+        vm.set_sender(BOB);
+        let err = contract
+            .swap(TOKEN0, U256::from(10_000), U256::ZERO)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PoolError::TransferFailed(TransferFailed { token }) if token == TOKEN0
+        ));
+    }
+
+    #[test]
+    fn test_remove_liquidity() {
+        let vm = TestVM::default();
+        let mut contract = seeded(&vm);
+
+        mock_push(&vm, TOKEN0, ALICE, U256::from(499_500));
+        mock_push(&vm, TOKEN1, ALICE, U256::from(1_998_000));
+
+        let err = contract
+            .remove_liquidity(U256::from(999_000), U256::ZERO, U256::from(1_998_001))
+            .unwrap_err();
+        assert!(matches!(err, PoolError::InsufficientOutputAmount(_)));
+
+        let amounts = contract
+            .remove_liquidity(U256::from(999_000), U256::from(499_500), U256::from(1_998_000))
+            .unwrap();
+        assert_eq!((U256::from(499_500), U256::from(1_998_000)), amounts);
+        assert_eq!(U256::from(1_000_000), contract.get_liquidity(ALICE));
+        assert_eq!(U256::from(1_001_000), contract.total_liquidity());
+        assert_eq!(
+            (U256::from(500_500), U256::from(2_002_000)),
+            contract.get_reserves()
+        );
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(LiquidityRemoved::SIGNATURE_HASH, topics[0]);
+    }
+
+    #[test]
+    fn test_remove_liquidity_rejects_excess_shares() {
+        let vm = TestVM::default();
+        let mut contract = seeded(&vm);
+
+        let err = contract
+            .remove_liquidity(U256::from(1_999_001), U256::ZERO, U256::ZERO)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PoolError::InsufficientLiquidity(InsufficientLiquidity { provider, liquidity, .. })
+                if provider == ALICE && liquidity == U256::from(1_999_000)
+        ));
+
+        let err = contract
+            .remove_liquidity(U256::ZERO, U256::ZERO, U256::ZERO)
+            .unwrap_err();
+        assert!(matches!(err, PoolError::InsufficientInputAmount(_)));
+    }
+
+    #[test]
+    fn test_liquidity_is_tracked_per_provider() {
+        let vm = TestVM::default();
+        let mut contract = seeded(&vm);
+        let pool = contract.vm().contract_address();
+
+        // a provider without shares cannot withdraw another provider's
+        vm.set_sender(BOB);
+        let err = contract
+            .remove_liquidity(U256::from(1), U256::ZERO, U256::ZERO)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PoolError::InsufficientLiquidity(InsufficientLiquidity { provider, liquidity, .. })
+                if provider == BOB && liquidity.is_zero()
+        ));
+
+        mock_pull(&vm, pool, TOKEN0, BOB, U256::from(100_000));
+        mock_pull(&vm, pool, TOKEN1, BOB, U256::from(400_000));
+        contract
+            .add_liquidity(U256::from(100_000), U256::from(400_000), U256::ZERO)
+            .unwrap();
+
+        let err = contract
+            .remove_liquidity(U256::from(200_001), U256::ZERO, U256::ZERO)
+            .unwrap_err();
+        assert!(matches!(err, PoolError::InsufficientLiquidity(_)));
+
+        mock_push(&vm, TOKEN0, BOB, U256::from(100_000));
+        mock_push(&vm, TOKEN1, BOB, U256::from(400_000));
+        let amounts = contract
+            .remove_liquidity(U256::from(200_000), U256::ZERO, U256::ZERO)
+            .unwrap();
+        assert_eq!((U256::from(100_000), U256::from(400_000)), amounts);
+
+        assert_eq!(U256::ZERO, contract.get_liquidity(BOB));
+        assert_eq!(U256::from(1_999_000), contract.get_liquidity(ALICE));
+        assert_eq!(U256::from(2_000_000), contract.total_liquidity());
+        assert_eq!(
+            (U256::from(1_000_000), U256::from(4_000_000)),
+            contract.get_reserves()
+        );
+    }
+}
+`,
+    solidity: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+interface IERC20 {
+    function transfer(address to, uint256 value) external returns (bool);
+    function transferFrom(address from, address to, uint256 value) external returns (bool);
+}
+
+// Constant-product (x * y = k) pool between two ERC-20 tokens. Reserves are
+// tracked internally, so tokens sent to the pool directly are ignored.
+contract LiquidityPool {
+    // Shares burned on the first deposit, so the share price can never be pushed
+    // high enough to round later deposits down to zero shares
+    uint256 private constant MINIMUM_LIQUIDITY = 1000;
+    // Swaps keep 0.3% of the input amount in the pool for liquidity providers
+    uint256 private constant FEE_NUMERATOR = 997;
+    uint256 private constant FEE_DENOMINATOR = 1000;
+
+    address public token0;
+    address public token1;
+    uint256 private reserve0;
+    uint256 private reserve1;
+    uint256 public totalLiquidity;
+    mapping(address => uint256) private liquidity;
+
+    event LiquidityAdded(address indexed provider, uint256 amount0, uint256 amount1, uint256 liquidity);
+    event LiquidityRemoved(address indexed provider, uint256 amount0, uint256 amount1, uint256 liquidity);
+    event Swap(address indexed trader, address indexed tokenIn, uint256 amountIn, uint256 amountOut);
+
+    error InvalidToken(address token);
+    error InsufficientInputAmount();
+    error InsufficientReserves();
+    error InsufficientLiquidity(address provider, uint256 liquidity, uint256 needed);
+    error InsufficientLiquidityMinted(uint256 liquidity, uint256 minLiquidity);
+    error InsufficientOutputAmount(uint256 amountOut, uint256 minAmountOut);
+    error TransferFailed(address token);
+    error Overflow();
+
+    constructor(address token0_, address token1_) {
+        if (token0_ == address(0)) {
+            revert InvalidToken(token0_);
+        }
+        if (token1_ == address(0) || token1_ == token0_) {
+            revert InvalidToken(token1_);
+        }
+
+        token0 = token0_;
+        token1 = token1_;
+    }
+
+    function getReserves() public view returns (uint256, uint256) {
+        return (reserve0, reserve1);
+    }
+
+    function getLiquidity(address provider) public view returns (uint256) {
+        return liquidity[provider];
+    }
+
+    // Amount of the other token a swap of amountIn would return at the
+    // current reserves, after the fee.
+    function getAmountOut(address tokenIn, uint256 amountIn) public view returns (uint256) {
+        (uint256 reserveIn, uint256 reserveOut) = _reservesFor(tokenIn);
+        return _amountOut(amountIn, reserveIn, reserveOut);
+    }
+
+    // Deposits both tokens at the pool's current ratio and mints LP shares to
+    // the caller. At most amount0 and amount1 are taken; the first deposit
+    // sets the ratio. Reverts if fewer than minLiquidity shares are minted.
+    function addLiquidity(
+        uint256 amount0,
+        uint256 amount1,
+        uint256 minLiquidity
+    ) public returns (uint256 minted) {
+        if (amount0 == 0 || amount1 == 0) {
+            revert InsufficientInputAmount();
+        }
+
+        uint256 total = totalLiquidity;
+        uint256 locked;
+
+        if (total == 0) {
+            uint256 root = _sqrt(_mul(amount0, amount1));
+            minted = root > MINIMUM_LIQUIDITY ? root - MINIMUM_LIQUIDITY : 0;
+            locked = MINIMUM_LIQUIDITY;
+        } else {
+            uint256 amount1Optimal = _mulDiv(amount0, reserve1, reserve0);
+            if (amount1Optimal <= amount1) {
+                amount1 = amount1Optimal;
+            } else {
+                amount0 = _mulDiv(amount1, reserve0, reserve1);
+            }
+            minted = _min(_mulDiv(amount0, total, reserve0), _mulDiv(amount1, total, reserve1));
+        }
+
+        if (minted == 0 || minted < minLiquidity) {
+            revert InsufficientLiquidityMinted(minted, minLiquidity);
+        }
+
+        _pullTokens(token0, msg.sender, amount0);
+        _pullTokens(token1, msg.sender, amount1);
+
+        totalLiquidity = _add(_add(total, locked), minted);
+        // A provider's shares are part of the total, so this cannot overflow
+        liquidity[msg.sender] += minted;
+        reserve0 = _add(reserve0, amount0);
+        reserve1 = _add(reserve1, amount1);
+
+        emit LiquidityAdded(msg.sender, amount0, amount1, minted);
+    }
+
+    // Burns the caller's LP shares and returns their part of both reserves.
+    // Reverts if either amount is below its minimum.
+    function removeLiquidity(
+        uint256 shares,
+        uint256 minAmount0,
+        uint256 minAmount1
+    ) public returns (uint256 amount0, uint256 amount1) {
+        if (shares == 0) {
+            revert InsufficientInputAmount();
+        }
+
+        uint256 balance = liquidity[msg.sender];
+        if (balance < shares) {
+            revert InsufficientLiquidity(msg.sender, balance, shares);
+        }
+
+        uint256 total = totalLiquidity;
+        amount0 = _mulDiv(shares, reserve0, total);
+        amount1 = _mulDiv(shares, reserve1, total);
+
+        if (amount0 < minAmount0) {
+            revert InsufficientOutputAmount(amount0, minAmount0);
+        }
+        if (amount1 < minAmount1) {
+            revert InsufficientOutputAmount(amount1, minAmount1);
+        }
+
+        liquidity[msg.sender] = balance - shares;
+        totalLiquidity = total - shares;
+        reserve0 -= amount0;
+        reserve1 -= amount1;
+
+        _pushTokens(token0, msg.sender, amount0);
+        _pushTokens(token1, msg.sender, amount1);
+
+        emit LiquidityRemoved(msg.sender, amount0, amount1, shares);
+    }
+
+    // Sells amountIn of tokenIn for the other token. Reverts if the output
+    // is below minAmountOut.
+    function swap(address tokenIn, uint256 amountIn, uint256 minAmountOut) public returns (uint256 amountOut) {
+        (uint256 reserveIn, uint256 reserveOut) = _reservesFor(tokenIn);
+        amountOut = _amountOut(amountIn, reserveIn, reserveOut);
+
+        if (amountOut == 0 || amountOut < minAmountOut) {
+            revert InsufficientOutputAmount(amountOut, minAmountOut);
+        }
+
+        address tokenOut = tokenIn == token0 ? token1 : token0;
+
+        _pullTokens(tokenIn, msg.sender, amountIn);
+
+        if (tokenIn == token0) {
+            reserve0 = _add(reserveIn, amountIn);
+            reserve1 = reserveOut - amountOut;
+        } else {
+            reserve1 = _add(reserveIn, amountIn);
+            reserve0 = reserveOut - amountOut;
+        }
+
+        _pushTokens(tokenOut, msg.sender, amountOut);
+
+        emit Swap(msg.sender, tokenIn, amountIn, amountOut);
+    }
+
+    // Returns (reserveIn, reserveOut) for a swap selling tokenIn
+    function _reservesFor(address tokenIn) internal view returns (uint256, uint256) {
+        if (tokenIn == token0) {
+            return (reserve0, reserve1);
+        }
+        if (tokenIn == token1) {
+            return (reserve1, reserve0);
+        }
+        revert InvalidToken(tokenIn);
+    }
+
+    // out = in * 997 * reserveOut / (reserveIn * 1000 + in * 997), which
+    // keeps reserveIn * reserveOut from decreasing
+    function _amountOut(uint256 amountIn, uint256 reserveIn, uint256 reserveOut) internal pure returns (uint256) {
+        if (amountIn == 0) {
+            revert InsufficientInputAmount();
+        }
+        if (reserveIn == 0 || reserveOut == 0) {
+            revert InsufficientReserves();
+        }
+
+        uint256 amountInWithFee = _mul(amountIn, FEE_NUMERATOR);
+        uint256 denominator = _add(_mul(reserveIn, FEE_DENOMINATOR), amountInWithFee);
+        return _mulDiv(amountInWithFee, reserveOut, denominator);
+    }
+
+    function _pullTokens(address token, address from, uint256 amount) internal {
+        if (!IERC20(token).transferFrom(from, address(this), amount)) {
+            revert TransferFailed(token);
+        }
+    }
+
+    function _pushTokens(address token, address to, uint256 amount) internal {
+        if (!IERC20(token).transfer(to, amount)) {
+            revert TransferFailed(token);
+        }
+    }
+
+    // a * b / denominator, rounded down
+    function _mulDiv(uint256 a, uint256 b, uint256 denominator) internal pure returns (uint256) {
+        return _mul(a, b) / denominator;
+    }
+
+    // Sums and products revert with Overflow() instead of the compiler's
+    // panic, the same error the Rust pool uses
+    function _add(uint256 a, uint256 b) internal pure returns (uint256) {
+        unchecked {
+            uint256 c = a + b;
+            if (c < a) {
+                revert Overflow();
+            }
+            return c;
+        }
+    }
+
+    function _mul(uint256 a, uint256 b) internal pure returns (uint256) {
+        if (a == 0) {
+            return 0;
+        }
+        unchecked {
+            uint256 c = a * b;
+            if (c / a != b) {
+                revert Overflow();
+            }
+            return c;
+        }
+    }
+
+    function _min(uint256 a, uint256 b) internal pure returns (uint256) {
+        return a < b ? a : b;
+    }
+
+    // Floor of the square root, by the Babylonian method
+    function _sqrt(uint256 y) internal pure returns (uint256 z) {
+        if (y > 3) {
+            z = y;
+            uint256 x = y / 2 + 1;
+            while (x < z) {
+                z = x;
+                x = (y / x + x) / 2;
+            }
+        } else if (y != 0) {
+            z = 1;
+        }
+    }
+}
+`,
+  },
+  staking: {
+    name: 'StakingRewards',
+    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::vec::Vec;
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    call::Call,
+    prelude::*,
+    storage::{StorageAddress, StorageMap, StorageU256},
+    stylus_core::log,
+};
+
+/// Fixed-point scale of reward_per_token (18 decimals)
+const PRECISION: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+sol_interface! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+}
+
+/// Synthetix-style staking: rewards are streamed at reward_rate per second
+/// until period_finish and shared between stakers in proportion to their
+/// stake. reward_per_token accumulates the reward earned by one staked token
+/// since deployment, so each account only needs a checkpoint of it.
+#[storage]
+#[entrypoint]
+pub struct StakingRewards {
+    staking_token: StorageAddress,
+    rewards_token: StorageAddress,
+    owner: StorageAddress,
+    period_finish: StorageU256,
+    reward_rate: StorageU256,
+    rewards_duration: StorageU256,
+    last_update_time: StorageU256,
+    reward_per_token_stored: StorageU256,
+    user_reward_per_token_paid: StorageMap<Address, StorageU256>,
+    rewards: StorageMap<Address, StorageU256>,
+    total_supply: StorageU256,
+    balances: StorageMap<Address, StorageU256>,
+}
+
+sol! {
+    #![sol(all_derives)]
+
+    event Staked(address indexed user, uint256 amount);
+    event Withdrawn(address indexed user, uint256 amount);
+    event RewardPaid(address indexed user, uint256 reward);
+    event RewardAdded(uint256 reward);
+    event RewardsDurationUpdated(uint256 newDuration);
+
+    error ZeroAmount();
+    error InsufficientBalance(address account, uint256 balance, uint256 needed);
+    error InvalidToken(address token);
+    error InvalidDuration(uint256 duration);
+    error InvalidRewardAmount(uint256 reward);
+    error RewardPeriodActive(uint256 periodFinish);
+    error TransferFailed(address token);
+    error Unauthorized(address account);
+    error Overflow();
+}
+
+#[derive(SolidityError, Debug)]
+pub enum StakingError {
+    ZeroAmount(ZeroAmount),
+    InsufficientBalance(InsufficientBalance),
+    InvalidToken(InvalidToken),
+    InvalidDuration(InvalidDuration),
+    InvalidRewardAmount(InvalidRewardAmount),
+    RewardPeriodActive(RewardPeriodActive),
+    TransferFailed(TransferFailed),
+    Unauthorized(Unauthorized),
+    Overflow(Overflow),
+}
+
+#[public]
+impl StakingRewards {
+    /// Makes the deploying account the owner, who funds reward periods.
+    #[constructor]
+    pub fn constructor(
+        &mut self,
+        staking_token: Address,
+        rewards_token: Address,
+        rewards_duration: U256,
+    ) -> Result<(), StakingError> {
+        if staking_token == Address::ZERO {
+            return Err(StakingError::InvalidToken(InvalidToken { token: staking_token }));
+        }
+        if rewards_token == Address::ZERO {
+            return Err(StakingError::InvalidToken(InvalidToken { token: rewards_token }));
+        }
+        if rewards_duration.is_zero() {
+            return Err(StakingError::InvalidDuration(InvalidDuration {
+                duration: rewards_duration,
+            }));
+        }
+
+        self.staking_token.set(staking_token);
+        self.rewards_token.set(rewards_token);
+        self.rewards_duration.set(rewards_duration);
+        let deployer = self.vm().tx_origin();
+        self.owner.set(deployer);
+        Ok(())
+    }
+
+    pub fn staking_token(&self) -> Address {
+        self.staking_token.get()
+    }
+
+    pub fn rewards_token(&self) -> Address {
+        self.rewards_token.get()
+    }
+
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get()
+    }
+
+    pub fn balance_of(&self, account: Address) -> U256 {
+        self.balances.get(account)
+    }
+
+    pub fn period_finish(&self) -> U256 {
+        self.period_finish.get()
+    }
+
+    pub fn reward_rate(&self) -> U256 {
+        self.reward_rate.get()
+    }
+
+    pub fn rewards_duration(&self) -> U256 {
+        self.rewards_duration.get()
+    }
+
+    pub fn last_time_reward_applicable(&self) -> U256 {
+        self.now().min(self.period_finish.get())
+    }
+
+    /// Reward earned by one staked token (scaled by 1e18) since deployment
+    pub fn reward_per_token(&self) -> Result<U256, StakingError> {
+        let total_supply = self.total_supply.get();
+        let stored = self.reward_per_token_stored.get();
+        if total_supply.is_zero() {
+            return Ok(stored);
+        }
+
+        let elapsed = self.last_time_reward_applicable() - self.last_update_time.get();
+        let streamed = checked_mul(checked_mul(elapsed, self.reward_rate.get())?, PRECISION)?;
+        checked_add(stored, streamed / total_supply)
+    }
+
+    /// Rewards the account can claim now
+    pub fn earned(&self, account: Address) -> Result<U256, StakingError> {
+        let accrued = self.reward_per_token()? - self.user_reward_per_token_paid.get(account);
+        let pending = checked_mul(self.balances.get(account), accrued)? / PRECISION;
+        checked_add(pending, self.rewards.get(account))
+    }
+
+    pub fn get_reward_for_duration(&self) -> Result<U256, StakingError> {
+        checked_mul(self.reward_rate.get(), self.rewards_duration.get())
+    }
+
+    pub fn stake(&mut self, amount: U256) -> Result<(), StakingError> {
+        // Rewards are settled first, like the Solidity updateReward modifier
+        let user = self.vm().msg_sender();
+        self.update_reward(user)?;
+
+        if amount.is_zero() {
+            return Err(StakingError::ZeroAmount(ZeroAmount {}));
+        }
+
+        let staking_token = self.staking_token.get();
+        self.pull_tokens(staking_token, user, amount)?;
+
+        self.total_supply.set(checked_add(self.total_supply.get(), amount)?);
+        // An account's stake is part of the total, so this cannot overflow
+        let balance = self.balances.get(user);
+        self.balances.insert(user, balance + amount);
+
+        log(self.vm(), Staked { user, amount });
+        Ok(())
+    }
+
+    pub fn withdraw(&mut self, amount: U256) -> Result<(), StakingError> {
+        let user = self.vm().msg_sender();
+        self.update_reward(user)?;
+
+        if amount.is_zero() {
+            return Err(StakingError::ZeroAmount(ZeroAmount {}));
+        }
+
+        let balance = self.balances.get(user);
+        if balance < amount {
+            return Err(StakingError::InsufficientBalance(InsufficientBalance {
+                account: user,
+                balance,
+                needed: amount,
+            }));
+        }
+
+        self.total_supply.set(self.total_supply.get() - amount);
+        self.balances.insert(user, balance - amount);
+
+        let staking_token = self.staking_token.get();
+        self.push_tokens(staking_token, user, amount)?;
+
+        log(self.vm(), Withdrawn { user, amount });
+        Ok(())
+    }
+
+    pub fn get_reward(&mut self) -> Result<(), StakingError> {
+        let user = self.vm().msg_sender();
+        self.update_reward(user)?;
+
+        let reward = self.rewards.get(user);
+        if reward.is_zero() {
+            return Ok(());
+        }
+
+        self.rewards.insert(user, U256::ZERO);
+        let rewards_token = self.rewards_token.get();
+        self.push_tokens(rewards_token, user, reward)?;
+
+        log(self.vm(), RewardPaid { user, reward });
+        Ok(())
+    }
+
+    /// Withdraws the caller's whole stake and claims their rewards
+    pub fn exit(&mut self) -> Result<(), StakingError> {
+        let balance = self.balances.get(self.vm().msg_sender());
+        self.withdraw(balance)?;
+        self.get_reward()
+    }
+
+    /// Pulls reward from the owner and streams it over the next
+    /// rewards_duration seconds. Rewards left from a running period are
+    /// rolled into the new one.
+    pub fn notify_reward_amount(&mut self, reward: U256) -> Result<(), StakingError> {
+        self.only_owner()?;
+
+        let now = self.now();
+        let duration = self.rewards_duration.get();
+        let period_finish = self.period_finish.get();
+
+        let total = if now >= period_finish {
+            reward
+        } else {
+            let leftover = checked_mul(period_finish - now, self.reward_rate.get())?;
+            checked_add(reward, leftover)?
+        };
+        let reward_rate = total / duration;
+        if reward_rate.is_zero() {
+            return Err(StakingError::InvalidRewardAmount(InvalidRewardAmount { reward }));
+        }
+
+        let owner = self.owner.get();
+        let rewards_token = self.rewards_token.get();
+        self.pull_tokens(rewards_token, owner, reward)?;
+
+        self.update_reward(Address::ZERO)?;
+        self.reward_rate.set(reward_rate);
+        self.last_update_time.set(now);
+        self.period_finish.set(checked_add(now, duration)?);
+
+        log(self.vm(), RewardAdded { reward });
+        Ok(())
+    }
+
+    /// Changes the length of future reward periods. Not allowed while a
+    /// period is running, since it would change the current rate.
+    pub fn set_rewards_duration(&mut self, rewards_duration: U256) -> Result<(), StakingError> {
+        self.only_owner()?;
+
+        let period_finish = self.period_finish.get();
+        if self.now() <= period_finish {
+            return Err(StakingError::RewardPeriodActive(RewardPeriodActive {
+                periodFinish: period_finish,
+            }));
+        }
+        if rewards_duration.is_zero() {
+            return Err(StakingError::InvalidDuration(InvalidDuration {
+                duration: rewards_duration,
+            }));
+        }
+
+        self.rewards_duration.set(rewards_duration);
+        log(
+            self.vm(),
+            RewardsDurationUpdated {
+                newDuration: rewards_duration,
+            },
+        );
+        Ok(())
+    }
+}
+
+impl StakingRewards {
+    fn only_owner(&self) -> Result<(), StakingError> {
+        let sender = self.vm().msg_sender();
+        if sender != self.owner.get() {
+            return Err(StakingError::Unauthorized(Unauthorized { account: sender }));
+        }
+        Ok(())
+    }
+
+    fn now(&self) -> U256 {
+        U256::from(self.vm().block_timestamp())
+    }
+
+    /// Checkpoints the accumulator, and the account's rewards unless account
+    /// is the zero address. Must run before any balance or rate change.
+    fn update_reward(&mut self, account: Address) -> Result<(), StakingError> {
+        let reward_per_token = self.reward_per_token()?;
+        self.reward_per_token_stored.set(reward_per_token);
+        self.last_update_time.set(self.last_time_reward_applicable());
+
+        if account != Address::ZERO {
+            let earned = self.earned(account)?;
+            self.rewards.insert(account, earned);
+            self.user_reward_per_token_paid.insert(account, reward_per_token);
+        }
+        Ok(())
+    }
+
+    fn pull_tokens(&mut self, token: Address, from: Address, amount: U256) -> Result<(), StakingError> {
+        let contract = self.vm().contract_address();
+        let erc20 = IERC20::new(token);
+        let call = Call::new_mutating(self);
+        match erc20.transfer_from(self.vm(), call, from, contract, amount) {
+            Ok(true) => Ok(()),
+            _ => Err(StakingError::TransferFailed(TransferFailed { token })),
+        }
+    }
+
+    fn push_tokens(&mut self, token: Address, to: Address, amount: U256) -> Result<(), StakingError> {
+        let erc20 = IERC20::new(token);
+        let call = Call::new_mutating(self);
+        match erc20.transfer(self.vm(), call, to, amount) {
+            Ok(true) => Ok(()),
+            _ => Err(StakingError::TransferFailed(TransferFailed { token })),
+        }
+    }
+}
+
+/// Sums and products revert with Overflow() instead of wrapping, the same
+/// error the Solidity contract uses
+fn checked_add(a: U256, b: U256) -> Result<U256, StakingError> {
+    a.checked_add(b).ok_or(StakingError::Overflow(Overflow {}))
+}
+
+fn checked_mul(a: U256, b: U256) -> Result<U256, StakingError> {
+    a.checked_mul(b).ok_or(StakingError::Overflow(Overflow {}))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_sol_types::{SolCall, SolEvent};
+    use stylus_sdk::testing::*;
+
+    const STAKING_TOKEN: Address = Address::repeat_byte(0x0a);
+    const REWARDS_TOKEN: Address = Address::repeat_byte(0x0b);
+    const OWNER: Address = Address::repeat_byte(0x11);
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+    const DURATION: u64 = 1_000;
+
+    sol! {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    /// Initializes the pool the way the constructor does, with OWNER as the
+    /// deployer and the caller.
+    fn deploy(vm: &TestVM) -> StakingRewards {
+        let mut contract = StakingRewards::from(vm);
+        contract.staking_token.set(STAKING_TOKEN);
+        contract.rewards_token.set(REWARDS_TOKEN);
+        contract.rewards_duration.set(U256::from(DURATION));
+        contract.owner.set(OWNER);
+        vm.set_sender(OWNER);
+        vm.set_block_timestamp(1_000);
+        contract
+    }
+
+    fn encode_true() -> Vec<u8> {
+        U256::from(1).to_be_bytes::<32>().to_vec()
+    }
+
+    /// Makes token.transferFrom(from, contract, value) succeed
+    fn mock_pull(vm: &TestVM, contract: Address, token: Address, from: Address, value: u64) {
+        let value = U256::from(value);
+        let data = transferFromCall { from, to: contract, value }.abi_encode();
+        vm.mock_call(token, data, U256::ZERO, Ok(encode_true()));
+    }
+
+    /// Makes token.transfer(to, value) succeed
+    fn mock_push(vm: &TestVM, token: Address, to: Address, value: u64) {
+        let value = U256::from(value);
+        let data = transferCall { to, value }.abi_encode();
+        vm.mock_call(token, data, U256::ZERO, Ok(encode_true()));
+    }
+
+    fn stake(vm: &TestVM, contract: &mut StakingRewards, user: Address, amount: u64) {
+        let address = contract.vm().contract_address();
+        mock_pull(vm, address, STAKING_TOKEN, user, amount);
+        vm.set_sender(user);
+        contract.stake(U256::from(amount)).unwrap();
+    }
+
+    fn notify(vm: &TestVM, contract: &mut StakingRewards, reward: u64) {
+        let address = contract.vm().contract_address();
+        mock_pull(vm, address, REWARDS_TOKEN, OWNER, reward);
+        vm.set_sender(OWNER);
+        contract.notify_reward_amount(U256::from(reward)).unwrap();
+    }
+
+    #[test]
+    fn test_stake_and_withdraw() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        stake(&vm, &mut contract, ALICE, 100);
+        assert_eq!(U256::from(100), contract.balance_of(ALICE));
+        assert_eq!(U256::from(100), contract.total_supply());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Staked::SIGNATURE_HASH, topics[0]);
+        assert_eq!(ALICE.into_word(), topics[1]);
+
+        mock_push(&vm, STAKING_TOKEN, ALICE, 40);
+        contract.withdraw(U256::from(40)).unwrap();
+        assert_eq!(U256::from(60), contract.balance_of(ALICE));
+        assert_eq!(U256::from(60), contract.total_supply());
+
+        let err = contract.withdraw(U256::from(61)).unwrap_err();
+        assert!(matches!(
+            err,
+            StakingError::InsufficientBalance(InsufficientBalance { balance, .. })
+                if balance == U256::from(60)
+        ));
+
+        let err = contract.stake(U256::ZERO).unwrap_err();
+        assert!(matches!(err, StakingError::ZeroAmount(_)));
+    }
+
+    #[test]
+    fn test_rewards_are_shared_by_stake() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        stake(&vm, &mut contract, ALICE, 100);
+        notify(&vm, &mut contract, 1_000_000);
+        assert_eq!(U256::from(1_000), contract.reward_rate());
+        assert_eq!(U256::from(2_000), contract.period_finish());
+        assert_eq!(U256::from(1_000_000), contract.get_reward_for_duration().unwrap());
+
+        // ALICE alone earns the first half of the period
+        vm.set_block_timestamp(1_500);
+        assert_eq!(U256::from(500_000), contract.earned(ALICE).unwrap());
+        assert_eq!(U256::from(5_000) * PRECISION, contract.reward_per_token().unwrap());
+
+        // then shares the second half 1:3 with BOB
+        stake(&vm, &mut contract, BOB, 300);
+        vm.set_block_timestamp(2_000);
+        assert_eq!(U256::from(625_000), contract.earned(ALICE).unwrap());
+        assert_eq!(U256::from(375_000), contract.earned(BOB).unwrap());
+
+        // nothing accrues after the period ends
+        vm.set_block_timestamp(3_000);
+        assert_eq!(U256::from(2_000), contract.last_time_reward_applicable());
+        assert_eq!(U256::from(625_000), contract.earned(ALICE).unwrap());
+    }
+
+    #[test]
+    fn test_get_reward() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        stake(&vm, &mut contract, ALICE, 100);
+        notify(&vm, &mut contract, 1_000_000);
+        vm.set_block_timestamp(1_250);
+
+        mock_push(&vm, REWARDS_TOKEN, ALICE, 250_000);
+        vm.set_sender(ALICE);
+        contract.get_reward().unwrap();
+        assert_eq!(U256::ZERO, contract.earned(ALICE).unwrap());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, data) = logs.last().unwrap();
+        assert_eq!(RewardPaid::SIGNATURE_HASH, topics[0]);
+        assert_eq!(U256::from(250_000).to_be_bytes::<32>().to_vec(), *data);
+
+        // rewards keep accruing from the claim onwards
+        vm.set_block_timestamp(1_500);
+        assert_eq!(U256::from(250_000), contract.earned(ALICE).unwrap());
+    }
+
+    #[test]
+    fn test_exit() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        stake(&vm, &mut contract, ALICE, 100);
+        notify(&vm, &mut contract, 1_000_000);
+        vm.set_block_timestamp(2_000);
+
+        mock_push(&vm, STAKING_TOKEN, ALICE, 100);
+        mock_push(&vm, REWARDS_TOKEN, ALICE, 1_000_000);
+        vm.set_sender(ALICE);
+        contract.exit().unwrap();
+
+        assert_eq!(U256::ZERO, contract.balance_of(ALICE));
+        assert_eq!(U256::ZERO, contract.total_supply());
+        assert_eq!(U256::ZERO, contract.earned(ALICE).unwrap());
+    }
+
+    #[test]
+    fn test_notify_reward_amount_rolls_over_leftover() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        stake(&vm, &mut contract, ALICE, 100);
+        notify(&vm, &mut contract, 1_000_000);
+
+        // half of the first reward is left and joins the new one
+        vm.set_block_timestamp(1_500);
+        notify(&vm, &mut contract, 500_000);
+        assert_eq!(U256::from(1_000), contract.reward_rate());
+        assert_eq!(U256::from(2_500), contract.period_finish());
+        assert_eq!(U256::from(500_000), contract.earned(ALICE).unwrap());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(RewardAdded::SIGNATURE_HASH, topics[0]);
+    }
+
+    #[test]
+    fn test_notify_reward_amount_rejects_invalid_calls() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        vm.set_sender(ALICE);
+        let err = contract.notify_reward_amount(U256::from(1_000_000)).unwrap_err();
+        assert!(matches!(
+            err,
+            StakingError::Unauthorized(Unauthorized { account }) if account == ALICE
+        ));
+
+        // less than one token per second rounds the rate down to zero
+        vm.set_sender(OWNER);
+        let err = contract.notify_reward_amount(U256::from(999)).unwrap_err();
+        assert!(matches!(err, StakingError::InvalidRewardAmount(_)));
+        assert_eq!(U256::ZERO, contract.reward_rate());
+    }
+
+    #[test]
+    fn test_reward_overflow_reverts() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        stake(&vm, &mut contract, ALICE, 100);
+
+        // a rate this high overflows the accumulator after one second
+        contract.reward_rate.set(U256::MAX / U256::from(DURATION));
+        contract.last_update_time.set(U256::from(1_000));
+        contract.period_finish.set(U256::from(2_000));
+        vm.set_block_timestamp(1_001);
+
+        let err = contract.reward_per_token().unwrap_err();
+        assert!(matches!(err, StakingError::Overflow(_)));
+        let err = contract.earned(ALICE).unwrap_err();
+        assert!(matches!(err, StakingError::Overflow(_)));
+
+        vm.set_sender(ALICE);
+        let err = contract.withdraw(U256::from(100)).unwrap_err();
+        assert!(matches!(err, StakingError::Overflow(_)));
+        assert_eq!(U256::from(100), contract.balance_of(ALICE));
+    }
+
+    #[test]
+    fn test_set_rewards_duration() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        notify(&vm, &mut contract, 1_000_000);
+
+        let err = contract.set_rewards_duration(U256::from(5_000)).unwrap_err();
+        assert!(matches!(
+            err,
+            StakingError::RewardPeriodActive(RewardPeriodActive { periodFinish: finish })
+                if finish == U256::from(2_000)
+        ));
+
+        vm.set_block_timestamp(2_001);
+        let err = contract.set_rewards_duration(U256::ZERO).unwrap_err();
+        assert!(matches!(err, StakingError::InvalidDuration(_)));
+
+        contract.set_rewards_duration(U256::from(5_000)).unwrap();
+        assert_eq!(U256::from(5_000), contract.rewards_duration());
+    }
+}
+`,
+    solidity: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+interface IERC20 {
+    function transfer(address to, uint256 value) external returns (bool);
+    function transferFrom(address from, address to, uint256 value) external returns (bool);
+}
+
+// Synthetix-style staking: rewards are streamed at rewardRate per second
+// until periodFinish and shared between stakers in proportion to their
+// stake. rewardPerToken accumulates the reward earned by one staked token
+// since deployment, so each account only needs a checkpoint of it.
+contract StakingRewards {
+    // Fixed-point scale of rewardPerToken (18 decimals)
+    uint256 private constant PRECISION = 1e18;
+
+    address public stakingToken;
+    address public rewardsToken;
+    address private _owner;
+
+    uint256 public periodFinish;
+    uint256 public rewardRate;
+    uint256 public rewardsDuration;
+    uint256 private lastUpdateTime;
+    uint256 private rewardPerTokenStored;
+
+    mapping(address => uint256) private userRewardPerTokenPaid;
+    mapping(address => uint256) private rewards;
+
+    uint256 private _totalSupply;
+    mapping(address => uint256) private balances;
+
+    event Staked(address indexed user, uint256 amount);
+    event Withdrawn(address indexed user, uint256 amount);
+    event RewardPaid(address indexed user, uint256 reward);
+    event RewardAdded(uint256 reward);
+    event RewardsDurationUpdated(uint256 newDuration);
+
+    error ZeroAmount();
+    error InsufficientBalance(address account, uint256 balance, uint256 needed);
+    error InvalidToken(address token);
+    error InvalidDuration(uint256 duration);
+    error InvalidRewardAmount(uint256 reward);
+    error RewardPeriodActive(uint256 periodFinish);
+    error TransferFailed(address token);
+    error Unauthorized(address account);
+    error Overflow();
+
+    modifier onlyOwner() {
+        if (msg.sender != _owner) {
+            revert Unauthorized(msg.sender);
+        }
+        _;
+    }
+
+    // Checkpoints the accumulator, and the account's rewards unless account
+    // is the zero address. Must run before any balance or rate change.
+    modifier updateReward(address account) {
+        rewardPerTokenStored = rewardPerToken();
+        lastUpdateTime = lastTimeRewardApplicable();
+
+        if (account != address(0)) {
+            rewards[account] = earned(account);
+            userRewardPerTokenPaid[account] = rewardPerTokenStored;
+        }
+        _;
+    }
+
+    constructor(address stakingToken_, address rewardsToken_, uint256 rewardsDuration_) {
+        if (stakingToken_ == address(0)) {
+            revert InvalidToken(stakingToken_);
+        }
+        if (rewardsToken_ == address(0)) {
+            revert InvalidToken(rewardsToken_);
+        }
+        if (rewardsDuration_ == 0) {
+            revert InvalidDuration(rewardsDuration_);
+        }
+
+        stakingToken = stakingToken_;
+        rewardsToken = rewardsToken_;
+        rewardsDuration = rewardsDuration_;
+        _owner = msg.sender;
+    }
+
+    function owner() public view returns (address) {
+        return _owner;
+    }
+
+    function totalSupply() public view returns (uint256) {
+        return _totalSupply;
+    }
+
+    function balanceOf(address account) public view returns (uint256) {
+        return balances[account];
+    }
+
+    function lastTimeRewardApplicable() public view returns (uint256) {
+        return block.timestamp < periodFinish ? block.timestamp : periodFinish;
+    }
+
+    // Reward earned by one staked token (scaled by 1e18) since deployment
+    function rewardPerToken() public view returns (uint256) {
+        if (_totalSupply == 0) {
+            return rewardPerTokenStored;
+        }
+
+        uint256 elapsed = lastTimeRewardApplicable() - lastUpdateTime;
+        return _add(rewardPerTokenStored, _mul(_mul(elapsed, rewardRate), PRECISION) / _totalSupply);
+    }
+
+    // Rewards the account can claim now
+    function earned(address account) public view returns (uint256) {
+        uint256 accrued = rewardPerToken() - userRewardPerTokenPaid[account];
+        return _add(_mul(balances[account], accrued) / PRECISION, rewards[account]);
+    }
+
+    function getRewardForDuration() public view returns (uint256) {
+        return _mul(rewardRate, rewardsDuration);
+    }
+
+    function stake(uint256 amount) public updateReward(msg.sender) {
+        if (amount == 0) {
+            revert ZeroAmount();
+        }
+
+        _pullTokens(stakingToken, msg.sender, amount);
+
+        _totalSupply = _add(_totalSupply, amount);
+        // An account's stake is part of the total, so this cannot overflow
+        balances[msg.sender] += amount;
+
+        emit Staked(msg.sender, amount);
+    }
+
+    function withdraw(uint256 amount) public updateReward(msg.sender) {
+        if (amount == 0) {
+            revert ZeroAmount();
+        }
+
+        uint256 balance = balances[msg.sender];
+        if (balance < amount) {
+            revert InsufficientBalance(msg.sender, balance, amount);
+        }
+
+        _totalSupply -= amount;
+        balances[msg.sender] = balance - amount;
+
+        _pushTokens(stakingToken, msg.sender, amount);
+
+        emit Withdrawn(msg.sender, amount);
+    }
+
+    function getReward() public updateReward(msg.sender) {
+        uint256 reward = rewards[msg.sender];
+        if (reward == 0) {
+            return;
+        }
+
+        rewards[msg.sender] = 0;
+        _pushTokens(rewardsToken, msg.sender, reward);
+
+        emit RewardPaid(msg.sender, reward);
+    }
+
+    // Withdraws the caller's whole stake and claims their rewards
+    function exit() public {
+        withdraw(balances[msg.sender]);
+        getReward();
+    }
+
+    // Pulls reward from the owner and streams it over the next rewardsDuration
+    // seconds. Rewards left from a running period are rolled into the new one.
+    function notifyRewardAmount(uint256 reward) public onlyOwner updateReward(address(0)) {
+        uint256 total = reward;
+        if (block.timestamp < periodFinish) {
+            total = _add(total, _mul(periodFinish - block.timestamp, rewardRate));
+        }
+
+        uint256 newRate = total / rewardsDuration;
+        if (newRate == 0) {
+            revert InvalidRewardAmount(reward);
+        }
+
+        _pullTokens(rewardsToken, _owner, reward);
+
+        rewardRate = newRate;
+        lastUpdateTime = block.timestamp;
+        periodFinish = _add(block.timestamp, rewardsDuration);
+
+        emit RewardAdded(reward);
+    }
+
+    // Changes the length of future reward periods. Not allowed while a period
+    // is running, since it would change the current rate.
+    function setRewardsDuration(uint256 rewardsDuration_) public onlyOwner {
+        if (block.timestamp <= periodFinish) {
+            revert RewardPeriodActive(periodFinish);
+        }
+        if (rewardsDuration_ == 0) {
+            revert InvalidDuration(rewardsDuration_);
+        }
+
+        rewardsDuration = rewardsDuration_;
+        emit RewardsDurationUpdated(rewardsDuration_);
+    }
+
+    // Sums and products revert with Overflow() instead of the compiler's
+    // panic, the same error the Rust contract uses
+    function _add(uint256 a, uint256 b) internal pure returns (uint256) {
+        unchecked {
+            uint256 c = a + b;
+            if (c < a) {
+                revert Overflow();
+            }
+            return c;
+        }
+    }
+
+    function _mul(uint256 a, uint256 b) internal pure returns (uint256) {
+        if (a == 0) {
+            return 0;
+        }
+        unchecked {
+            uint256 c = a * b;
+            if (c / a != b) {
+                revert Overflow();
+            }
+            return c;
+        }
+    }
+
+    function _pullTokens(address token, address from, uint256 amount) internal {
+        if (!IERC20(token).transferFrom(from, address(this), amount)) {
+            revert TransferFailed(token);
+        }
+    }
+
+    function _pushTokens(address token, address to, uint256 amount) internal {
+        if (!IERC20(token).transfer(to, amount)) {
+            revert TransferFailed(token);
+        }
+    }
+}
+`,
+  },
+  erc4626: {
+    name: 'ERC4626Vault',
+    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256, U512, U8},
+    call::Call,
+    prelude::*,
+    storage::{StorageAddress, StorageMap, StorageString, StorageU256, StorageU8},
+    stylus_core::log,
+};
+
+sol_interface! {
+    interface IERC20 {
+        function decimals() external view returns (uint8);
+        function balanceOf(address account) external view returns (uint256);
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+}
+
+/// ERC-4626 vault over a single ERC-20 asset. The vault is itself an ERC-20
+/// whose tokens are shares of the assets it holds. Conversions count one
+/// virtual share and one virtual asset, which makes the first-depositor
+/// inflation attack unprofitable, and always round in the vault's favour.
+#[storage]
+#[entrypoint]
+pub struct ERC4626Vault {
+    asset: StorageAddress,
+    decimals: StorageU8,
+    name: StorageString,
+    symbol: StorageString,
+    total_supply: StorageU256,
+    balances: StorageMap<Address, StorageU256>,
+    allowances: StorageMap<Address, StorageMap<Address, StorageU256>>,
+}
+
+sol! {
+    #![sol(all_derives)]
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+    event Deposit(address indexed sender, address indexed owner, uint256 assets, uint256 shares);
+    event Withdraw(
+        address indexed sender,
+        address indexed receiver,
+        address indexed owner,
+        uint256 assets,
+        uint256 shares
+    );
+
+    error InsufficientBalance(address sender, uint256 balance, uint256 needed);
+    error InsufficientAllowance(address spender, uint256 allowance, uint256 needed);
+    error InvalidReceiver(address receiver);
+    error ERC4626ExceededMaxWithdraw(address owner, uint256 assets, uint256 max);
+    error ERC4626ExceededMaxRedeem(address owner, uint256 shares, uint256 max);
+    error TransferFailed(address token);
+    error MathOverflow();
+}
+
+#[derive(SolidityError, Debug)]
+pub enum VaultError {
+    InsufficientBalance(InsufficientBalance),
+    InsufficientAllowance(InsufficientAllowance),
+    InvalidReceiver(InvalidReceiver),
+    ERC4626ExceededMaxWithdraw(ERC4626ExceededMaxWithdraw),
+    ERC4626ExceededMaxRedeem(ERC4626ExceededMaxRedeem),
+    TransferFailed(TransferFailed),
+    MathOverflow(MathOverflow),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Rounding {
+    Floor,
+    Ceil,
+}
+
+#[public]
+impl ERC4626Vault {
+    /// Shares use the asset's decimals, or 18 if the asset does not report
+    /// them.
+    #[constructor]
+    pub fn constructor(&mut self, asset: Address, name: String, symbol: String) {
+        let decimals = IERC20::new(asset)
+            .decimals(self.vm(), Call::new())
+            .unwrap_or(18);
+
+        self.asset.set(asset);
+        self.decimals.set(U8::from(decimals));
+        self.name.set_str(&name);
+        self.symbol.set_str(&symbol);
+    }
+
+    pub fn name(&self) -> String {
+        self.name.get_string()
+    }
+
+    pub fn symbol(&self) -> String {
+        self.symbol.get_string()
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals.get().to()
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        self.total_supply.get()
+    }
+
+    pub fn balance_of(&self, account: Address) -> U256 {
+        self.balances.get(account)
+    }
+
+    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        self.allowances.getter(owner).get(spender)
+    }
+
+    pub fn transfer(&mut self, to: Address, amount: U256) -> Result<bool, VaultError> {
+        let sender = self.vm().msg_sender();
+        self.move_shares(sender, to, amount)?;
+        Ok(true)
+    }
+
+    pub fn approve(&mut self, spender: Address, amount: U256) -> bool {
+        let owner = self.vm().msg_sender();
+        self.allowances.setter(owner).insert(spender, amount);
+        log(
+            self.vm(),
+            Approval {
+                owner,
+                spender,
+                value: amount,
+            },
+        );
+        true
+    }
+
+    pub fn transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<bool, VaultError> {
+        let spender = self.vm().msg_sender();
+        self.spend_allowance(from, spender, amount)?;
+        self.move_shares(from, to, amount)?;
+        Ok(true)
+    }
+
+    pub fn asset(&self) -> Address {
+        self.asset.get()
+    }
+
+    /// Assets held by the vault, read from the asset's balanceOf
+    pub fn total_assets(&self) -> Result<U256, VaultError> {
+        let asset = self.asset.get();
+        IERC20::new(asset)
+            .balance_of(self.vm(), Call::new(), self.vm().contract_address())
+            .map_err(|_| VaultError::TransferFailed(TransferFailed { token: asset }))
+    }
+
+    pub fn convert_to_shares(&self, assets: U256) -> Result<U256, VaultError> {
+        self.to_shares(assets, Rounding::Floor)
+    }
+
+    pub fn convert_to_assets(&self, shares: U256) -> Result<U256, VaultError> {
+        self.to_assets(shares, Rounding::Floor)
+    }
+
+    pub fn max_deposit(&self, _receiver: Address) -> U256 {
+        U256::MAX
+    }
+
+    pub fn max_mint(&self, _receiver: Address) -> U256 {
+        U256::MAX
+    }
+
+    pub fn max_withdraw(&self, owner: Address) -> Result<U256, VaultError> {
+        self.to_assets(self.balances.get(owner), Rounding::Floor)
+    }
+
+    pub fn max_redeem(&self, owner: Address) -> U256 {
+        self.balances.get(owner)
+    }
+
+    /// Shares minted by depositing assets, rounded down
+    pub fn preview_deposit(&self, assets: U256) -> Result<U256, VaultError> {
+        self.to_shares(assets, Rounding::Floor)
+    }
+
+    /// Assets needed to mint shares, rounded up
+    pub fn preview_mint(&self, shares: U256) -> Result<U256, VaultError> {
+        self.to_assets(shares, Rounding::Ceil)
+    }
+
+    /// Shares burned to withdraw assets, rounded up
+    pub fn preview_withdraw(&self, assets: U256) -> Result<U256, VaultError> {
+        self.to_shares(assets, Rounding::Ceil)
+    }
+
+    /// Assets returned by redeeming shares, rounded down
+    pub fn preview_redeem(&self, shares: U256) -> Result<U256, VaultError> {
+        self.to_assets(shares, Rounding::Floor)
+    }
+
+    pub fn deposit(&mut self, assets: U256, receiver: Address) -> Result<U256, VaultError> {
+        let shares = self.preview_deposit(assets)?;
+        self.enter(receiver, assets, shares)?;
+        Ok(shares)
+    }
+
+    pub fn mint(&mut self, shares: U256, receiver: Address) -> Result<U256, VaultError> {
+        let assets = self.preview_mint(shares)?;
+        self.enter(receiver, assets, shares)?;
+        Ok(assets)
+    }
+
+    pub fn withdraw(
+        &mut self,
+        assets: U256,
+        receiver: Address,
+        owner: Address,
+    ) -> Result<U256, VaultError> {
+        let max = self.max_withdraw(owner)?;
+        if assets > max {
+            return Err(VaultError::ERC4626ExceededMaxWithdraw(
+                ERC4626ExceededMaxWithdraw { owner, assets, max },
+            ));
+        }
+
+        let shares = self.preview_withdraw(assets)?;
+        self.exit(receiver, owner, assets, shares)?;
+        Ok(shares)
+    }
+
+    pub fn redeem(
+        &mut self,
+        shares: U256,
+        receiver: Address,
+        owner: Address,
+    ) -> Result<U256, VaultError> {
+        let max = self.max_redeem(owner);
+        if shares > max {
+            return Err(VaultError::ERC4626ExceededMaxRedeem(ERC4626ExceededMaxRedeem {
+                owner,
+                shares,
+                max,
+            }));
+        }
+
+        let assets = self.preview_redeem(shares)?;
+        self.exit(receiver, owner, assets, shares)?;
+        Ok(assets)
+    }
+}
+
+impl ERC4626Vault {
+    fn to_shares(&self, assets: U256, rounding: Rounding) -> Result<U256, VaultError> {
+        let total_assets = self.total_assets()?;
+        mul_div(
+            assets,
+            self.total_supply.get() + U256::from(1),
+            total_assets + U256::from(1),
+            rounding,
+        )
+    }
+
+    fn to_assets(&self, shares: U256, rounding: Rounding) -> Result<U256, VaultError> {
+        let total_assets = self.total_assets()?;
+        mul_div(
+            shares,
+            total_assets + U256::from(1),
+            self.total_supply.get() + U256::from(1),
+            rounding,
+        )
+    }
+
+    /// Pulls assets from the caller and mints shares to receiver
+    fn enter(&mut self, receiver: Address, assets: U256, shares: U256) -> Result<(), VaultError> {
+        if receiver == Address::ZERO {
+            return Err(VaultError::InvalidReceiver(InvalidReceiver { receiver }));
+        }
+
+        let sender = self.vm().msg_sender();
+        let vault = self.vm().contract_address();
+        let asset = self.asset.get();
+        let erc20 = IERC20::new(asset);
+        let call = Call::new_mutating(self);
+        match erc20.transfer_from(self.vm(), call, sender, vault, assets) {
+            Ok(true) => {}
+            _ => return Err(VaultError::TransferFailed(TransferFailed { token: asset })),
+        }
+
+        self.mint_shares(receiver, shares)?;
+        log(
+            self.vm(),
+            Deposit {
+                sender,
+                owner: receiver,
+                assets,
+                shares,
+            },
+        );
+        Ok(())
+    }
+
+    /// Burns owner's shares, spending the caller's allowance when the caller
+    /// is not the owner, and sends the assets to receiver
+    fn exit(
+        &mut self,
+        receiver: Address,
+        owner: Address,
+        assets: U256,
+        shares: U256,
+    ) -> Result<(), VaultError> {
+        let sender = self.vm().msg_sender();
+        if sender != owner {
+            self.spend_allowance(owner, sender, shares)?;
+        }
+        self.burn_shares(owner, shares)?;
+
+        let asset = self.asset.get();
+        let erc20 = IERC20::new(asset);
+        let call = Call::new_mutating(self);
+        match erc20.transfer(self.vm(), call, receiver, assets) {
+            Ok(true) => {}
+            _ => return Err(VaultError::TransferFailed(TransferFailed { token: asset })),
+        }
+
+        log(
+            self.vm(),
+            Withdraw {
+                sender,
+                receiver,
+                owner,
+                assets,
+                shares,
+            },
+        );
+        Ok(())
+    }
+
+    fn spend_allowance(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        amount: U256,
+    ) -> Result<(), VaultError> {
+        let allowance = self.allowances.getter(owner).get(spender);
+        if allowance < amount {
+            return Err(VaultError::InsufficientAllowance(InsufficientAllowance {
+                spender,
+                allowance,
+                needed: amount,
+            }));
+        }
+        // An infinite allowance is never decreased
+        if allowance != U256::MAX {
+            self.allowances
+                .setter(owner)
+                .insert(spender, allowance - amount);
+        }
+        Ok(())
+    }
+
+    fn move_shares(&mut self, from: Address, to: Address, amount: U256) -> Result<(), VaultError> {
+        if to == Address::ZERO {
+            return Err(VaultError::InvalidReceiver(InvalidReceiver { receiver: to }));
+        }
+
+        let from_balance = self.balances.get(from);
+        if from_balance < amount {
+            return Err(VaultError::InsufficientBalance(InsufficientBalance {
+                sender: from,
+                balance: from_balance,
+                needed: amount,
+            }));
+        }
+
+        self.balances.insert(from, from_balance - amount);
+        let to_balance = self.balances.get(to);
+        self.balances.insert(to, to_balance + amount);
+
+        log(
+            self.vm(),
+            Transfer {
+                from,
+                to,
+                value: amount,
+            },
+        );
+        Ok(())
+    }
+
+    fn mint_shares(&mut self, to: Address, amount: U256) -> Result<(), VaultError> {
+        let total_supply = self
+            .total_supply
+            .get()
+            .checked_add(amount)
+            .ok_or(VaultError::MathOverflow(MathOverflow {}))?;
+        self.total_supply.set(total_supply);
+        let balance = self.balances.get(to);
+        self.balances.insert(to, balance + amount);
+
+        log(
+            self.vm(),
+            Transfer {
+                from: Address::ZERO,
+                to,
+                value: amount,
+            },
+        );
+        Ok(())
+    }
+
+    fn burn_shares(&mut self, from: Address, amount: U256) -> Result<(), VaultError> {
+        let balance = self.balances.get(from);
+        if balance < amount {
+            return Err(VaultError::InsufficientBalance(InsufficientBalance {
+                sender: from,
+                balance,
+                needed: amount,
+            }));
+        }
+
+        self.balances.insert(from, balance - amount);
+        self.total_supply.set(self.total_supply.get() - amount);
+
+        log(
+            self.vm(),
+            Transfer {
+                from,
+                to: Address::ZERO,
+                value: amount,
+            },
+        );
+        Ok(())
+    }
+}
+
+/// x * y / denominator without intermediate overflow. The product is
+/// computed on 512 bits; only a result that does not fit in 256 bits fails.
+fn mul_div(x: U256, y: U256, denominator: U256, rounding: Rounding) -> Result<U256, VaultError> {
+    let product: U512 = x.widening_mul(y);
+    let (mut quotient, remainder) = product.div_rem(U512::from(denominator));
+    if rounding == Rounding::Ceil && !remainder.is_zero() {
+        quotient += U512::from(1);
+    }
+    U256::checked_from_limbs_slice(quotient.as_limbs()).ok_or(VaultError::MathOverflow(MathOverflow {}))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_sol_types::{SolCall, SolEvent};
+    use stylus_sdk::testing::*;
+
+    const ASSET: Address = Address::repeat_byte(0x0a);
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+
+    sol! {
+        function decimals() external view returns (uint8);
+        function balanceOf(address account) external view returns (uint256);
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    /// Initializes the vault the way the constructor does for an 18-decimal
+    /// asset that the vault holds none of.
+    fn deploy(vm: &TestVM) -> ERC4626Vault {
+        let mut contract = ERC4626Vault::from(vm);
+        contract.asset.set(ASSET);
+        contract.decimals.set(U8::from(18));
+        contract.name.set_str("Vault Shares");
+        contract.symbol.set_str("vSHR");
+        set_total_assets(vm, &contract, 0);
+        contract
+    }
+
+    fn encode(value: U256) -> Vec<u8> {
+        value.to_be_bytes::<32>().to_vec()
+    }
+
+    /// Makes the asset report amount as the vault's balance
+    fn set_total_assets(vm: &TestVM, contract: &ERC4626Vault, amount: u64) {
+        let data = balanceOfCall {
+            account: contract.vm().contract_address(),
+        }
+        .abi_encode();
+        vm.mock_static_call(ASSET, data, Ok(encode(U256::from(amount))));
+    }
+
+    /// Makes asset.transferFrom(from, vault, value) succeed
+    fn mock_pull(vm: &TestVM, contract: &ERC4626Vault, from: Address, value: u64) {
+        let data = transferFromCall {
+            from,
+            to: contract.vm().contract_address(),
+            value: U256::from(value),
+        }
+        .abi_encode();
+        vm.mock_call(ASSET, data, U256::ZERO, Ok(encode(U256::from(1))));
+    }
+
+    /// Makes asset.transfer(to, value) succeed
+    fn mock_push(vm: &TestVM, to: Address, value: u64) {
+        let data = transferCall {
+            to,
+            value: U256::from(value),
+        }
+        .abi_encode();
+        vm.mock_call(ASSET, data, U256::ZERO, Ok(encode(U256::from(1))));
+    }
+
+    #[test]
+    fn test_constructor_reads_asset_decimals() {
+        let vm = TestVM::default();
+        let mut contract = ERC4626Vault::from(&vm);
+        vm.mock_static_call(ASSET, decimalsCall {}.abi_encode(), Ok(encode(U256::from(6))));
+
+        contract.constructor(ASSET, "Vault Shares".into(), "vSHR".into());
+        assert_eq!(ASSET, contract.asset());
+        assert_eq!(6, contract.decimals());
+        assert_eq!("vSHR", contract.symbol());
+    }
+
+    // The amounts below are shared with test/ERC4626Vault.t.sol on the
+    // Solidity side, so both implementations must round identically.
+    #[test]
+    fn test_vault_lifecycle() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        // an empty vault converts 1:1
+        assert_eq!(U256::from(1_000), contract.preview_deposit(U256::from(1_000)).unwrap());
+
+        vm.set_sender(ALICE);
+        mock_pull(&vm, &contract, ALICE, 1_000);
+        let shares = contract.deposit(U256::from(1_000), ALICE).unwrap();
+        assert_eq!(U256::from(1_000), shares);
+        assert_eq!(U256::from(1_000), contract.balance_of(ALICE));
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Deposit::SIGNATURE_HASH, topics[0]);
+        assert_eq!(ALICE.into_word(), topics[1]);
+
+        // 500 assets of yield: every conversion rounds towards the vault
+        set_total_assets(&vm, &contract, 1_500);
+        assert_eq!(U256::from(66), contract.convert_to_shares(U256::from(100)).unwrap());
+        assert_eq!(U256::from(66), contract.preview_deposit(U256::from(100)).unwrap());
+        assert_eq!(U256::from(99), contract.preview_mint(U256::from(66)).unwrap());
+        assert_eq!(U256::from(1_499), contract.convert_to_assets(U256::from(1_000)).unwrap());
+        assert_eq!(U256::from(67), contract.preview_withdraw(U256::from(100)).unwrap());
+        assert_eq!(U256::from(98), contract.preview_redeem(U256::from(66)).unwrap());
+        assert_eq!(U256::from(1_499), contract.max_withdraw(ALICE).unwrap());
+
+        vm.set_sender(BOB);
+        mock_pull(&vm, &contract, BOB, 100);
+        assert_eq!(U256::from(66), contract.deposit(U256::from(100), BOB).unwrap());
+        set_total_assets(&vm, &contract, 1_600);
+
+        vm.set_sender(ALICE);
+        mock_push(&vm, ALICE, 750);
+        assert_eq!(U256::from(750), contract.redeem(U256::from(500), ALICE, ALICE).unwrap());
+        set_total_assets(&vm, &contract, 850);
+
+        mock_push(&vm, ALICE, 100);
+        assert_eq!(U256::from(67), contract.withdraw(U256::from(100), ALICE, ALICE).unwrap());
+        set_total_assets(&vm, &contract, 750);
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Withdraw::SIGNATURE_HASH, topics[0]);
+
+        assert_eq!(U256::from(433), contract.balance_of(ALICE));
+        assert_eq!(U256::from(499), contract.total_supply());
+
+        vm.set_sender(BOB);
+        mock_pull(&vm, &contract, BOB, 16);
+        assert_eq!(U256::from(16), contract.mint(U256::from(10), BOB).unwrap());
+        assert_eq!(U256::from(76), contract.balance_of(BOB));
+    }
+
+    #[test]
+    fn test_withdraw_and_redeem_reject_excess() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        vm.set_sender(ALICE);
+        mock_pull(&vm, &contract, ALICE, 1_000);
+        contract.deposit(U256::from(1_000), ALICE).unwrap();
+        set_total_assets(&vm, &contract, 1_000);
+
+        let err = contract.withdraw(U256::from(1_001), ALICE, ALICE).unwrap_err();
+        assert!(matches!(
+            err,
+            VaultError::ERC4626ExceededMaxWithdraw(ERC4626ExceededMaxWithdraw { max, .. })
+                if max == U256::from(1_000)
+        ));
+
+        let err = contract.redeem(U256::from(1_001), ALICE, ALICE).unwrap_err();
+        assert!(matches!(err, VaultError::ERC4626ExceededMaxRedeem(_)));
+
+        let err = contract.deposit(U256::from(1), Address::ZERO).unwrap_err();
+        assert!(matches!(err, VaultError::InvalidReceiver(_)));
+    }
+
+    #[test]
+    fn test_redeem_on_behalf_spends_allowance() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        vm.set_sender(ALICE);
+        mock_pull(&vm, &contract, ALICE, 1_000);
+        contract.deposit(U256::from(1_000), ALICE).unwrap();
+        set_total_assets(&vm, &contract, 1_000);
+
+        vm.set_sender(BOB);
+        let err = contract.redeem(U256::from(100), BOB, ALICE).unwrap_err();
+        assert!(matches!(
+            err,
+            VaultError::InsufficientAllowance(InsufficientAllowance { spender, .. }) if spender == BOB
+        ));
+        assert_eq!(U256::from(1_000), contract.balance_of(ALICE));
+
+        vm.set_sender(ALICE);
+        contract.approve(BOB, U256::from(150));
+
+        vm.set_sender(BOB);
+        mock_push(&vm, BOB, 100);
+        assert_eq!(U256::from(100), contract.redeem(U256::from(100), BOB, ALICE).unwrap());
+        assert_eq!(U256::from(900), contract.balance_of(ALICE));
+        assert_eq!(U256::from(50), contract.allowance(ALICE, BOB));
+    }
+
+    #[test]
+    fn test_mul_div() {
+        let (two, three) = (U256::from(2), U256::from(3));
+        assert_eq!(U256::from(3), mul_div(U256::from(5), two, three, Rounding::Floor).unwrap());
+        assert_eq!(U256::from(4), mul_div(U256::from(5), two, three, Rounding::Ceil).unwrap());
+        assert_eq!(two, mul_div(U256::from(3), two, three, Rounding::Ceil).unwrap());
+
+        // the intermediate product may exceed 256 bits
+        assert_eq!(U256::MAX, mul_div(U256::MAX, U256::MAX, U256::MAX, Rounding::Floor).unwrap());
+        assert_eq!(
+            U256::MAX / three,
+            mul_div(U256::MAX, two, U256::from(6), Rounding::Floor).unwrap()
+        );
+
+        let err = mul_div(U256::MAX, two, U256::from(1), Rounding::Floor).unwrap_err();
+        assert!(matches!(err, VaultError::MathOverflow(_)));
+        assert_eq!(U256::MAX, mul_div(U256::MAX, three, three, Rounding::Ceil).unwrap());
+        let err = mul_div(U256::MAX, U256::MAX, U256::MAX - U256::from(1), Rounding::Floor).unwrap_err();
+        assert!(matches!(err, VaultError::MathOverflow(_)));
+    }
+}
+`,
+    solidity: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+interface IERC20 {
+    function decimals() external view returns (uint8);
+    function balanceOf(address account) external view returns (uint256);
+    function transfer(address to, uint256 value) external returns (bool);
+    function transferFrom(address from, address to, uint256 value) external returns (bool);
+}
+
+// ERC-4626 vault over a single ERC-20 asset. The vault is itself an ERC-20
+// whose tokens are shares of the assets it holds. Conversions count one
+// virtual share and one virtual asset, which makes the first-depositor
+// inflation attack unprofitable, and always round in the vault's favour.
+contract ERC4626Vault {
+    enum Rounding {
+        Floor,
+        Ceil
+    }
+
+    address private _asset;
+    uint8 private _decimals;
+    string private _name;
+    string private _symbol;
+    uint256 private _totalSupply;
+    mapping(address => uint256) private balances;
+    mapping(address => mapping(address => uint256)) private allowances;
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+    event Deposit(address indexed sender, address indexed owner, uint256 assets, uint256 shares);
+    event Withdraw(
+        address indexed sender,
+        address indexed receiver,
+        address indexed owner,
+        uint256 assets,
+        uint256 shares
+    );
+
+    error InsufficientBalance(address sender, uint256 balance, uint256 needed);
+    error InsufficientAllowance(address spender, uint256 allowance, uint256 needed);
+    error InvalidReceiver(address receiver);
+    error ERC4626ExceededMaxWithdraw(address owner, uint256 assets, uint256 max);
+    error ERC4626ExceededMaxRedeem(address owner, uint256 shares, uint256 max);
+    error TransferFailed(address token);
+    error MathOverflow();
+
+    // Shares use the asset's decimals, or 18 if the asset does not report them.
+    constructor(address asset_, string memory name_, string memory symbol_) {
+        _asset = asset_;
+        _decimals = 18;
+        _name = name_;
+        _symbol = symbol_;
+
+        if (asset_.code.length > 0) {
+            try IERC20(asset_).decimals() returns (uint8 assetDecimals) {
+                _decimals = assetDecimals;
+            } catch {}
+        }
+    }
+
+    function name() public view returns (string memory) {
+        return _name;
+    }
+
+    function symbol() public view returns (string memory) {
+        return _symbol;
+    }
+
+    function decimals() public view returns (uint8) {
+        return _decimals;
+    }
+
+    function totalSupply() public view returns (uint256) {
+        return _totalSupply;
+    }
+
+    function balanceOf(address account) public view returns (uint256) {
+        return balances[account];
+    }
+
+    function allowance(address owner, address spender) public view returns (uint256) {
+        return allowances[owner][spender];
+    }
+
+    function transfer(address to, uint256 amount) public returns (bool) {
+        _transfer(msg.sender, to, amount);
+        return true;
+    }
+
+    function approve(address spender, uint256 amount) public returns (bool) {
+        allowances[msg.sender][spender] = amount;
+        emit Approval(msg.sender, spender, amount);
+        return true;
+    }
+
+    function transferFrom(address from, address to, uint256 amount) public returns (bool) {
+        _spendAllowance(from, msg.sender, amount);
+        _transfer(from, to, amount);
+        return true;
+    }
+
+    function asset() public view returns (address) {
+        return _asset;
+    }
+
+    // Assets held by the vault, read from the asset's balanceOf
+    function totalAssets() public view returns (uint256) {
+        return IERC20(_asset).balanceOf(address(this));
+    }
+
+    function convertToShares(uint256 assets) public view returns (uint256) {
+        return _toShares(assets, Rounding.Floor);
+    }
+
+    function convertToAssets(uint256 shares) public view returns (uint256) {
+        return _toAssets(shares, Rounding.Floor);
+    }
+
+    function maxDeposit(address) public view virtual returns (uint256) {
+        return type(uint256).max;
+    }
+
+    function maxMint(address) public view virtual returns (uint256) {
+        return type(uint256).max;
+    }
+
+    function maxWithdraw(address owner) public view returns (uint256) {
+        return _toAssets(balances[owner], Rounding.Floor);
+    }
+
+    function maxRedeem(address owner) public view returns (uint256) {
+        return balances[owner];
+    }
+
+    // Shares minted by depositing assets, rounded down
+    function previewDeposit(uint256 assets) public view returns (uint256) {
+        return _toShares(assets, Rounding.Floor);
+    }
+
+    // Assets needed to mint shares, rounded up
+    function previewMint(uint256 shares) public view returns (uint256) {
+        return _toAssets(shares, Rounding.Ceil);
+    }
+
+    // Shares burned to withdraw assets, rounded up
+    function previewWithdraw(uint256 assets) public view returns (uint256) {
+        return _toShares(assets, Rounding.Ceil);
+    }
+
+    // Assets returned by redeeming shares, rounded down
+    function previewRedeem(uint256 shares) public view returns (uint256) {
+        return _toAssets(shares, Rounding.Floor);
+    }
+
+    function deposit(uint256 assets, address receiver) public returns (uint256) {
+        uint256 shares = previewDeposit(assets);
+        _enter(receiver, assets, shares);
+        return shares;
+    }
+
+    function mint(uint256 shares, address receiver) public returns (uint256) {
+        uint256 assets = previewMint(shares);
+        _enter(receiver, assets, shares);
+        return assets;
+    }
+
+    function withdraw(uint256 assets, address receiver, address owner) public returns (uint256) {
+        uint256 max = maxWithdraw(owner);
+        if (assets > max) {
+            revert ERC4626ExceededMaxWithdraw(owner, assets, max);
+        }
+
+        uint256 shares = previewWithdraw(assets);
+        _exit(receiver, owner, assets, shares);
+        return shares;
+    }
+
+    function redeem(uint256 shares, address receiver, address owner) public returns (uint256) {
+        uint256 max = maxRedeem(owner);
+        if (shares > max) {
+            revert ERC4626ExceededMaxRedeem(owner, shares, max);
+        }
+
+        uint256 assets = previewRedeem(shares);
+        _exit(receiver, owner, assets, shares);
+        return assets;
+    }
+
+    function _toShares(uint256 assets, Rounding rounding) internal view returns (uint256) {
+        return _mulDiv(assets, _totalSupply + 1, totalAssets() + 1, rounding);
+    }
+
+    function _toAssets(uint256 shares, Rounding rounding) internal view returns (uint256) {
+        return _mulDiv(shares, totalAssets() + 1, _totalSupply + 1, rounding);
+    }
+
+    // Pulls assets from the caller and mints shares to receiver
+    function _enter(address receiver, uint256 assets, uint256 shares) internal {
+        if (receiver == address(0)) {
+            revert InvalidReceiver(receiver);
+        }
+
+        if (!IERC20(_asset).transferFrom(msg.sender, address(this), assets)) {
+            revert TransferFailed(_asset);
+        }
+
+        _totalSupply += shares;
+        unchecked {
+            balances[receiver] += shares;
+        }
+        emit Transfer(address(0), receiver, shares);
+
+        emit Deposit(msg.sender, receiver, assets, shares);
+    }
+
+    // Burns owner's shares, spending the caller's allowance when the caller
+    // is not the owner, and sends the assets to receiver
+    function _exit(address receiver, address owner, uint256 assets, uint256 shares) internal {
+        if (msg.sender != owner) {
+            _spendAllowance(owner, msg.sender, shares);
+        }
+
+        uint256 balance = balances[owner];
+        if (balance < shares) {
+            revert InsufficientBalance(owner, balance, shares);
+        }
+        unchecked {
+            balances[owner] = balance - shares;
+            _totalSupply -= shares;
+        }
+        emit Transfer(owner, address(0), shares);
+
+        if (!IERC20(_asset).transfer(receiver, assets)) {
+            revert TransferFailed(_asset);
+        }
+
+        emit Withdraw(msg.sender, receiver, owner, assets, shares);
+    }
+
+    function _spendAllowance(address owner, address spender, uint256 amount) internal {
+        uint256 current = allowances[owner][spender];
+        if (current < amount) {
+            revert InsufficientAllowance(spender, current, amount);
+        }
+        // An infinite allowance is never decreased
+        if (current != type(uint256).max) {
+            unchecked {
+                allowances[owner][spender] = current - amount;
+            }
+        }
+    }
+
+    function _transfer(address from, address to, uint256 amount) internal {
+        if (to == address(0)) {
+            revert InvalidReceiver(to);
+        }
+
+        uint256 fromBalance = balances[from];
+        if (fromBalance < amount) {
+            revert InsufficientBalance(from, fromBalance, amount);
+        }
+
+        unchecked {
+            balances[from] = fromBalance - amount;
+            balances[to] += amount;
+        }
+        emit Transfer(from, to, amount);
+    }
+
+    // x * y / denominator without intermediate overflow (Remco Bloemen's
+    // 512-bit mulDiv, as in OpenZeppelin's Math library). Only a result that
+    // does not fit in 256 bits fails.
+    function _mulDiv(
+        uint256 x,
+        uint256 y,
+        uint256 denominator,
+        Rounding rounding
+    ) internal pure returns (uint256 result) {
+        // Checked first, since the division below overwrites denominator
+        bool roundUp = rounding == Rounding.Ceil && mulmod(x, y, denominator) > 0;
+
+        unchecked {
+            // 512-bit product as prod1 * 2^256 + prod0
+            uint256 prod0 = x * y;
+            uint256 prod1;
+            assembly {
+                let mm := mulmod(x, y, not(0))
+                prod1 := sub(sub(mm, prod0), lt(mm, prod0))
+            }
+
+            if (prod1 == 0) {
+                result = prod0 / denominator;
+            } else {
+                if (denominator <= prod1) {
+                    revert MathOverflow();
+                }
+
+                // Make the division exact by subtracting the remainder
+                uint256 remainder;
+                assembly {
+                    remainder := mulmod(x, y, denominator)
+                    prod1 := sub(prod1, gt(remainder, prod0))
+                    prod0 := sub(prod0, remainder)
+                }
+
+                // Factor the largest power of two out of the denominator
+                uint256 twos = denominator & (0 - denominator);
+                assembly {
+                    denominator := div(denominator, twos)
+                    prod0 := div(prod0, twos)
+                    twos := add(div(sub(0, twos), twos), 1)
+                }
+                prod0 |= prod1 * twos;
+
+                // Multiply by the inverse of the now odd denominator modulo
+                // 2^256, found by Newton-Raphson iterations
+                uint256 inverse = (3 * denominator) ^ 2;
+                inverse *= 2 - denominator * inverse;
+                inverse *= 2 - denominator * inverse;
+                inverse *= 2 - denominator * inverse;
+                inverse *= 2 - denominator * inverse;
+                inverse *= 2 - denominator * inverse;
+                inverse *= 2 - denominator * inverse;
+                result = prod0 * inverse;
+            }
+        }
+
+        if (roundUp) {
+            if (result == type(uint256).max) {
+                revert MathOverflow();
+            }
+            result += 1;
+        }
+    }
+}
+`,
+    solidityTest: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {ERC4626Vault} from "../ERC4626Vault.sol";
+
+// Run with forge test from contracts-solidity. The amounts mirror the
+// Rust tests in contracts-rust/src/lib.rs, so both implementations must
+// round identically.
+
+contract MockAsset {
+    uint8 public constant decimals = 6;
+    mapping(address => uint256) public balanceOf;
+    mapping(address => mapping(address => uint256)) public allowance;
+
+    function mint(address to, uint256 amount) external {
+        balanceOf[to] += amount;
+    }
+
+    function approve(address spender, uint256 amount) external returns (bool) {
+        allowance[msg.sender][spender] = amount;
+        return true;
+    }
+
+    function transfer(address to, uint256 amount) external returns (bool) {
+        balanceOf[msg.sender] -= amount;
+        balanceOf[to] += amount;
+        return true;
+    }
+
+    function transferFrom(address from, address to, uint256 amount) external returns (bool) {
+        allowance[from][msg.sender] -= amount;
+        balanceOf[from] -= amount;
+        balanceOf[to] += amount;
+        return true;
+    }
+}
+
+// A vault user with its own address, standing in for ALICE and BOB
+contract Account {
+    function approve(MockAsset asset, address spender) external {
+        asset.approve(spender, type(uint256).max);
+    }
+
+    function deposit(ERC4626Vault vault, uint256 assets) external returns (uint256) {
+        return vault.deposit(assets, address(this));
+    }
+
+    function mint(ERC4626Vault vault, uint256 shares) external returns (uint256) {
+        return vault.mint(shares, address(this));
+    }
+
+    function withdraw(ERC4626Vault vault, uint256 assets) external returns (uint256) {
+        return vault.withdraw(assets, address(this), address(this));
+    }
+
+    function redeem(ERC4626Vault vault, uint256 shares) external returns (uint256) {
+        return vault.redeem(shares, address(this), address(this));
+    }
+}
+
+contract VaultHarness is ERC4626Vault {
+    constructor(address asset_) ERC4626Vault(asset_, "Vault Shares", "vSHR") {}
+
+    function mulDiv(uint256 x, uint256 y, uint256 denominator, bool ceil) external pure returns (uint256) {
+        return _mulDiv(x, y, denominator, ceil ? Rounding.Ceil : Rounding.Floor);
+    }
+}
+
+contract ERC4626VaultTest {
+    MockAsset private asset;
+    VaultHarness private vault;
+    Account private alice;
+    Account private bob;
+
+    function setUp() public {
+        asset = new MockAsset();
+        vault = new VaultHarness(address(asset));
+        alice = new Account();
+        bob = new Account();
+
+        asset.mint(address(alice), 10_000);
+        asset.mint(address(bob), 10_000);
+        alice.approve(asset, address(vault));
+        bob.approve(asset, address(vault));
+    }
+
+    function testDecimalsFollowAsset() public view {
+        require(vault.decimals() == 6, "decimals");
+    }
+
+    function testVaultLifecycle() public {
+        // an empty vault converts 1:1
+        require(vault.previewDeposit(1_000) == 1_000, "empty previewDeposit");
+
+        require(alice.deposit(vault, 1_000) == 1_000, "alice deposit");
+        require(vault.balanceOf(address(alice)) == 1_000, "alice shares");
+
+        // 500 assets of yield: every conversion rounds towards the vault
+        asset.mint(address(vault), 500);
+        require(vault.convertToShares(100) == 66, "convertToShares");
+        require(vault.previewDeposit(100) == 66, "previewDeposit");
+        require(vault.previewMint(66) == 99, "previewMint");
+        require(vault.convertToAssets(1_000) == 1_499, "convertToAssets");
+        require(vault.previewWithdraw(100) == 67, "previewWithdraw");
+        require(vault.previewRedeem(66) == 98, "previewRedeem");
+        require(vault.maxWithdraw(address(alice)) == 1_499, "maxWithdraw");
+
+        require(bob.deposit(vault, 100) == 66, "bob deposit");
+        require(alice.redeem(vault, 500) == 750, "alice redeem");
+        require(alice.withdraw(vault, 100) == 67, "alice withdraw");
+
+        require(vault.balanceOf(address(alice)) == 433, "alice shares after exit");
+        require(vault.totalSupply() == 499, "total supply");
+        require(vault.totalAssets() == 750, "total assets");
+
+        require(bob.mint(vault, 10) == 16, "bob mint");
+        require(vault.balanceOf(address(bob)) == 76, "bob shares");
+    }
+
+    function testMulDiv() public view {
+        uint256 max = type(uint256).max;
+
+        require(vault.mulDiv(5, 2, 3, false) == 3, "floor");
+        require(vault.mulDiv(5, 2, 3, true) == 4, "ceil");
+        require(vault.mulDiv(3, 2, 3, true) == 2, "exact ceil");
+
+        // the intermediate product may exceed 256 bits
+        require(vault.mulDiv(max, max, max, false) == max, "max * max / max");
+        require(vault.mulDiv(max, 2, 6, false) == max / 3, "max * 2 / 6");
+        require(vault.mulDiv(max, 3, 3, true) == max, "max * 3 / 3 ceil");
+    }
+
+    function testMulDivRevertsOnOverflow() public {
+        uint256 max = type(uint256).max;
+
+        try vault.mulDiv(max, 2, 1, false) {
+            revert("expected MathOverflow");
+        } catch (bytes memory reason) {
+            require(bytes4(reason) == ERC4626Vault.MathOverflow.selector, "wrong error");
+        }
+
+        try vault.mulDiv(max, max, max - 1, false) {
+            revert("expected MathOverflow");
+        } catch (bytes memory reason) {
+            require(bytes4(reason) == ERC4626Vault.MathOverflow.selector, "wrong error");
+        }
+    }
+}
+`,
+  },
+  erc1155: {
+    name: 'ERC1155MultiToken',
+    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, U256},
+    call::Call,
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageMap, StorageString, StorageU256},
+    stylus_core::log,
+};
+
+/// Values a receiver contract must return from its callbacks (their selectors)
+const ERC1155_RECEIVED: FixedBytes<4> = FixedBytes::new([0xf2, 0x3a, 0x6e, 0x61]);
+const ERC1155_BATCH_RECEIVED: FixedBytes<4> = FixedBytes::new([0xbc, 0x19, 0x7c, 0x81]);
+const INTERFACE_ID_ERC165: FixedBytes<4> = FixedBytes::new([0x01, 0xff, 0xc9, 0xa7]);
+const INTERFACE_ID_ERC1155: FixedBytes<4> = FixedBytes::new([0xd9, 0xb2, 0x6a, 0x26]);
+const INTERFACE_ID_ERC1155_METADATA_URI: FixedBytes<4> = FixedBytes::new([0x0e, 0x89, 0x34, 0x1c]);
+
+sol_interface! {
+    interface IERC1155Receiver {
+        function onERC1155Received(address operator, address from, uint256 id, uint256 value, bytes data) external returns (bytes4);
+        function onERC1155BatchReceived(address operator, address from, uint256[] ids, uint256[] values, bytes data) external returns (bytes4);
+    }
+}
+
+#[storage]
+#[entrypoint]
+pub struct ERC1155 {
+    balances: StorageMap<U256, StorageMap<Address, StorageU256>>,
+    operator_approvals: StorageMap<Address, StorageMap<Address, StorageBool>>,
+    uri: StorageString,
+    owner: StorageAddress,
+}
+
+sol! {
+    #![sol(all_derives)]
+
+    event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value);
+    event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values);
+    event ApprovalForAll(address indexed account, address indexed operator, bool approved);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    error ERC1155InsufficientBalance(address sender, uint256 balance, uint256 needed, uint256 tokenId);
+    error ERC1155InvalidSender(address sender);
+    error ERC1155InvalidReceiver(address receiver);
+    error ERC1155MissingApprovalForAll(address operator, address owner);
+    error ERC1155InvalidOperator(address operator);
+    error ERC1155InvalidArrayLength(uint256 idsLength, uint256 valuesLength);
+    error BalanceOverflow(address account, uint256 tokenId);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+}
+
+#[derive(SolidityError, Debug)]
+pub enum Erc1155Error {
+    ERC1155InsufficientBalance(ERC1155InsufficientBalance),
+    ERC1155InvalidSender(ERC1155InvalidSender),
+    ERC1155InvalidReceiver(ERC1155InvalidReceiver),
+    ERC1155MissingApprovalForAll(ERC1155MissingApprovalForAll),
+    ERC1155InvalidOperator(ERC1155InvalidOperator),
+    ERC1155InvalidArrayLength(ERC1155InvalidArrayLength),
+    BalanceOverflow(BalanceOverflow),
+    Unauthorized(Unauthorized),
+    InvalidOwner(InvalidOwner),
+}
+
+#[public]
+impl ERC1155 {
+    /// Sets the metadata URI shared by every token id and makes the deploying
+    /// account the owner.
+    #[constructor]
+    pub fn constructor(&mut self, uri: String) {
+        self.uri.set_str(&uri);
+
+        let deployer = self.vm().tx_origin();
+        self.write_owner(deployer);
+    }
+
+    /// Clients replace the {id} placeholder in the URI with the hex token id,
+    /// as described in EIP-1155.
+    pub fn uri(&self, _id: U256) -> String {
+        self.uri.get_string()
+    }
+
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        interface_id == INTERFACE_ID_ERC165
+            || interface_id == INTERFACE_ID_ERC1155
+            || interface_id == INTERFACE_ID_ERC1155_METADATA_URI
+    }
+
+    pub fn balance_of(&self, account: Address, id: U256) -> U256 {
+        self.balances.getter(id).get(account)
+    }
+
+    pub fn balance_of_batch(
+        &self,
+        accounts: Vec<Address>,
+        ids: Vec<U256>,
+    ) -> Result<Vec<U256>, Erc1155Error> {
+        if accounts.len() != ids.len() {
+            return Err(Erc1155Error::ERC1155InvalidArrayLength(
+                ERC1155InvalidArrayLength {
+                    idsLength: U256::from(ids.len()),
+                    valuesLength: U256::from(accounts.len()),
+                },
+            ));
+        }
+
+        Ok(accounts
+            .iter()
+            .zip(ids.iter())
+            .map(|(account, id)| self.balance_of(*account, *id))
+            .collect())
+    }
+
+    pub fn set_approval_for_all(
+        &mut self,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), Erc1155Error> {
+        if operator == Address::ZERO {
+            return Err(Erc1155Error::ERC1155InvalidOperator(ERC1155InvalidOperator {
+                operator,
+            }));
+        }
+
+        let account = self.vm().msg_sender();
+        self.operator_approvals.setter(account).insert(operator, approved);
+        log(
+            self.vm(),
+            ApprovalForAll {
+                account,
+                operator,
+                approved,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn is_approved_for_all(&self, account: Address, operator: Address) -> bool {
+        self.operator_approvals.getter(account).get(operator)
+    }
+
+    /// Moves value tokens of one id and, when the receiver is a contract,
+    /// requires it to accept them through onERC1155Received.
+    pub fn safe_transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        id: U256,
+        value: U256,
+        data: Bytes,
+    ) -> Result<(), Erc1155Error> {
+        self.check_transfer(from, to)?;
+        self.transfer_single(from, to, id, value, data)
+    }
+
+    /// Batched safeTransferFrom. ids and values must have the same length, and
+    /// a contract receiver is called once through onERC1155BatchReceived.
+    pub fn safe_batch_transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        ids: Vec<U256>,
+        values: Vec<U256>,
+        data: Bytes,
+    ) -> Result<(), Erc1155Error> {
+        self.check_transfer(from, to)?;
+        self.transfer_batch(from, to, ids, values, data)
+    }
+
+    pub fn mint(
+        &mut self,
+        to: Address,
+        id: U256,
+        value: U256,
+        data: Bytes,
+    ) -> Result<(), Erc1155Error> {
+        self.only_owner()?;
+        if to == Address::ZERO {
+            return Err(Erc1155Error::ERC1155InvalidReceiver(ERC1155InvalidReceiver {
+                receiver: to,
+            }));
+        }
+        self.transfer_single(Address::ZERO, to, id, value, data)
+    }
+
+    pub fn mint_batch(
+        &mut self,
+        to: Address,
+        ids: Vec<U256>,
+        values: Vec<U256>,
+        data: Bytes,
+    ) -> Result<(), Erc1155Error> {
+        self.only_owner()?;
+        if to == Address::ZERO {
+            return Err(Erc1155Error::ERC1155InvalidReceiver(ERC1155InvalidReceiver {
+                receiver: to,
+            }));
+        }
+        self.transfer_batch(Address::ZERO, to, ids, values, data)
+    }
+
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), Erc1155Error> {
+        self.only_owner()?;
+        if new_owner == Address::ZERO {
+            return Err(Erc1155Error::InvalidOwner(InvalidOwner { owner: new_owner }));
+        }
+        self.write_owner(new_owner);
+        Ok(())
+    }
+
+    pub fn renounce_ownership(&mut self) -> Result<(), Erc1155Error> {
+        self.only_owner()?;
+        self.write_owner(Address::ZERO);
+        Ok(())
+    }
+}
+
+impl ERC1155 {
+    fn only_owner(&self) -> Result<(), Erc1155Error> {
+        let sender = self.vm().msg_sender();
+        if sender != self.owner.get() {
+            return Err(Erc1155Error::Unauthorized(Unauthorized { account: sender }));
+        }
+        Ok(())
+    }
+
+    /// Shared checks of both transfer entry points: the caller must be the
+    /// holder or one of their operators, and neither end may be the zero
+    /// address.
+    fn check_transfer(&self, from: Address, to: Address) -> Result<(), Erc1155Error> {
+        let operator = self.vm().msg_sender();
+        if from != operator && !self.is_approved_for_all(from, operator) {
+            return Err(Erc1155Error::ERC1155MissingApprovalForAll(
+                ERC1155MissingApprovalForAll {
+                    operator,
+                    owner: from,
+                },
+            ));
+        }
+        if to == Address::ZERO {
+            return Err(Erc1155Error::ERC1155InvalidReceiver(ERC1155InvalidReceiver {
+                receiver: to,
+            }));
+        }
+        if from == Address::ZERO {
+            return Err(Erc1155Error::ERC1155InvalidSender(ERC1155InvalidSender { sender: from }));
+        }
+        Ok(())
+    }
+
+    /// Moves value tokens of id from one account to another. The zero
+    /// address as from mints, and its balance is never read.
+    fn move_balance(
+        &mut self,
+        from: Address,
+        to: Address,
+        id: U256,
+        value: U256,
+    ) -> Result<(), Erc1155Error> {
+        if from != Address::ZERO {
+            let from_balance = self.balances.getter(id).get(from);
+            if from_balance < value {
+                return Err(Erc1155Error::ERC1155InsufficientBalance(
+                    ERC1155InsufficientBalance {
+                        sender: from,
+                        balance: from_balance,
+                        needed: value,
+                        tokenId: id,
+                    },
+                ));
+            }
+            self.balances.setter(id).insert(from, from_balance - value);
+        }
+
+        let to_balance = self.balances.getter(id).get(to);
+        let new_balance = to_balance
+            .checked_add(value)
+            .ok_or(Erc1155Error::BalanceOverflow(BalanceOverflow {
+                account: to,
+                tokenId: id,
+            }))?;
+        self.balances.setter(id).insert(to, new_balance);
+        Ok(())
+    }
+
+    fn transfer_single(
+        &mut self,
+        from: Address,
+        to: Address,
+        id: U256,
+        value: U256,
+        data: Bytes,
+    ) -> Result<(), Erc1155Error> {
+        self.move_balance(from, to, id, value)?;
+
+        let operator = self.vm().msg_sender();
+        log(
+            self.vm(),
+            TransferSingle {
+                operator,
+                from,
+                to,
+                id,
+                value,
+            },
+        );
+        self.check_on_erc1155_received(operator, from, to, id, value, data)
+    }
+
+    fn transfer_batch(
+        &mut self,
+        from: Address,
+        to: Address,
+        ids: Vec<U256>,
+        values: Vec<U256>,
+        data: Bytes,
+    ) -> Result<(), Erc1155Error> {
+        if ids.len() != values.len() {
+            return Err(Erc1155Error::ERC1155InvalidArrayLength(
+                ERC1155InvalidArrayLength {
+                    idsLength: U256::from(ids.len()),
+                    valuesLength: U256::from(values.len()),
+                },
+            ));
+        }
+
+        for (id, value) in ids.iter().zip(values.iter()) {
+            self.move_balance(from, to, *id, *value)?;
+        }
+
+        let operator = self.vm().msg_sender();
+        log(
+            self.vm(),
+            TransferBatch {
+                operator,
+                from,
+                to,
+                ids: ids.clone(),
+                values: values.clone(),
+            },
+        );
+        self.check_on_erc1155_batch_received(operator, from, to, ids, values, data)
+    }
+
+    /// Accounts without code always accept tokens. Contracts must implement
+    /// IERC1155Receiver and return the callback's selector.
+    fn check_on_erc1155_received(
+        &mut self,
+        operator: Address,
+        from: Address,
+        to: Address,
+        id: U256,
+        value: U256,
+        data: Bytes,
+    ) -> Result<(), Erc1155Error> {
+        if self.vm().code_size(to) == 0 {
+            return Ok(());
+        }
+
+        let receiver = IERC1155Receiver::new(to);
+        let call = Call::new_mutating(self);
+        let received = receiver.on_erc_1155_received(
+            self.vm(),
+            call,
+            operator,
+            from,
+            id,
+            value,
+            data.0.into(),
+        );
+
+        match received {
+            Ok(retval) if retval == ERC1155_RECEIVED => Ok(()),
+            _ => Err(Erc1155Error::ERC1155InvalidReceiver(ERC1155InvalidReceiver {
+                receiver: to,
+            })),
+        }
+    }
+
+    fn check_on_erc1155_batch_received(
+        &mut self,
+        operator: Address,
+        from: Address,
+        to: Address,
+        ids: Vec<U256>,
+        values: Vec<U256>,
+        data: Bytes,
+    ) -> Result<(), Erc1155Error> {
+        if self.vm().code_size(to) == 0 {
+            return Ok(());
+        }
+
+        let receiver = IERC1155Receiver::new(to);
+        let call = Call::new_mutating(self);
+        let received = receiver.on_erc_1155_batch_received(
+            self.vm(),
+            call,
+            operator,
+            from,
+            ids,
+            values,
+            data.0.into(),
+        );
+
+        match received {
+            Ok(retval) if retval == ERC1155_BATCH_RECEIVED => Ok(()),
+            _ => Err(Erc1155Error::ERC1155InvalidReceiver(ERC1155InvalidReceiver {
+                receiver: to,
+            })),
+        }
+    }
+
+    fn write_owner(&mut self, new_owner: Address) {
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+        log(
+            self.vm(),
+            OwnershipTransferred {
+                previousOwner: previous_owner,
+                newOwner: new_owner,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_sol_types::{SolCall, SolEvent};
+    use stylus_sdk::testing::*;
+
+    const OWNER: Address = Address::repeat_byte(0x11);
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+    const RECEIVER: Address = Address::repeat_byte(0x7e);
+
+    sol! {
+        function onERC1155Received(address operator, address from, uint256 id, uint256 value, bytes data) external returns (bytes4);
+        function onERC1155BatchReceived(address operator, address from, uint256[] ids, uint256[] values, bytes data) external returns (bytes4);
+    }
+
+    /// Initializes the collection the way the constructor does, with OWNER as
+    /// the deployer and the caller.
+    fn deploy(vm: &TestVM) -> ERC1155 {
+        let mut contract = ERC1155::from(vm);
+        contract.uri.set_str("ipfs://collection/{id}.json");
+        contract.write_owner(OWNER);
+        vm.set_sender(OWNER);
+        contract
+    }
+
+    fn ids(values: &[u64]) -> Vec<U256> {
+        values.iter().map(|value| U256::from(*value)).collect()
+    }
+
+    fn no_data() -> Bytes {
+        Bytes(Vec::new())
+    }
+
+    /// A bytes4 return value, padded to a word
+    fn encode_selector(selector: FixedBytes<4>) -> Vec<u8> {
+        let mut word = selector.to_vec();
+        word.resize(32, 0);
+        word
+    }
+
+    /// Answers RECEIVER's onERC1155Received for a transfer of value tokens of
+    /// id by ALICE
+    fn mock_received(vm: &TestVM, id: u64, value: u64, result: Result<Vec<u8>, Vec<u8>>) {
+        let data = onERC1155ReceivedCall {
+            operator: ALICE,
+            from: ALICE,
+            id: U256::from(id),
+            value: U256::from(value),
+            data: Vec::new().into(),
+        }
+        .abi_encode();
+        vm.mock_call(RECEIVER, data, U256::ZERO, result);
+    }
+
+    /// Answers RECEIVER's onERC1155BatchReceived for a batch transfer by ALICE
+    fn mock_batch_received(
+        vm: &TestVM,
+        token_ids: &[u64],
+        values: &[u64],
+        result: Result<Vec<u8>, Vec<u8>>,
+    ) {
+        let data = onERC1155BatchReceivedCall {
+            operator: ALICE,
+            from: ALICE,
+            ids: ids(token_ids),
+            values: ids(values),
+            data: Vec::new().into(),
+        }
+        .abi_encode();
+        vm.mock_call(RECEIVER, data, U256::ZERO, result);
+    }
+
+    #[test]
+    fn test_uri_and_supports_interface() {
+        let vm = TestVM::default();
+        let contract = deploy(&vm);
+
+        assert_eq!("ipfs://collection/{id}.json", contract.uri(U256::from(7)));
+
+        assert!(contract.supports_interface(INTERFACE_ID_ERC165));
+        assert!(contract.supports_interface(INTERFACE_ID_ERC1155));
+        assert!(contract.supports_interface(INTERFACE_ID_ERC1155_METADATA_URI));
+        assert!(!contract.supports_interface(FixedBytes::new([0xff, 0xff, 0xff, 0xff])));
+    }
+
+    #[test]
+    fn test_mint() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        contract.mint(ALICE, U256::from(1), U256::from(100), no_data()).unwrap();
+        assert_eq!(U256::from(100), contract.balance_of(ALICE, U256::from(1)));
+        assert_eq!(U256::ZERO, contract.balance_of(ALICE, U256::from(2)));
+
+        let logs = vm.get_emitted_logs();
+        let (topics, data) = logs.last().unwrap();
+        assert_eq!(TransferSingle::SIGNATURE_HASH, topics[0]);
+        assert_eq!(OWNER.into_word(), topics[1]);
+        assert_eq!(Address::ZERO.into_word(), topics[2]);
+        assert_eq!(ALICE.into_word(), topics[3]);
+        let event = TransferSingle::decode_raw_log(topics.iter().copied(), data, true).unwrap();
+        assert_eq!(U256::from(1), event.id);
+        assert_eq!(U256::from(100), event.value);
+
+        let err = contract
+            .mint(Address::ZERO, U256::from(1), U256::from(1), no_data())
+            .unwrap_err();
+        assert!(matches!(err, Erc1155Error::ERC1155InvalidReceiver(_)));
+
+        let err = contract
+            .mint(ALICE, U256::from(1), U256::MAX, no_data())
+            .unwrap_err();
+        assert!(matches!(err, Erc1155Error::BalanceOverflow(_)));
+    }
+
+    #[test]
+    fn test_mint_rejects_non_owner() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        vm.set_sender(ALICE);
+        let err = contract
+            .mint(ALICE, U256::from(1), U256::from(1), no_data())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Erc1155Error::Unauthorized(Unauthorized { account }) if account == ALICE
+        ));
+
+        let err = contract
+            .mint_batch(ALICE, ids(&[1]), ids(&[1]), no_data())
+            .unwrap_err();
+        assert!(matches!(err, Erc1155Error::Unauthorized(_)));
+        assert_eq!(U256::ZERO, contract.balance_of(ALICE, U256::from(1)));
+    }
+
+    #[test]
+    fn test_mint_batch_and_balance_of_batch() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        contract
+            .mint_batch(ALICE, ids(&[1, 2, 3]), ids(&[10, 20, 30]), no_data())
+            .unwrap();
+        contract.mint(BOB, U256::from(2), U256::from(5), no_data()).unwrap();
+
+        let balances = contract
+            .balance_of_batch(vec![ALICE, ALICE, ALICE, BOB, BOB], ids(&[1, 2, 3, 2, 3]))
+            .unwrap();
+        assert_eq!(ids(&[10, 20, 30, 5, 0]), balances);
+
+        let err = contract
+            .balance_of_batch(vec![ALICE], ids(&[1, 2]))
+            .unwrap_err();
+        assert!(matches!(err, Erc1155Error::ERC1155InvalidArrayLength(_)));
+
+        let err = contract
+            .mint_batch(ALICE, ids(&[1, 2]), ids(&[1]), no_data())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Erc1155Error::ERC1155InvalidArrayLength(ERC1155InvalidArrayLength {
+                idsLength: ids_length,
+                valuesLength: values_length,
+            }) if ids_length == U256::from(2) && values_length == U256::from(1)
+        ));
+    }
+
+    #[test]
+    fn test_safe_transfer_from() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        contract.mint(ALICE, U256::from(1), U256::from(100), no_data()).unwrap();
+
+        // accounts without code accept tokens without a callback
+        vm.set_sender(ALICE);
+        contract
+            .safe_transfer_from(ALICE, BOB, U256::from(1), U256::from(40), Bytes(vec![1, 2, 3]))
+            .unwrap();
+        assert_eq!(U256::from(60), contract.balance_of(ALICE, U256::from(1)));
+        assert_eq!(U256::from(40), contract.balance_of(BOB, U256::from(1)));
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(TransferSingle::SIGNATURE_HASH, topics[0]);
+        assert_eq!(ALICE.into_word(), topics[1]);
+        assert_eq!(ALICE.into_word(), topics[2]);
+        assert_eq!(BOB.into_word(), topics[3]);
+    }
+
+    #[test]
+    fn test_safe_transfer_from_rejects_invalid_transfers() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        contract.mint(ALICE, U256::from(1), U256::from(100), no_data()).unwrap();
+
+        vm.set_sender(ALICE);
+        let err = contract
+            .safe_transfer_from(ALICE, BOB, U256::from(1), U256::from(101), no_data())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Erc1155Error::ERC1155InsufficientBalance(ERC1155InsufficientBalance {
+                balance,
+                needed,
+                ..
+            }) if balance == U256::from(100) && needed == U256::from(101)
+        ));
+
+        let err = contract
+            .safe_transfer_from(ALICE, Address::ZERO, U256::from(1), U256::from(1), no_data())
+            .unwrap_err();
+        assert!(matches!(err, Erc1155Error::ERC1155InvalidReceiver(_)));
+
+        // not the holder, not an operator
+        vm.set_sender(BOB);
+        let err = contract
+            .safe_transfer_from(ALICE, BOB, U256::from(1), U256::from(1), no_data())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Erc1155Error::ERC1155MissingApprovalForAll(ERC1155MissingApprovalForAll {
+                operator,
+                owner,
+            }) if operator == BOB && owner == ALICE
+        ));
+
+        assert_eq!(U256::from(100), contract.balance_of(ALICE, U256::from(1)));
+    }
+
+    #[test]
+    fn test_safe_batch_transfer_from() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        contract
+            .mint_batch(ALICE, ids(&[1, 2, 3]), ids(&[10, 20, 30]), no_data())
+            .unwrap();
+
+        vm.set_sender(ALICE);
+        contract
+            .safe_batch_transfer_from(ALICE, BOB, ids(&[1, 3]), ids(&[4, 30]), no_data())
+            .unwrap();
+
+        let balances = contract
+            .balance_of_batch(
+                vec![ALICE, ALICE, ALICE, BOB, BOB, BOB],
+                ids(&[1, 2, 3, 1, 2, 3]),
+            )
+            .unwrap();
+        assert_eq!(ids(&[6, 20, 0, 4, 0, 30]), balances);
+
+        let logs = vm.get_emitted_logs();
+        let (topics, data) = logs.last().unwrap();
+        assert_eq!(TransferBatch::SIGNATURE_HASH, topics[0]);
+        assert_eq!(ALICE.into_word(), topics[2]);
+        assert_eq!(BOB.into_word(), topics[3]);
+        let event = TransferBatch::decode_raw_log(topics.iter().copied(), data, true).unwrap();
+        assert_eq!(ids(&[1, 3]), event.ids);
+        assert_eq!(ids(&[4, 30]), event.values);
+
+        let err = contract
+            .safe_batch_transfer_from(ALICE, BOB, ids(&[1, 2]), ids(&[1]), no_data())
+            .unwrap_err();
+        assert!(matches!(err, Erc1155Error::ERC1155InvalidArrayLength(_)));
+
+        let err = contract
+            .safe_batch_transfer_from(ALICE, BOB, ids(&[2, 3]), ids(&[1, 1]), no_data())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Erc1155Error::ERC1155InsufficientBalance(ERC1155InsufficientBalance { tokenId: token_id, .. })
+                if token_id == U256::from(3)
+        ));
+    }
+
+    #[test]
+    fn test_set_approval_for_all() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        contract
+            .mint_batch(ALICE, ids(&[1, 2]), ids(&[10, 20]), no_data())
+            .unwrap();
+
+        vm.set_sender(ALICE);
+        contract.set_approval_for_all(BOB, true).unwrap();
+        assert!(contract.is_approved_for_all(ALICE, BOB));
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(ApprovalForAll::SIGNATURE_HASH, topics[0]);
+        assert_eq!(ALICE.into_word(), topics[1]);
+        assert_eq!(BOB.into_word(), topics[2]);
+
+        // an operator can move every id of the holder
+        vm.set_sender(BOB);
+        contract
+            .safe_batch_transfer_from(ALICE, BOB, ids(&[1, 2]), ids(&[10, 20]), no_data())
+            .unwrap();
+        assert_eq!(U256::from(20), contract.balance_of(BOB, U256::from(2)));
+
+        vm.set_sender(ALICE);
+        contract.set_approval_for_all(BOB, false).unwrap();
+        assert!(!contract.is_approved_for_all(ALICE, BOB));
+
+        let err = contract.set_approval_for_all(Address::ZERO, true).unwrap_err();
+        assert!(matches!(err, Erc1155Error::ERC1155InvalidOperator(_)));
+    }
+
+    #[test]
+    fn test_safe_transfer_from_to_contract() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        contract.mint(ALICE, U256::from(1), U256::from(100), no_data()).unwrap();
+        vm.set_code(RECEIVER, vec![0x00]);
+        vm.set_sender(ALICE);
+
+        mock_received(&vm, 1, 40, Ok(encode_selector(ERC1155_RECEIVED)));
+        contract
+            .safe_transfer_from(ALICE, RECEIVER, U256::from(1), U256::from(40), no_data())
+            .unwrap();
+        assert_eq!(U256::from(40), contract.balance_of(RECEIVER, U256::from(1)));
+
+        // the batch selector is the wrong answer to a single transfer
+        mock_received(&vm, 1, 10, Ok(encode_selector(ERC1155_BATCH_RECEIVED)));
+        let err = contract
+            .safe_transfer_from(ALICE, RECEIVER, U256::from(1), U256::from(10), no_data())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Erc1155Error::ERC1155InvalidReceiver(ERC1155InvalidReceiver { receiver })
+                if receiver == RECEIVER
+        ));
+
+        mock_received(&vm, 1, 5, Err(Vec::new()));
+        let err = contract
+            .safe_transfer_from(ALICE, RECEIVER, U256::from(1), U256::from(5), no_data())
+            .unwrap_err();
+        assert!(matches!(err, Erc1155Error::ERC1155InvalidReceiver(_)));
+    }
+
+    #[test]
+    fn test_safe_batch_transfer_from_to_contract() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        contract
+            .mint_batch(ALICE, ids(&[1, 2]), ids(&[10, 20]), no_data())
+            .unwrap();
+        vm.set_code(RECEIVER, vec![0x00]);
+        vm.set_sender(ALICE);
+
+        mock_batch_received(&vm, &[1, 2], &[1, 2], Ok(encode_selector(ERC1155_BATCH_RECEIVED)));
+        contract
+            .safe_batch_transfer_from(ALICE, RECEIVER, ids(&[1, 2]), ids(&[1, 2]), no_data())
+            .unwrap();
+        let balances = contract
+            .balance_of_batch(vec![RECEIVER, RECEIVER], ids(&[1, 2]))
+            .unwrap();
+        assert_eq!(ids(&[1, 2]), balances);
+
+        // the single-transfer selector is the wrong answer to a batch
+        mock_batch_received(&vm, &[1, 2], &[3, 3], Ok(encode_selector(ERC1155_RECEIVED)));
+        let err = contract
+            .safe_batch_transfer_from(ALICE, RECEIVER, ids(&[1, 2]), ids(&[3, 3]), no_data())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Erc1155Error::ERC1155InvalidReceiver(ERC1155InvalidReceiver { receiver })
+                if receiver == RECEIVER
+        ));
+
+        mock_batch_received(&vm, &[1, 2], &[4, 4], Err(Vec::new()));
+        let err = contract
+            .safe_batch_transfer_from(ALICE, RECEIVER, ids(&[1, 2]), ids(&[4, 4]), no_data())
+            .unwrap_err();
+        assert!(matches!(err, Erc1155Error::ERC1155InvalidReceiver(_)));
+    }
+}
+`,
+    solidity: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+interface IERC1155Receiver {
+    function onERC1155Received(
+        address operator,
+        address from,
+        uint256 id,
+        uint256 value,
+        bytes calldata data
+    ) external returns (bytes4);
+
+    function onERC1155BatchReceived(
+        address operator,
+        address from,
+        uint256[] calldata ids,
+        uint256[] calldata values,
+        bytes calldata data
+    ) external returns (bytes4);
+}
+
+contract ERC1155MultiToken {
+    mapping(uint256 => mapping(address => uint256)) private balances;
+    mapping(address => mapping(address => bool)) private operatorApprovals;
+
+    string private _uri;
+
+    address private _owner;
+
+    event TransferSingle(
+        address indexed operator,
+        address indexed from,
+        address indexed to,
+        uint256 id,
+        uint256 value
+    );
+    event TransferBatch(
+        address indexed operator,
+        address indexed from,
+        address indexed to,
+        uint256[] ids,
+        uint256[] values
+    );
+    event ApprovalForAll(address indexed account, address indexed operator, bool approved);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    error ERC1155InsufficientBalance(address sender, uint256 balance, uint256 needed, uint256 tokenId);
+    error ERC1155InvalidSender(address sender);
+    error ERC1155InvalidReceiver(address receiver);
+    error ERC1155MissingApprovalForAll(address operator, address owner);
+    error ERC1155InvalidOperator(address operator);
+    error ERC1155InvalidArrayLength(uint256 idsLength, uint256 valuesLength);
+    error BalanceOverflow(address account, uint256 tokenId);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+
+    modifier onlyOwner() {
+        if (msg.sender != _owner) {
+            revert Unauthorized(msg.sender);
+        }
+        _;
+    }
+
+    constructor(string memory uri_) {
+        _uri = uri_;
+        _transferOwnership(msg.sender);
+    }
+
+    // Clients replace the {id} placeholder in the URI with the hex token id,
+    // as described in EIP-1155.
+    function uri(uint256) public view returns (string memory) {
+        return _uri;
+    }
+
+    function supportsInterface(bytes4 interfaceId) public view returns (bool) {
+        return
+            interfaceId == 0x01ffc9a7 || // ERC-165
+            interfaceId == 0xd9b26a26 || // ERC-1155
+            interfaceId == 0x0e89341c; // ERC-1155 Metadata URI
+    }
+
+    function balanceOf(address account, uint256 id) public view returns (uint256) {
+        return balances[id][account];
+    }
+
+    function balanceOfBatch(
+        address[] memory accounts,
+        uint256[] memory ids
+    ) public view returns (uint256[] memory) {
+        if (accounts.length != ids.length) {
+            revert ERC1155InvalidArrayLength(ids.length, accounts.length);
+        }
+
+        uint256[] memory batchBalances = new uint256[](accounts.length);
+        for (uint256 i = 0; i < accounts.length; ++i) {
+            batchBalances[i] = balanceOf(accounts[i], ids[i]);
+        }
+        return batchBalances;
+    }
+
+    function setApprovalForAll(address operator, bool approved) public {
+        if (operator == address(0)) {
+            revert ERC1155InvalidOperator(operator);
+        }
+
+        operatorApprovals[msg.sender][operator] = approved;
+        emit ApprovalForAll(msg.sender, operator, approved);
+    }
+
+    function isApprovedForAll(address account, address operator) public view returns (bool) {
+        return operatorApprovals[account][operator];
+    }
+
+    // Moves value tokens of one id and, when the receiver is a contract,
+    // requires it to accept them through onERC1155Received.
+    function safeTransferFrom(
+        address from,
+        address to,
+        uint256 id,
+        uint256 value,
+        bytes memory data
+    ) public {
+        _checkTransfer(from, to);
+        _transferSingle(from, to, id, value, data);
+    }
+
+    // Batched safeTransferFrom. ids and values must have the same length, and
+    // a contract receiver is called once through onERC1155BatchReceived.
+    function safeBatchTransferFrom(
+        address from,
+        address to,
+        uint256[] memory ids,
+        uint256[] memory values,
+        bytes memory data
+    ) public {
+        _checkTransfer(from, to);
+        _transferBatch(from, to, ids, values, data);
+    }
+
+    function mint(address to, uint256 id, uint256 value, bytes memory data) public onlyOwner {
+        if (to == address(0)) {
+            revert ERC1155InvalidReceiver(to);
+        }
+        _transferSingle(address(0), to, id, value, data);
+    }
+
+    function mintBatch(
+        address to,
+        uint256[] memory ids,
+        uint256[] memory values,
+        bytes memory data
+    ) public onlyOwner {
+        if (to == address(0)) {
+            revert ERC1155InvalidReceiver(to);
+        }
+        _transferBatch(address(0), to, ids, values, data);
+    }
+
+    function owner() public view returns (address) {
+        return _owner;
+    }
+
+    function transferOwnership(address newOwner) public onlyOwner {
+        if (newOwner == address(0)) {
+            revert InvalidOwner(newOwner);
+        }
+        _transferOwnership(newOwner);
+    }
+
+    function renounceOwnership() public onlyOwner {
+        _transferOwnership(address(0));
+    }
+
+    // Shared checks of both transfer entry points: the caller must be the
+    // holder or one of their operators, and neither end may be the zero
+    // address.
+    function _checkTransfer(address from, address to) internal view {
+        if (from != msg.sender && !isApprovedForAll(from, msg.sender)) {
+            revert ERC1155MissingApprovalForAll(msg.sender, from);
+        }
+        if (to == address(0)) {
+            revert ERC1155InvalidReceiver(to);
+        }
+        if (from == address(0)) {
+            revert ERC1155InvalidSender(from);
+        }
+    }
+
+    // Moves value tokens of id from one account to another. The zero
+    // address as from mints, and its balance is never read.
+    function _moveBalance(address from, address to, uint256 id, uint256 value) internal {
+        if (from != address(0)) {
+            uint256 fromBalance = balances[id][from];
+            if (fromBalance < value) {
+                revert ERC1155InsufficientBalance(from, fromBalance, value, id);
+            }
+            unchecked {
+                balances[id][from] = fromBalance - value;
+            }
+        }
+
+        uint256 toBalance = balances[id][to];
+        if (value > type(uint256).max - toBalance) {
+            revert BalanceOverflow(to, id);
+        }
+        unchecked {
+            balances[id][to] = toBalance + value;
+        }
+    }
+
+    function _transferSingle(
+        address from,
+        address to,
+        uint256 id,
+        uint256 value,
+        bytes memory data
+    ) internal {
+        _moveBalance(from, to, id, value);
+
+        emit TransferSingle(msg.sender, from, to, id, value);
+        _checkOnERC1155Received(msg.sender, from, to, id, value, data);
+    }
+
+    function _transferBatch(
+        address from,
+        address to,
+        uint256[] memory ids,
+        uint256[] memory values,
+        bytes memory data
+    ) internal {
+        if (ids.length != values.length) {
+            revert ERC1155InvalidArrayLength(ids.length, values.length);
+        }
+
+        for (uint256 i = 0; i < ids.length; ++i) {
+            _moveBalance(from, to, ids[i], values[i]);
+        }
+
+        emit TransferBatch(msg.sender, from, to, ids, values);
+        _checkOnERC1155BatchReceived(msg.sender, from, to, ids, values, data);
+    }
+
+    // Accounts without code always accept tokens. Contracts must implement
+    // IERC1155Receiver and return the callback's selector.
+    function _checkOnERC1155Received(
+        address operator,
+        address from,
+        address to,
+        uint256 id,
+        uint256 value,
+        bytes memory data
+    ) internal {
+        if (to.code.length == 0) {
+            return;
+        }
+
+        try IERC1155Receiver(to).onERC1155Received(operator, from, id, value, data) returns (bytes4 retval) {
+            if (retval != IERC1155Receiver.onERC1155Received.selector) {
+                revert ERC1155InvalidReceiver(to);
+            }
+        } catch {
+            revert ERC1155InvalidReceiver(to);
+        }
+    }
+
+    function _checkOnERC1155BatchReceived(
+        address operator,
+        address from,
+        address to,
+        uint256[] memory ids,
+        uint256[] memory values,
+        bytes memory data
+    ) internal {
+        if (to.code.length == 0) {
+            return;
+        }
+
+        try IERC1155Receiver(to).onERC1155BatchReceived(operator, from, ids, values, data) returns (
+            bytes4 retval
+        ) {
+            if (retval != IERC1155Receiver.onERC1155BatchReceived.selector) {
+                revert ERC1155InvalidReceiver(to);
+            }
+        } catch {
+            revert ERC1155InvalidReceiver(to);
+        }
+    }
+
+    function _transferOwnership(address newOwner) internal {
+        address previousOwner = _owner;
+        _owner = newOwner;
+        emit OwnershipTransferred(previousOwner, newOwner);
+    }
+}
+`,
+  },
+  multisig: {
+    name: 'MultiSigWallet',
+    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::vec::Vec;
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, U256},
+    call::Call,
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageBytes, StorageMap, StorageU256, StorageVec},
+    stylus_core::log,
+};
+
+/// A proposed call and its approval state
+#[storage]
+pub struct Transaction {
+    to: StorageAddress,
+    value: StorageU256,
+    data: StorageBytes,
+    executed: StorageBool,
+    confirmations: StorageU256,
+}
+
+#[storage]
+#[entrypoint]
+pub struct MultiSigWallet {
+    owners: StorageVec<StorageAddress>,
+    is_owner: StorageMap<Address, StorageBool>,
+    threshold: StorageU256,
+    transactions: StorageVec<Transaction>,
+    confirmed: StorageMap<U256, StorageMap<Address, StorageBool>>,
+}
+
+sol! {
+    #![sol(all_derives)]
+
+    event Deposit(address indexed sender, uint256 amount);
+    event SubmitTransaction(address indexed owner, uint256 indexed txId, address indexed to, uint256 value, bytes data);
+    event ConfirmTransaction(address indexed owner, uint256 indexed txId);
+    event RevokeConfirmation(address indexed owner, uint256 indexed txId);
+    event ExecuteTransaction(address indexed owner, uint256 indexed txId);
+
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+    error InvalidThreshold(uint256 threshold, uint256 ownerCount);
+    error TransactionNotFound(uint256 txId);
+    error TransactionAlreadyExecuted(uint256 txId);
+    error AlreadyConfirmed(uint256 txId, address owner);
+    error NotConfirmed(uint256 txId, address owner);
+    error InsufficientConfirmations(uint256 txId, uint256 confirmations, uint256 threshold);
+    error ExecutionFailed(uint256 txId);
+}
+
+#[derive(SolidityError, Debug)]
+pub enum MultiSigError {
+    Unauthorized(Unauthorized),
+    InvalidOwner(InvalidOwner),
+    InvalidThreshold(InvalidThreshold),
+    TransactionNotFound(TransactionNotFound),
+    TransactionAlreadyExecuted(TransactionAlreadyExecuted),
+    AlreadyConfirmed(AlreadyConfirmed),
+    NotConfirmed(NotConfirmed),
+    InsufficientConfirmations(InsufficientConfirmations),
+    ExecutionFailed(ExecutionFailed),
+}
+
+#[public]
+impl MultiSigWallet {
+    /// Sets the owners and the number of confirmations a transaction needs.
+    /// Owners must be distinct and non-zero, and the threshold must be
+    /// between 1 and the number of owners.
+    #[constructor]
+    pub fn constructor(
+        &mut self,
+        owners: Vec<Address>,
+        threshold: U256,
+    ) -> Result<(), MultiSigError> {
+        let owner_count = U256::from(owners.len());
+        if threshold.is_zero() || threshold > owner_count {
+            return Err(MultiSigError::InvalidThreshold(InvalidThreshold {
+                threshold,
+                ownerCount: owner_count,
+            }));
+        }
+
+        for owner in owners {
+            if owner == Address::ZERO || self.is_owner.get(owner) {
+                return Err(MultiSigError::InvalidOwner(InvalidOwner { owner }));
+            }
+            self.is_owner.insert(owner, true);
+            self.owners.push(owner);
+        }
+
+        self.threshold.set(threshold);
+        Ok(())
+    }
+
+    #[receive]
+    #[payable]
+    pub fn receive(&mut self) -> Result<(), Vec<u8>> {
+        log(
+            self.vm(),
+            Deposit {
+                sender: self.vm().msg_sender(),
+                amount: self.vm().msg_value(),
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get_owners(&self) -> Vec<Address> {
+        (0..self.owners.len())
+            .filter_map(|i| self.owners.get(i))
+            .collect()
+    }
+
+    pub fn is_owner(&self, account: Address) -> bool {
+        self.is_owner.get(account)
+    }
+
+    pub fn threshold(&self) -> U256 {
+        self.threshold.get()
+    }
+
+    pub fn transaction_count(&self) -> U256 {
+        U256::from(self.transactions.len())
+    }
+
+    /// Returns the target, value, calldata, executed flag and confirmation
+    /// count of a transaction.
+    pub fn get_transaction(
+        &self,
+        tx_id: U256,
+    ) -> Result<(Address, U256, Bytes, bool, U256), MultiSigError> {
+        let tx = self
+            .transactions
+            .getter(tx_id)
+            .ok_or(MultiSigError::TransactionNotFound(TransactionNotFound { txId: tx_id }))?;
+
+        Ok((
+            tx.to.get(),
+            tx.value.get(),
+            Bytes(tx.data.get_bytes()),
+            tx.executed.get(),
+            tx.confirmations.get(),
+        ))
+    }
+
+    pub fn is_confirmed(&self, tx_id: U256, owner: Address) -> bool {
+        self.confirmed.getter(tx_id).get(owner)
+    }
+
+    /// Proposes a call of to with value wei and data. The submitter still has
+    /// to confirm it like every other owner.
+    pub fn submit_transaction(
+        &mut self,
+        to: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<U256, MultiSigError> {
+        let owner = self.only_owner()?;
+
+        let tx_id = U256::from(self.transactions.len());
+        let mut tx = self.transactions.grow();
+        tx.to.set(to);
+        tx.value.set(value);
+        tx.data.set_bytes(&data.0);
+
+        log(
+            self.vm(),
+            SubmitTransaction {
+                owner,
+                txId: tx_id,
+                to,
+                value,
+                data: data.0.into(),
+            },
+        );
+        Ok(tx_id)
+    }
+
+    pub fn confirm(&mut self, tx_id: U256) -> Result<(), MultiSigError> {
+        let owner = self.only_owner()?;
+        self.require_pending(tx_id)?;
+        if self.is_confirmed(tx_id, owner) {
+            return Err(MultiSigError::AlreadyConfirmed(AlreadyConfirmed {
+                txId: tx_id,
+                owner,
+            }));
+        }
+
+        self.confirmed.setter(tx_id).insert(owner, true);
+        let mut tx = self.transactions.setter(tx_id).unwrap();
+        let confirmations = tx.confirmations.get();
+        tx.confirmations.set(confirmations + U256::from(1));
+
+        log(self.vm(), ConfirmTransaction { owner, txId: tx_id });
+        Ok(())
+    }
+
+    pub fn revoke(&mut self, tx_id: U256) -> Result<(), MultiSigError> {
+        let owner = self.only_owner()?;
+        self.require_pending(tx_id)?;
+        if !self.is_confirmed(tx_id, owner) {
+            return Err(MultiSigError::NotConfirmed(NotConfirmed { txId: tx_id, owner }));
+        }
+
+        self.confirmed.setter(tx_id).insert(owner, false);
+        let mut tx = self.transactions.setter(tx_id).unwrap();
+        let confirmations = tx.confirmations.get();
+        tx.confirmations.set(confirmations - U256::from(1));
+
+        log(self.vm(), RevokeConfirmation { owner, txId: tx_id });
+        Ok(())
+    }
+
+    /// Performs the call once enough owners have confirmed it. The
+    /// transaction is marked executed before the call, so a reentrant
+    /// execute of the same id fails even if the crate is built with the
+    /// stylus-sdk "reentrant" feature. A failed call reverts the whole
+    /// transaction, which clears the flag again.
+    pub fn execute(&mut self, tx_id: U256) -> Result<(), MultiSigError> {
+        let owner = self.only_owner()?;
+        self.require_pending(tx_id)?;
+
+        let threshold = self.threshold.get();
+        let (to, value, data) = {
+            let mut tx = self.transactions.setter(tx_id).unwrap();
+            let confirmations = tx.confirmations.get();
+            if confirmations < threshold {
+                return Err(MultiSigError::InsufficientConfirmations(
+                    InsufficientConfirmations {
+                        txId: tx_id,
+                        confirmations,
+                        threshold,
+                    },
+                ));
+            }
+
+            tx.executed.set(true);
+            (tx.to.get(), tx.value.get(), tx.data.get_bytes())
+        };
+
+        let context = Call::new_payable(self, value);
+        if self.vm().call(&context, to, &data).is_err() {
+            return Err(MultiSigError::ExecutionFailed(ExecutionFailed { txId: tx_id }));
+        }
+
+        log(self.vm(), ExecuteTransaction { owner, txId: tx_id });
+        Ok(())
+    }
+}
+
+impl MultiSigWallet {
+    fn only_owner(&self) -> Result<Address, MultiSigError> {
+        let sender = self.vm().msg_sender();
+        if !self.is_owner.get(sender) {
+            return Err(MultiSigError::Unauthorized(Unauthorized { account: sender }));
+        }
+        Ok(sender)
+    }
+
+    /// Fails unless tx_id exists and has not been executed
+    fn require_pending(&self, tx_id: U256) -> Result<(), MultiSigError> {
+        let tx = self
+            .transactions
+            .getter(tx_id)
+            .ok_or(MultiSigError::TransactionNotFound(TransactionNotFound { txId: tx_id }))?;
+        if tx.executed.get() {
+            return Err(MultiSigError::TransactionAlreadyExecuted(
+                TransactionAlreadyExecuted { txId: tx_id },
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_sol_types::SolEvent;
+    use stylus_sdk::testing::*;
+
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+    const CAROL: Address = Address::repeat_byte(0xc0);
+    const MALLORY: Address = Address::repeat_byte(0xee);
+    const TARGET: Address = Address::repeat_byte(0x77);
+
+    /// A 2-of-3 wallet owned by ALICE, BOB and CAROL, with ALICE as the caller
+    fn deploy(vm: &TestVM) -> MultiSigWallet {
+        let mut contract = MultiSigWallet::from(vm);
+        contract
+            .constructor(vec![ALICE, BOB, CAROL], U256::from(2))
+            .unwrap();
+        vm.set_sender(ALICE);
+        contract
+    }
+
+    /// Submits a call of TARGET with 5 wei and calldata 0xdeadbeef
+    fn submit(contract: &mut MultiSigWallet) -> U256 {
+        contract
+            .submit_transaction(TARGET, U256::from(5), Bytes(vec![0xde, 0xad, 0xbe, 0xef]))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_constructor() {
+        let vm = TestVM::default();
+        let contract = deploy(&vm);
+
+        assert_eq!(vec![ALICE, BOB, CAROL], contract.get_owners());
+        assert!(contract.is_owner(BOB));
+        assert!(!contract.is_owner(MALLORY));
+        assert_eq!(U256::from(2), contract.threshold());
+    }
+
+    #[test]
+    fn test_constructor_rejects_invalid_setup() {
+        let vm = TestVM::default();
+
+        let mut contract = MultiSigWallet::from(&vm);
+        let err = contract
+            .constructor(vec![ALICE, BOB], U256::from(3))
+            .unwrap_err();
+        assert!(matches!(err, MultiSigError::InvalidThreshold(_)));
+
+        let err = contract.constructor(vec![ALICE], U256::ZERO).unwrap_err();
+        assert!(matches!(err, MultiSigError::InvalidThreshold(_)));
+
+        let err = contract
+            .constructor(vec![ALICE, Address::ZERO], U256::from(1))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            MultiSigError::InvalidOwner(InvalidOwner { owner }) if owner == Address::ZERO
+        ));
+
+        let vm = TestVM::default();
+        let mut contract = MultiSigWallet::from(&vm);
+        let err = contract
+            .constructor(vec![ALICE, BOB, ALICE], U256::from(2))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            MultiSigError::InvalidOwner(InvalidOwner { owner }) if owner == ALICE
+        ));
+    }
+
+    #[test]
+    fn test_submit_transaction() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        assert_eq!(U256::ZERO, submit(&mut contract));
+        assert_eq!(U256::from(1), submit(&mut contract));
+        assert_eq!(U256::from(2), contract.transaction_count());
+
+        let (to, value, data, executed, confirmations) =
+            contract.get_transaction(U256::from(1)).unwrap();
+        assert_eq!(TARGET, to);
+        assert_eq!(U256::from(5), value);
+        assert_eq!(vec![0xde, 0xad, 0xbe, 0xef], data.0);
+        assert!(!executed);
+        assert_eq!(U256::ZERO, confirmations);
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(SubmitTransaction::SIGNATURE_HASH, topics[0]);
+        assert_eq!(ALICE.into_word(), topics[1]);
+        assert_eq!(U256::from(1).to_be_bytes::<32>(), topics[2].0);
+        assert_eq!(TARGET.into_word(), topics[3]);
+
+        let err = contract.get_transaction(U256::from(2)).unwrap_err();
+        assert!(matches!(err, MultiSigError::TransactionNotFound(_)));
+    }
+
+    #[test]
+    fn test_only_owners_can_act() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let tx_id = submit(&mut contract);
+
+        vm.set_sender(MALLORY);
+        let err = contract
+            .submit_transaction(TARGET, U256::ZERO, Bytes(Vec::new()))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            MultiSigError::Unauthorized(Unauthorized { account }) if account == MALLORY
+        ));
+        assert!(matches!(
+            contract.confirm(tx_id).unwrap_err(),
+            MultiSigError::Unauthorized(_)
+        ));
+        assert!(matches!(
+            contract.revoke(tx_id).unwrap_err(),
+            MultiSigError::Unauthorized(_)
+        ));
+        assert!(matches!(
+            contract.execute(tx_id).unwrap_err(),
+            MultiSigError::Unauthorized(_)
+        ));
+    }
+
+    #[test]
+    fn test_confirm_and_revoke() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let tx_id = submit(&mut contract);
+
+        contract.confirm(tx_id).unwrap();
+        assert!(contract.is_confirmed(tx_id, ALICE));
+        let (_, _, _, _, confirmations) = contract.get_transaction(tx_id).unwrap();
+        assert_eq!(U256::from(1), confirmations);
+
+        let err = contract.confirm(tx_id).unwrap_err();
+        assert!(matches!(
+            err,
+            MultiSigError::AlreadyConfirmed(AlreadyConfirmed { owner, .. }) if owner == ALICE
+        ));
+
+        contract.revoke(tx_id).unwrap();
+        assert!(!contract.is_confirmed(tx_id, ALICE));
+        let (_, _, _, _, confirmations) = contract.get_transaction(tx_id).unwrap();
+        assert_eq!(U256::ZERO, confirmations);
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(RevokeConfirmation::SIGNATURE_HASH, topics[0]);
+
+        let err = contract.revoke(tx_id).unwrap_err();
+        assert!(matches!(err, MultiSigError::NotConfirmed(_)));
+
+        let err = contract.confirm(U256::from(9)).unwrap_err();
+        assert!(matches!(err, MultiSigError::TransactionNotFound(_)));
+    }
+
+    #[test]
+    fn test_execute() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let tx_id = submit(&mut contract);
+        contract.confirm(tx_id).unwrap();
+
+        let err = contract.execute(tx_id).unwrap_err();
+        assert!(matches!(
+            err,
+            MultiSigError::InsufficientConfirmations(InsufficientConfirmations {
+                confirmations,
+                threshold,
+                ..
+            }) if confirmations == U256::from(1) && threshold == U256::from(2)
+        ));
+
+        vm.set_sender(BOB);
+        contract.confirm(tx_id).unwrap();
+
+        // the call must carry the stored value and calldata
+        vm.mock_call(TARGET, vec![0xde, 0xad, 0xbe, 0xef], U256::from(5), Ok(Vec::new()));
+        contract.execute(tx_id).unwrap();
+
+        let (_, _, _, executed, _) = contract.get_transaction(tx_id).unwrap();
+        assert!(executed);
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(ExecuteTransaction::SIGNATURE_HASH, topics[0]);
+        assert_eq!(BOB.into_word(), topics[1]);
+
+        // an executed transaction is final
+        let err = contract.execute(tx_id).unwrap_err();
+        assert!(matches!(err, MultiSigError::TransactionAlreadyExecuted(_)));
+        let err = contract.revoke(tx_id).unwrap_err();
+        assert!(matches!(err, MultiSigError::TransactionAlreadyExecuted(_)));
+    }
+
+    #[test]
+    fn test_execute_reports_failed_call() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        let tx_id = submit(&mut contract);
+        contract.confirm(tx_id).unwrap();
+        vm.set_sender(BOB);
+        contract.confirm(tx_id).unwrap();
+
+        vm.mock_call(TARGET, vec![0xde, 0xad, 0xbe, 0xef], U256::from(5), Err(Vec::new()));
+        let err = contract.execute(tx_id).unwrap_err();
+        assert!(matches!(
+            err,
+            MultiSigError::ExecutionFailed(ExecutionFailed { txId: id }) if id == tx_id
+        ));
+
+        // On chain the error reverts the executed flag with everything else,
+        // but TestVM keeps storage as it was during the call. The transaction
+        // is already marked there, so a target calling back into execute
+        // would be turned away.
+        let (_, _, _, executed, _) = contract.get_transaction(tx_id).unwrap();
+        assert!(executed);
+
+        vm.set_sender(CAROL);
+        let err = contract.execute(tx_id).unwrap_err();
+        assert!(matches!(
+            err,
+            MultiSigError::TransactionAlreadyExecuted(TransactionAlreadyExecuted { txId: id })
+                if id == tx_id
+        ));
+    }
+}
+`,
+    solidity: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+// M-of-N wallet: any owner can propose a call, and once threshold owners
+// have confirmed it any owner can execute it.
+contract MultiSigWallet {
+    // A proposed call and its approval state
+    struct Transaction {
+        address to;
+        uint256 value;
+        bytes data;
+        bool executed;
+        uint256 confirmations;
+    }
+
+    address[] private owners;
+    mapping(address => bool) private _isOwner;
+    uint256 private _threshold;
+    Transaction[] private transactions;
+    mapping(uint256 => mapping(address => bool)) private confirmed;
+
+    event Deposit(address indexed sender, uint256 amount);
+    event SubmitTransaction(
+        address indexed owner,
+        uint256 indexed txId,
+        address indexed to,
+        uint256 value,
+        bytes data
+    );
+    event ConfirmTransaction(address indexed owner, uint256 indexed txId);
+    event RevokeConfirmation(address indexed owner, uint256 indexed txId);
+    event ExecuteTransaction(address indexed owner, uint256 indexed txId);
+
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+    error InvalidThreshold(uint256 threshold, uint256 ownerCount);
+    error TransactionNotFound(uint256 txId);
+    error TransactionAlreadyExecuted(uint256 txId);
+    error AlreadyConfirmed(uint256 txId, address owner);
+    error NotConfirmed(uint256 txId, address owner);
+    error InsufficientConfirmations(uint256 txId, uint256 confirmations, uint256 threshold);
+    error ExecutionFailed(uint256 txId);
+
+    modifier onlyOwner() {
+        if (!_isOwner[msg.sender]) {
+            revert Unauthorized(msg.sender);
+        }
+        _;
+    }
+
+    // Fails unless txId exists and has not been executed
+    modifier pending(uint256 txId) {
+        if (txId >= transactions.length) {
+            revert TransactionNotFound(txId);
+        }
+        if (transactions[txId].executed) {
+            revert TransactionAlreadyExecuted(txId);
+        }
+        _;
+    }
+
+    // Owners must be distinct and non-zero, and the threshold must be
+    // between 1 and the number of owners.
+    constructor(address[] memory owners_, uint256 threshold_) {
+        if (threshold_ == 0 || threshold_ > owners_.length) {
+            revert InvalidThreshold(threshold_, owners_.length);
+        }
+
+        for (uint256 i = 0; i < owners_.length; ++i) {
+            address owner = owners_[i];
+            if (owner == address(0) || _isOwner[owner]) {
+                revert InvalidOwner(owner);
+            }
+            _isOwner[owner] = true;
+            owners.push(owner);
+        }
+
+        _threshold = threshold_;
+    }
+
+    receive() external payable {
+        emit Deposit(msg.sender, msg.value);
+    }
+
+    function getOwners() public view returns (address[] memory) {
+        return owners;
+    }
+
+    function isOwner(address account) public view returns (bool) {
+        return _isOwner[account];
+    }
+
+    function threshold() public view returns (uint256) {
+        return _threshold;
+    }
+
+    function transactionCount() public view returns (uint256) {
+        return transactions.length;
+    }
+
+    // Returns the target, value, calldata, executed flag and confirmation
+    // count of a transaction.
+    function getTransaction(
+        uint256 txId
+    ) public view returns (address, uint256, bytes memory, bool, uint256) {
+        if (txId >= transactions.length) {
+            revert TransactionNotFound(txId);
+        }
+
+        Transaction storage transaction = transactions[txId];
+        return (
+            transaction.to,
+            transaction.value,
+            transaction.data,
+            transaction.executed,
+            transaction.confirmations
+        );
+    }
+
+    function isConfirmed(uint256 txId, address owner) public view returns (bool) {
+        return confirmed[txId][owner];
+    }
+
+    // Proposes a call of to with value wei and data. The submitter still has
+    // to confirm it like every other owner.
+    function submitTransaction(
+        address to,
+        uint256 value,
+        bytes memory data
+    ) public onlyOwner returns (uint256) {
+        uint256 txId = transactions.length;
+        transactions.push(
+            Transaction({to: to, value: value, data: data, executed: false, confirmations: 0})
+        );
+
+        emit SubmitTransaction(msg.sender, txId, to, value, data);
+        return txId;
+    }
+
+    function confirm(uint256 txId) public onlyOwner pending(txId) {
+        if (confirmed[txId][msg.sender]) {
+            revert AlreadyConfirmed(txId, msg.sender);
+        }
+
+        confirmed[txId][msg.sender] = true;
+        transactions[txId].confirmations += 1;
+
+        emit ConfirmTransaction(msg.sender, txId);
+    }
+
+    function revoke(uint256 txId) public onlyOwner pending(txId) {
+        if (!confirmed[txId][msg.sender]) {
+            revert NotConfirmed(txId, msg.sender);
+        }
+
+        confirmed[txId][msg.sender] = false;
+        transactions[txId].confirmations -= 1;
+
+        emit RevokeConfirmation(msg.sender, txId);
+    }
+
+    // Performs the call once enough owners have confirmed it. The transaction
+    // is marked executed before the call, so a reentrant execute of the same
+    // id fails. A failed call reverts the whole transaction, which clears the
+    // flag again.
+    function execute(uint256 txId) public onlyOwner pending(txId) {
+        Transaction storage transaction = transactions[txId];
+        if (transaction.confirmations < _threshold) {
+            revert InsufficientConfirmations(txId, transaction.confirmations, _threshold);
+        }
+
+        transaction.executed = true;
+
+        (bool success, ) = transaction.to.call{value: transaction.value}(transaction.data);
+        if (!success) {
+            revert ExecutionFailed(txId);
+        }
+
+        emit ExecuteTransaction(msg.sender, txId);
+    }
+}
+`,
+  },
+  upgradeable: {
+    name: 'UpgradeableCounter',
+    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    alloy_primitives::{b256, Address, B256, U256},
+    call::Call,
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageU256},
+    stylus_core::log,
+};
+
+/// ERC-1967 implementation slot, keccak256("eip1967.proxy.implementation") - 1.
+/// Under a proxy this is read and written in the proxy's storage.
+const IMPLEMENTATION_SLOT: B256 =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+sol_interface! {
+    interface IERC1822Proxiable {
+        function proxiableUUID() external view returns (bytes32);
+    }
+}
+
+/// UUPS implementation of a counter, meant to run behind the ERC-1967 proxy
+/// in contracts-solidity/proxy. The proxy keeps only the implementation
+/// address, at IMPLEMENTATION_SLOT, so these fields start at slot 0 of the
+/// proxy's storage. Their order and types match the Solidity version, which
+/// packs owner and initialized into slot 1 the same way, so one can replace
+/// the other in an upgrade. New fields must only ever be appended.
+#[storage]
+#[entrypoint]
+pub struct UpgradeableCounter {
+    number: StorageU256,
+    owner: StorageAddress,
+    initialized: StorageBool,
+}
+
+sol! {
+    #![sol(all_derives)]
+
+    event NumberSet(uint256 oldValue, uint256 newValue);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+    event Upgraded(address indexed implementation);
+
+    error CounterOverflow(uint256 value);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+    error AlreadyInitialized();
+    error ERC1967InvalidImplementation(address implementation);
+    error UUPSUnauthorizedCallContext();
+}
+
+#[derive(SolidityError, Debug)]
+pub enum UpgradeError {
+    CounterOverflow(CounterOverflow),
+    Unauthorized(Unauthorized),
+    InvalidOwner(InvalidOwner),
+    AlreadyInitialized(AlreadyInitialized),
+    ERC1967InvalidImplementation(ERC1967InvalidImplementation),
+    UUPSUnauthorizedCallContext(UUPSUnauthorizedCallContext),
+}
+
+#[public]
+impl UpgradeableCounter {
+    /// Runs once, in the implementation's own storage, and locks it: the bare
+    /// implementation can never be initialized, so it has no owner who could
+    /// upgrade it. Proxies are set up through initialize instead.
+    #[constructor]
+    pub fn constructor(&mut self) {
+        self.initialized.set(true);
+    }
+
+    /// Sets up a proxy's storage. The proxy calls this with the initializer
+    /// calldata passed to its constructor, in the same transaction as its
+    /// deployment, so nobody can initialize it first.
+    pub fn initialize(&mut self, owner: Address) -> Result<(), UpgradeError> {
+        if self.initialized.get() {
+            return Err(UpgradeError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if owner == Address::ZERO {
+            return Err(UpgradeError::InvalidOwner(InvalidOwner { owner }));
+        }
+
+        self.initialized.set(true);
+        self.write_owner(owner);
+        Ok(())
+    }
+
+    pub fn number(&self) -> U256 {
+        self.number.get()
+    }
+
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    pub fn set_number(&mut self, new_number: U256) -> Result<(), UpgradeError> {
+        self.only_owner()?;
+        self.write_number(new_number);
+        Ok(())
+    }
+
+    pub fn increment(&mut self) -> Result<(), UpgradeError> {
+        let number = self.number.get();
+        let next = number
+            .checked_add(U256::from(1))
+            .ok_or(UpgradeError::CounterOverflow(CounterOverflow { value: number }))?;
+        self.write_number(next);
+        Ok(())
+    }
+
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), UpgradeError> {
+        self.only_owner()?;
+        if new_owner == Address::ZERO {
+            return Err(UpgradeError::InvalidOwner(InvalidOwner { owner: new_owner }));
+        }
+        self.write_owner(new_owner);
+        Ok(())
+    }
+
+    /// ERC-1822 marker: a contract returning the ERC-1967 slot here can be
+    /// upgraded to. Reverts when called through a proxy, where the slot is
+    /// set, so that a proxy can never be installed as an implementation.
+    #[selector(name = "proxiableUUID")]
+    pub fn proxiable_uuid(&self) -> Result<B256, UpgradeError> {
+        if self.implementation() != Address::ZERO {
+            return Err(UpgradeError::UUPSUnauthorizedCallContext(
+                UUPSUnauthorizedCallContext {},
+            ));
+        }
+        Ok(IMPLEMENTATION_SLOT)
+    }
+
+    /// Current implementation, as recorded in the ERC-1967 slot
+    pub fn implementation(&self) -> Address {
+        let word = self.vm().storage_load_bytes32(Self::implementation_slot());
+        Address::from_word(word)
+    }
+
+    /// Points the proxy at a new implementation. Only the owner can upgrade,
+    /// and the new implementation must itself be UUPS, so that it can be
+    /// upgraded again.
+    pub fn upgrade_to(&mut self, new_implementation: Address) -> Result<(), UpgradeError> {
+        self.only_owner()?;
+
+        let uuid =
+            IERC1822Proxiable::new(new_implementation).proxiable_uuid(self.vm(), Call::new());
+        if !matches!(uuid, Ok(uuid) if uuid == IMPLEMENTATION_SLOT) {
+            return Err(UpgradeError::ERC1967InvalidImplementation(
+                ERC1967InvalidImplementation {
+                    implementation: new_implementation,
+                },
+            ));
+        }
+
+        // Safety: the ERC-1967 slot is hashed, so no field above can overlap it
+        let slot = Self::implementation_slot();
+        unsafe {
+            self.vm().storage_cache_bytes32(slot, new_implementation.into_word());
+        }
+        log(
+            self.vm(),
+            Upgraded {
+                implementation: new_implementation,
+            },
+        );
+        Ok(())
+    }
+}
+
+impl UpgradeableCounter {
+    fn implementation_slot() -> U256 {
+        U256::from_be_bytes(IMPLEMENTATION_SLOT.0)
+    }
+
+    fn only_owner(&self) -> Result<(), UpgradeError> {
+        let sender = self.vm().msg_sender();
+        if sender != self.owner.get() {
+            return Err(UpgradeError::Unauthorized(Unauthorized { account: sender }));
+        }
+        Ok(())
+    }
+
+    fn write_number(&mut self, new_number: U256) {
+        let old_number = self.number.get();
+        self.number.set(new_number);
+        log(
+            self.vm(),
+            NumberSet {
+                oldValue: old_number,
+                newValue: new_number,
+            },
+        );
+    }
+
+    fn write_owner(&mut self, new_owner: Address) {
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+        log(
+            self.vm(),
+            OwnershipTransferred {
+                previousOwner: previous_owner,
+                newOwner: new_owner,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_sol_types::{SolCall, SolEvent};
+    use stylus_sdk::testing::*;
+
+    const OWNER: Address = Address::repeat_byte(0x11);
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const NEW_IMPLEMENTATION: Address = Address::repeat_byte(0x22);
+
+    sol! {
+        function proxiableUUID() external view returns (bytes32);
+    }
+
+    /// A proxy's view of the contract: fresh storage, initialized for OWNER,
+    /// with OWNER as the caller.
+    fn deploy(vm: &TestVM) -> UpgradeableCounter {
+        let mut contract = UpgradeableCounter::from(vm);
+        contract.initialize(OWNER).unwrap();
+        vm.set_sender(OWNER);
+        contract
+    }
+
+    fn mock_proxiable_uuid(vm: &TestVM, implementation: Address, uuid: B256) {
+        let data = proxiableUUIDCall {}.abi_encode();
+        vm.mock_static_call(implementation, data, Ok(uuid.to_vec()));
+    }
+
+    #[test]
+    fn test_initialize() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        assert_eq!(OWNER, contract.owner());
+
+        let err = contract.initialize(ALICE).unwrap_err();
+        assert!(matches!(err, UpgradeError::AlreadyInitialized(_)));
+        assert_eq!(OWNER, contract.owner());
+
+        let vm = TestVM::default();
+        let mut contract = UpgradeableCounter::from(&vm);
+        let err = contract.initialize(Address::ZERO).unwrap_err();
+        assert!(matches!(err, UpgradeError::InvalidOwner(_)));
+    }
+
+    #[test]
+    fn test_constructor_locks_implementation() {
+        let vm = TestVM::default();
+        let mut contract = UpgradeableCounter::from(&vm);
+        contract.constructor();
+
+        let err = contract.initialize(ALICE).unwrap_err();
+        assert!(matches!(err, UpgradeError::AlreadyInitialized(_)));
+        assert_eq!(Address::ZERO, contract.owner());
+    }
+
+    #[test]
+    fn test_counter() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        contract.set_number(U256::from(41)).unwrap();
+        contract.increment().unwrap();
+        assert_eq!(U256::from(42), contract.number());
+
+        vm.set_sender(ALICE);
+        let err = contract.set_number(U256::ZERO).unwrap_err();
+        assert!(matches!(
+            err,
+            UpgradeError::Unauthorized(Unauthorized { account }) if account == ALICE
+        ));
+
+        vm.set_sender(OWNER);
+        contract.set_number(U256::MAX).unwrap();
+        let err = contract.increment().unwrap_err();
+        assert!(matches!(err, UpgradeError::CounterOverflow(_)));
+    }
+
+    // The Solidity implementation declares the same fields in the same order:
+    // number in slot 0, then owner and initialized packed into slot 1.
+    #[test]
+    fn test_storage_layout() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        contract.set_number(U256::from(7)).unwrap();
+
+        let slot0 = vm.storage_load_bytes32(U256::ZERO);
+        assert_eq!(U256::from(7), U256::from_be_bytes(slot0.0));
+
+        let slot1 = vm.storage_load_bytes32(U256::from(1));
+        assert_eq!(OWNER.as_slice(), &slot1[12..]);
+        assert_eq!(1, slot1[11]);
+    }
+
+    #[test]
+    fn test_upgrade_to() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        assert_eq!(IMPLEMENTATION_SLOT, contract.proxiable_uuid().unwrap());
+        assert_eq!(Address::ZERO, contract.implementation());
+
+        mock_proxiable_uuid(&vm, NEW_IMPLEMENTATION, IMPLEMENTATION_SLOT);
+        contract.upgrade_to(NEW_IMPLEMENTATION).unwrap();
+        assert_eq!(NEW_IMPLEMENTATION, contract.implementation());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(Upgraded::SIGNATURE_HASH, topics[0]);
+        assert_eq!(NEW_IMPLEMENTATION.into_word(), topics[1]);
+    }
+
+    #[test]
+    fn test_proxiable_uuid_rejects_calls_through_proxy() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        // once the slot is set, this storage belongs to a proxy
+        mock_proxiable_uuid(&vm, NEW_IMPLEMENTATION, IMPLEMENTATION_SLOT);
+        contract.upgrade_to(NEW_IMPLEMENTATION).unwrap();
+
+        let err = contract.proxiable_uuid().unwrap_err();
+        assert!(matches!(err, UpgradeError::UUPSUnauthorizedCallContext(_)));
+    }
+
+    #[test]
+    fn test_upgrade_to_rejects_non_owner() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+        mock_proxiable_uuid(&vm, NEW_IMPLEMENTATION, IMPLEMENTATION_SLOT);
+
+        vm.set_sender(ALICE);
+        let err = contract.upgrade_to(NEW_IMPLEMENTATION).unwrap_err();
+        assert!(matches!(
+            err,
+            UpgradeError::Unauthorized(Unauthorized { account }) if account == ALICE
+        ));
+        assert_eq!(Address::ZERO, contract.implementation());
+    }
+
+    #[test]
+    fn test_upgrade_to_rejects_non_uups_implementation() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm);
+
+        mock_proxiable_uuid(&vm, NEW_IMPLEMENTATION, B256::ZERO);
+        let err = contract.upgrade_to(NEW_IMPLEMENTATION).unwrap_err();
+        assert!(matches!(
+            err,
+            UpgradeError::ERC1967InvalidImplementation(ERC1967InvalidImplementation {
+                implementation,
+            }) if implementation == NEW_IMPLEMENTATION
+        ));
+        assert_eq!(Address::ZERO, contract.implementation());
+    }
+}
+`,
+    solidity: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+interface IERC1822Proxiable {
+    function proxiableUUID() external view returns (bytes32);
+}
+
+// UUPS implementation of a counter, meant to run behind the ERC-1967 proxy
+// in proxy/ERC1967Proxy.sol. The state variables start at slot 0 of the
+// proxy's storage and match the Rust version field for field, so one can
+// replace the other in an upgrade. New variables must only ever be appended.
+contract UpgradeableCounter {
+    // keccak256("eip1967.proxy.implementation") - 1
+    bytes32 private constant IMPLEMENTATION_SLOT =
+        0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc;
+
+    uint256 private _number;
+    address private _owner;
+    bool private initialized;
+
+    event NumberSet(uint256 oldValue, uint256 newValue);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+    event Upgraded(address indexed implementation);
+
+    error CounterOverflow(uint256 value);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+    error AlreadyInitialized();
+    error ERC1967InvalidImplementation(address implementation);
+    error UUPSUnauthorizedCallContext();
+
+    modifier onlyOwner() {
+        if (msg.sender != _owner) {
+            revert Unauthorized(msg.sender);
+        }
+        _;
+    }
+
+    // Runs once, in the implementation's own storage, and locks it: the bare
+    // implementation can never be initialized, so it has no owner who could
+    // upgrade it. Proxies are set up through initialize instead.
+    constructor() {
+        initialized = true;
+    }
+
+    function initialize(address owner_) public {
+        if (initialized) {
+            revert AlreadyInitialized();
+        }
+        if (owner_ == address(0)) {
+            revert InvalidOwner(owner_);
+        }
+
+        initialized = true;
+        _transferOwnership(owner_);
+    }
+
+    function number() public view returns (uint256) {
+        return _number;
+    }
+
+    function owner() public view returns (address) {
+        return _owner;
+    }
+
+    function setNumber(uint256 newNumber) public onlyOwner {
+        _setNumber(newNumber);
+    }
+
+    function increment() public {
+        if (_number == type(uint256).max) {
+            revert CounterOverflow(_number);
+        }
+        _setNumber(_number + 1);
+    }
+
+    function transferOwnership(address newOwner) public onlyOwner {
+        if (newOwner == address(0)) {
+            revert InvalidOwner(newOwner);
+        }
+        _transferOwnership(newOwner);
+    }
+
+    // ERC-1822 marker: a contract returning the ERC-1967 slot here can be
+    // upgraded to. Reverts when called through a proxy, where the slot is
+    // set, so that a proxy can never be installed as an implementation.
+    function proxiableUUID() public view virtual returns (bytes32) {
+        if (implementation() != address(0)) {
+            revert UUPSUnauthorizedCallContext();
+        }
+        return IMPLEMENTATION_SLOT;
+    }
+
+    // Current implementation, as recorded in the ERC-1967 slot
+    function implementation() public view returns (address impl) {
+        assembly {
+            impl := sload(IMPLEMENTATION_SLOT)
+        }
+    }
+
+    // Points the proxy at a new implementation. Only the owner can upgrade,
+    // and the new implementation must itself be UUPS, so that it can be
+    // upgraded again.
+    function upgradeTo(address newImplementation) public onlyOwner {
+        try IERC1822Proxiable(newImplementation).proxiableUUID() returns (bytes32 uuid) {
+            if (uuid != IMPLEMENTATION_SLOT) {
+                revert ERC1967InvalidImplementation(newImplementation);
+            }
+        } catch {
+            revert ERC1967InvalidImplementation(newImplementation);
+        }
+
+        assembly {
+            sstore(IMPLEMENTATION_SLOT, newImplementation)
+        }
+        emit Upgraded(newImplementation);
+    }
+
+    function _setNumber(uint256 newNumber) internal {
+        uint256 oldNumber = _number;
+        _number = newNumber;
+        emit NumberSet(oldNumber, newNumber);
+    }
+
+    function _transferOwnership(address newOwner) internal {
+        address previousOwner = _owner;
+        _owner = newOwner;
+        emit OwnershipTransferred(previousOwner, newOwner);
+    }
+}
+`,
+    proxy: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+// Minimal ERC-1967 proxy. Every call is delegated to the implementation
+// stored at the ERC-1967 slot, and the implementation (UUPS) carries the
+// upgrade logic. The proxy declares no state variables, so the
+// implementation's storage starts at slot 0.
+contract ERC1967Proxy {
+    // keccak256("eip1967.proxy.implementation") - 1
+    bytes32 internal constant IMPLEMENTATION_SLOT =
+        0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc;
+
+    event Upgraded(address indexed implementation);
+
+    error ERC1967InvalidImplementation(address implementation);
+
+    // data is the initializer calldata. It is delegated in the deployment
+    // transaction, so nobody can initialize the proxy before its deployer.
+    constructor(address implementation, bytes memory data) payable {
+        if (implementation.code.length == 0) {
+            revert ERC1967InvalidImplementation(implementation);
+        }
+
+        assembly {
+            sstore(IMPLEMENTATION_SLOT, implementation)
+        }
+        emit Upgraded(implementation);
+
+        if (data.length > 0) {
+            (bool success, bytes memory returndata) = implementation.delegatecall(data);
+            if (!success) {
+                assembly {
+                    revert(add(returndata, 32), mload(returndata))
+                }
+            }
+        }
+    }
+
+    fallback() external payable {
+        assembly {
+            let implementation := sload(IMPLEMENTATION_SLOT)
+
+            calldatacopy(0, 0, calldatasize())
+            let result := delegatecall(gas(), implementation, 0, calldatasize(), 0, 0)
+            returndatacopy(0, 0, returndatasize())
+
+            switch result
+            case 0 {
+                revert(0, returndatasize())
+            }
+            default {
+                return(0, returndatasize())
+            }
+        }
+    }
+}
+`,
+  },
+  compute: {
+    name: 'ComputeBenchmark',
+    // Measured at several sizes, since placeholder zeros would do no work
+    profileCases: {
+      cases: {
+        iteratedKeccak: [10, 100, 1000].map((rounds) => ({
+          label: String(rounds),
+          args: ['0x' + '01'.padStart(64, '0'), rounds],
+        })),
+        iteratedSha256: [10, 100, 1000].map((rounds) => ({
+          label: String(rounds),
+          args: ['0x' + '01'.padStart(64, '0'), rounds],
+        })),
+        // 3 ** exponent modulo 2^255 - 19
+        modExp: [10, 100, 1000].map((exponent) => ({
+          label: String(exponent),
+          args: [
+            3,
+            exponent,
+            '57896044618658097711785492504343953926634992332820282019728792003956564819949',
+          ],
+        })),
+        // Descending values, the most moves insertion sort can make
+        sort: [1, 10, 100].map((length) => ({
+          label: String(length),
+          args: [Array.from({ length }, (_, i) => length - i)],
+        })),
+        // The largest input, which needs all 134 steps to converge
+        fixedSqrt: [10, 50, 134].map((iterations) => ({
+          label: String(iterations),
+          args: [
+            '115792089237316195423570985008687907853269984665640564039457',
+            iterations,
+          ],
+        })),
+        // log2(3.0)
+        fixedLog2: [8, 32, 59].map((bits) => ({
+          label: String(bits),
+          args: ['3000000000000000000', bits],
+        })),
+      },
+    },
+    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::vec::Vec;
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    call::Call,
+    prelude::*,
+};
+
+/// The sha256 precompile
+const SHA256: Address = Address::with_last_byte(2);
+/// 1.0 in 18-decimal fixed point
+const UNIT: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+/// Stateless compute benchmarks. Every function takes a size parameter that
+/// scales its work, and the Solidity version runs the same algorithms step
+/// for step, so gas differences come from execution cost alone.
+#[storage]
+#[entrypoint]
+pub struct ComputeBenchmark {}
+
+sol! {
+    #![sol(all_derives)]
+
+    error ZeroModulus();
+    error SqrtOverflow(uint256 x);
+    error LogUndefined(uint256 x);
+    error PrecompileFailed(address precompile);
+}
+
+#[derive(SolidityError, Debug)]
+pub enum ComputeError {
+    ZeroModulus(ZeroModulus),
+    SqrtOverflow(SqrtOverflow),
+    LogUndefined(LogUndefined),
+    PrecompileFailed(PrecompileFailed),
+}
+
+#[public]
+impl ComputeBenchmark {
+    /// Hashes seed with keccak256 rounds times, feeding each hash into the next
+    pub fn iterated_keccak(&self, seed: B256, rounds: u32) -> B256 {
+        let mut hash = seed;
+        for _ in 0..rounds {
+            hash = self.vm().native_keccak256(hash.as_slice());
+        }
+        hash
+    }
+
+    /// Hashes seed with sha256 rounds times through the precompile
+    pub fn iterated_sha256(&self, seed: B256, rounds: u32) -> Result<B256, ComputeError> {
+        let mut hash = seed;
+        for _ in 0..rounds {
+            match self.vm().static_call(&Call::new(), SHA256, hash.as_slice()) {
+                Ok(output) if output.len() == 32 => hash = B256::from_slice(&output),
+                _ => {
+                    return Err(ComputeError::PrecompileFailed(PrecompileFailed {
+                        precompile: SHA256,
+                    }))
+                }
+            }
+        }
+        Ok(hash)
+    }
+
+    /// base ** exponent % modulus, one modular multiplication per unit of
+    /// exponent. Products are taken in 512 bits, like Solidity's mulmod.
+    pub fn mod_exp(
+        &self,
+        base: U256,
+        exponent: u32,
+        modulus: U256,
+    ) -> Result<U256, ComputeError> {
+        if modulus.is_zero() {
+            return Err(ComputeError::ZeroModulus(ZeroModulus {}));
+        }
+
+        let mut result = U256::from(1) % modulus;
+        for _ in 0..exponent {
+            result = result.mul_mod(base, modulus);
+        }
+        Ok(result)
+    }
+
+    /// Insertion sort, so the Rust and Solidity versions do the same number of
+    /// comparisons and moves for a given input.
+    pub fn sort(&self, values: Vec<U256>) -> Vec<U256> {
+        let mut values = values;
+        for i in 1..values.len() {
+            let value = values[i];
+            let mut j = i;
+            while j > 0 && values[j - 1] > value {
+                values[j] = values[j - 1];
+                j -= 1;
+            }
+            values[j] = value;
+        }
+        values
+    }
+
+    /// Square root of an 18-decimal fixed-point number, with at most
+    /// iterations Newton steps. The result is exact once the steps converge,
+    /// which takes up to 134 for the largest inputs: starting from a / 2 + 1,
+    /// each step only halves the estimate until it nears the root. Fewer steps
+    /// return an upper bound.
+    pub fn fixed_sqrt(&self, x: U256, iterations: u32) -> Result<U256, ComputeError> {
+        let a = x
+            .checked_mul(UNIT)
+            .ok_or(ComputeError::SqrtOverflow(SqrtOverflow { x }))?;
+        if a.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        // Starts above the root, so the steps decrease until they converge
+        let mut z = a / U256::from(2) + U256::from(1);
+        for _ in 0..iterations {
+            let next = (z + a / z) / U256::from(2);
+            if next >= z {
+                break;
+            }
+            z = next;
+        }
+        Ok(z)
+    }
+
+    /// Binary logarithm of an 18-decimal fixed-point number of at least 1.0.
+    /// The integer part is exact, and each of the bits rounds of squaring
+    /// adds one fractional bit, up to the 59 that 18 decimals can hold.
+    pub fn fixed_log2(&self, x: U256, bits: u32) -> Result<U256, ComputeError> {
+        if x < UNIT {
+            return Err(ComputeError::LogUndefined(LogUndefined { x }));
+        }
+
+        let n = (x / UNIT).bit_len() - 1;
+        let mut result = U256::from(n) * UNIT;
+
+        // y is x / 2^n, in [1.0, 2.0)
+        let mut y = x >> n;
+        let mut delta = UNIT / U256::from(2);
+        for _ in 0..bits {
+            y = y * y / UNIT;
+            if y >= UNIT * U256::from(2) {
+                result += delta;
+                y >>= 1;
+            }
+            delta >>= 1;
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use stylus_sdk::{alloy_primitives::{b256, keccak256}, testing::*};
+
+    const MAX_ITERATIONS: u32 = 256;
+
+    fn unit(value: u64) -> U256 {
+        U256::from(value) * UNIT
+    }
+
+    #[test]
+    fn test_iterated_keccak() {
+        let vm = TestVM::default();
+        let contract = ComputeBenchmark::from(&vm);
+        let seed = B256::repeat_byte(0x42);
+
+        assert_eq!(seed, contract.iterated_keccak(seed, 0));
+        assert_eq!(keccak256(seed), contract.iterated_keccak(seed, 1));
+        assert_eq!(keccak256(keccak256(keccak256(seed))), contract.iterated_keccak(seed, 3));
+    }
+
+    #[test]
+    fn test_iterated_sha256() {
+        let vm = TestVM::default();
+        let contract = ComputeBenchmark::from(&vm);
+
+        // sha256 of 32 zero bytes, then of that hash
+        let first = b256!("66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925");
+        let second = b256!("2b32db6c2c0a6235fb1397e8225ea85e0f0e6e8c7b126d0016ccbde0e667151e");
+        vm.mock_static_call(SHA256, B256::ZERO.to_vec(), Ok(first.to_vec()));
+        vm.mock_static_call(SHA256, first.to_vec(), Ok(second.to_vec()));
+
+        assert_eq!(B256::ZERO, contract.iterated_sha256(B256::ZERO, 0).unwrap());
+        assert_eq!(first, contract.iterated_sha256(B256::ZERO, 1).unwrap());
+        assert_eq!(second, contract.iterated_sha256(B256::ZERO, 2).unwrap());
+
+        vm.mock_static_call(SHA256, second.to_vec(), Err(Vec::new()));
+        let err = contract.iterated_sha256(B256::ZERO, 3).unwrap_err();
+        assert!(matches!(err, ComputeError::PrecompileFailed(_)));
+    }
+
+    #[test]
+    fn test_mod_exp() {
+        let vm = TestVM::default();
+        let contract = ComputeBenchmark::from(&vm);
+
+        let modulus = U256::from(1_000_003);
+        assert_eq!(U256::from(247_362), contract.mod_exp(U256::from(7), 123, modulus).unwrap());
+        assert_eq!(
+            U256::from(249_612_481),
+            contract
+                .mod_exp(U256::from(12_345), 1000, U256::from(1_000_000_007))
+                .unwrap()
+        );
+        assert_eq!(U256::from(1), contract.mod_exp(U256::from(7), 0, modulus).unwrap());
+        assert_eq!(U256::ZERO, contract.mod_exp(U256::from(7), 0, U256::from(1)).unwrap());
+
+        // products wider than 256 bits: MAX is 1 modulo MAX - 1
+        let modulus = U256::MAX - U256::from(1);
+        assert_eq!(U256::from(1), contract.mod_exp(U256::MAX, 5, modulus).unwrap());
+
+        let err = contract.mod_exp(U256::from(7), 1, U256::ZERO).unwrap_err();
+        assert!(matches!(err, ComputeError::ZeroModulus(_)));
+    }
+
+    #[test]
+    fn test_sort() {
+        let vm = TestVM::default();
+        let contract = ComputeBenchmark::from(&vm);
+
+        let values = [5u64, 3, 9, 1, 3, 0].map(U256::from).to_vec();
+        let sorted = [0u64, 1, 3, 3, 5, 9].map(U256::from).to_vec();
+        assert_eq!(sorted, contract.sort(values));
+        assert_eq!(sorted, contract.sort(sorted.clone()));
+        assert!(contract.sort(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_fixed_sqrt() {
+        let vm = TestVM::default();
+        let contract = ComputeBenchmark::from(&vm);
+
+        assert_eq!(unit(2), contract.fixed_sqrt(unit(4), MAX_ITERATIONS).unwrap());
+        assert_eq!(unit(1), contract.fixed_sqrt(unit(1), MAX_ITERATIONS).unwrap());
+        assert_eq!(
+            U256::from(1_414_213_562_373_095_048u64),
+            contract.fixed_sqrt(unit(2), MAX_ITERATIONS).unwrap()
+        );
+        assert_eq!(U256::ZERO, contract.fixed_sqrt(U256::ZERO, MAX_ITERATIONS).unwrap());
+
+        // too few steps stop above the root
+        assert!(contract.fixed_sqrt(unit(2), 10).unwrap() > unit(2));
+
+        let err = contract.fixed_sqrt(U256::MAX, MAX_ITERATIONS).unwrap_err();
+        assert!(matches!(err, ComputeError::SqrtOverflow(_)));
+    }
+
+    #[test]
+    fn test_fixed_log2() {
+        let vm = TestVM::default();
+        let contract = ComputeBenchmark::from(&vm);
+
+        assert_eq!(U256::ZERO, contract.fixed_log2(unit(1), 64).unwrap());
+        assert_eq!(unit(3), contract.fixed_log2(unit(8), 64).unwrap());
+        // log2(2.5) = 1.32192809488736234...
+        assert_eq!(
+            U256::from(1_321_928_094_887_362_334u64),
+            contract.fixed_log2(unit(5) / U256::from(2), 64).unwrap()
+        );
+        // with 8 fractional bits, log2(10) = 3.3203125
+        assert_eq!(
+            U256::from(3_320_312_500_000_000_000u64),
+            contract.fixed_log2(unit(10), 8).unwrap()
+        );
+
+        let err = contract.fixed_log2(UNIT - U256::from(1), 64).unwrap_err();
+        assert!(matches!(err, ComputeError::LogUndefined(_)));
+    }
+}
+`,
+    solidity: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+// Stateless compute benchmarks. Every function takes a size parameter that
+// scales its work, and the Rust version runs the same algorithms step for
+// step, so gas differences come from execution cost alone. Functions that
+// could be pure are declared view, as the Rust ABI exports them.
+contract ComputeBenchmark {
+    // 1.0 in 18-decimal fixed point
+    uint256 private constant UNIT = 1e18;
+    // The sha256 precompile
+    address private constant SHA256 = address(2);
+
+    error ZeroModulus();
+    error SqrtOverflow(uint256 x);
+    error LogUndefined(uint256 x);
+    error PrecompileFailed(address precompile);
+
+    // Hashes seed with keccak256 rounds times, feeding each hash into the next
+    function iteratedKeccak(
+        bytes32 seed,
+        uint32 rounds
+    ) public view returns (bytes32 hash) {
+        hash = seed;
+        for (uint32 i = 0; i < rounds; ++i) {
+            hash = keccak256(abi.encodePacked(hash));
+        }
+    }
+
+    // Hashes seed with sha256 rounds times through the precompile
+    function iteratedSha256(bytes32 seed, uint32 rounds) public view returns (bytes32 hash) {
+        hash = seed;
+        for (uint32 i = 0; i < rounds; ++i) {
+            (bool success, bytes memory output) = SHA256.staticcall(abi.encodePacked(hash));
+            if (!success || output.length != 32) {
+                revert PrecompileFailed(SHA256);
+            }
+            hash = bytes32(output);
+        }
+    }
+
+    // base ** exponent % modulus, one modular multiplication per unit of
+    // exponent
+    function modExp(
+        uint256 base,
+        uint32 exponent,
+        uint256 modulus
+    ) public view returns (uint256 result) {
+        if (modulus == 0) {
+            revert ZeroModulus();
+        }
+
+        result = 1 % modulus;
+        for (uint32 i = 0; i < exponent; ++i) {
+            result = mulmod(result, base, modulus);
+        }
+    }
+
+    // Insertion sort, so the Rust and Solidity versions do the same number of
+    // comparisons and moves for a given input.
+    function sort(uint256[] memory values) public view returns (uint256[] memory) {
+        for (uint256 i = 1; i < values.length; ++i) {
+            uint256 value = values[i];
+            uint256 j = i;
+            while (j > 0 && values[j - 1] > value) {
+                values[j] = values[j - 1];
+                --j;
+            }
+            values[j] = value;
+        }
+        return values;
+    }
+
+    // Square root of an 18-decimal fixed-point number, with at most
+    // iterations Newton steps. The result is exact once the steps converge,
+    // which takes up to 134 for the largest inputs: starting from a / 2 + 1,
+    // each step only halves the estimate until it nears the root. Fewer steps
+    // return an upper bound.
+    function fixedSqrt(uint256 x, uint32 iterations) public view returns (uint256) {
+        if (x > type(uint256).max / UNIT) {
+            revert SqrtOverflow(x);
+        }
+        uint256 a = x * UNIT;
+        if (a == 0) {
+            return 0;
+        }
+
+        // Starts above the root, so the steps decrease until they converge
+        uint256 z = a / 2 + 1;
+        for (uint32 i = 0; i < iterations; ++i) {
+            uint256 next = (z + a / z) / 2;
+            if (next >= z) {
+                break;
+            }
+            z = next;
+        }
+        return z;
+    }
+
+    // Binary logarithm of an 18-decimal fixed-point number of at least 1.0.
+    // The integer part is exact, and each of the bits rounds of squaring
+    // adds one fractional bit, up to the 59 that 18 decimals can hold.
+    function fixedLog2(uint256 x, uint32 bits) public view returns (uint256 result) {
+        if (x < UNIT) {
+            revert LogUndefined(x);
+        }
+
+        uint256 n = 0;
+        for (uint256 q = x / UNIT; q > 1; q >>= 1) {
+            ++n;
+        }
+        result = n * UNIT;
+
+        // y is x / 2^n, in [1.0, 2.0)
+        uint256 y = x >> n;
+        uint256 delta = UNIT / 2;
+        for (uint32 i = 0; i < bits; ++i) {
+            y = (y * y) / UNIT;
+            if (y >= 2 * UNIT) {
+                result += delta;
+                y >>= 1;
+            }
+            delta >>= 1;
+        }
+    }
+}
+`,
+  },
+  eip712: {
+    name: 'OrderSettlement',
+    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloy_sol_types::{sol, Eip712Domain, SolStruct, SolValue};
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{b256, Address, B256, U256},
+    call::Call,
+    prelude::*,
+    storage::{StorageBool, StorageMap},
+    stylus_core::log,
+};
+
+/// Largest s value of a non-malleable signature (secp256k1n / 2)
+const MAX_S: B256 = b256!("7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0");
+/// The ecrecover precompile
+const ECRECOVER: Address = Address::with_last_byte(1);
+
+sol_interface! {
+    interface IERC20 {
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+}
+
+sol! {
+    #![sol(all_derives)]
+
+    /// A maker's offer to sell sellAmount of sellToken for buyAmount of
+    /// buyToken. A zero taker lets anyone fill it.
+    #[derive(AbiType)]
+    struct Order {
+        address maker;
+        address taker;
+        address sellToken;
+        uint256 sellAmount;
+        address buyToken;
+        uint256 buyAmount;
+        uint256 nonce;
+        uint256 deadline;
+    }
+
+    event OrderFilled(bytes32 indexed orderHash, address indexed maker, address indexed taker);
+    event OrderCancelled(address indexed maker, uint256 nonce);
+
+    error OrderExpired(uint256 deadline);
+    error InvalidTaker(address taker, address sender);
+    error NonceAlreadyUsed(address maker, uint256 nonce);
+    error InvalidSignatureLength(uint256 length);
+    error InvalidSigner(address signer, address maker);
+    error TransferFailed(address token);
+}
+
+/// Settles orders signed off-chain as EIP-712 typed data. The order struct
+/// and domain are hashed with alloy_sol_types, and the signer is recovered
+/// through the ecrecover precompile. Both tokens move directly between maker
+/// and taker, so each has to approve this contract first.
+#[storage]
+#[entrypoint]
+pub struct OrderSettlement {
+    used_nonces: StorageMap<Address, StorageMap<U256, StorageBool>>,
+}
+
+#[derive(SolidityError, Debug)]
+pub enum OrderError {
+    OrderExpired(OrderExpired),
+    InvalidTaker(InvalidTaker),
+    NonceAlreadyUsed(NonceAlreadyUsed),
+    InvalidSignatureLength(InvalidSignatureLength),
+    InvalidSigner(InvalidSigner),
+    TransferFailed(TransferFailed),
+}
+
+#[public]
+impl OrderSettlement {
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    pub fn domain_separator(&self) -> B256 {
+        self.domain().separator()
+    }
+
+    /// The EIP-712 digest the maker signs for order
+    pub fn hash_order(&self, order: Order) -> B256 {
+        order.eip712_signing_hash(&self.domain())
+    }
+
+    pub fn is_nonce_used(&self, maker: Address, nonce: U256) -> bool {
+        self.used_nonces.getter(maker).get(nonce)
+    }
+
+    /// Invalidates every order the sender signed with nonce
+    pub fn cancel(&mut self, nonce: U256) -> Result<(), OrderError> {
+        let maker = self.vm().msg_sender();
+        self.use_nonce(maker, nonce)?;
+        log(self.vm(), OrderCancelled { maker, nonce });
+        Ok(())
+    }
+
+    /// Fills order for the sender, who pays buyAmount of buyToken to the
+    /// maker and receives sellAmount of sellToken. signature is the maker's
+    /// 65-byte r, s, v signature of hash_order(order). Returns that hash.
+    pub fn verify_and_execute(
+        &mut self,
+        order: Order,
+        signature: Bytes,
+    ) -> Result<B256, OrderError> {
+        let taker = self.vm().msg_sender();
+        if U256::from(self.vm().block_timestamp()) > order.deadline {
+            return Err(OrderError::OrderExpired(OrderExpired {
+                deadline: order.deadline,
+            }));
+        }
+        if order.taker != Address::ZERO && order.taker != taker {
+            return Err(OrderError::InvalidTaker(InvalidTaker {
+                taker: order.taker,
+                sender: taker,
+            }));
+        }
+
+        let order_hash = self.hash_order(order.clone());
+        let signer = self.recover_signer(order_hash, &signature)?;
+        if signer == Address::ZERO || signer != order.maker {
+            return Err(OrderError::InvalidSigner(InvalidSigner {
+                signer,
+                maker: order.maker,
+            }));
+        }
+
+        // The nonce is spent before the transfers, so a token calling back
+        // into this contract cannot fill the same order twice
+        self.use_nonce(order.maker, order.nonce)?;
+        self.pull_tokens(order.sellToken, order.maker, taker, order.sellAmount)?;
+        self.pull_tokens(order.buyToken, taker, order.maker, order.buyAmount)?;
+
+        log(
+            self.vm(),
+            OrderFilled {
+                orderHash: order_hash,
+                maker: order.maker,
+                taker,
+            },
+        );
+        Ok(order_hash)
+    }
+}
+
+impl OrderSettlement {
+    fn domain(&self) -> Eip712Domain {
+        Eip712Domain::new(
+            Some("OrderSettlement".into()),
+            Some("1".into()),
+            Some(U256::from(self.vm().chain_id())),
+            Some(self.vm().contract_address()),
+            None,
+        )
+    }
+
+    fn use_nonce(&mut self, maker: Address, nonce: U256) -> Result<(), OrderError> {
+        let mut nonces = self.used_nonces.setter(maker);
+        let mut used = nonces.setter(nonce);
+        if used.get() {
+            return Err(OrderError::NonceAlreadyUsed(NonceAlreadyUsed { maker, nonce }));
+        }
+        used.set(true);
+        Ok(())
+    }
+
+    /// Recovers the signer of digest through the ecrecover precompile. Invalid
+    /// and malleable signatures recover to the zero address.
+    fn recover_signer(&self, digest: B256, signature: &[u8]) -> Result<Address, OrderError> {
+        if signature.len() != 65 {
+            return Err(OrderError::InvalidSignatureLength(InvalidSignatureLength {
+                length: U256::from(signature.len()),
+            }));
+        }
+
+        let r = B256::from_slice(&signature[..32]);
+        let s = B256::from_slice(&signature[32..64]);
+        let v = signature[64];
+        if s > MAX_S {
+            return Ok(Address::ZERO);
+        }
+
+        let input = (digest, U256::from(v), r, s).abi_encode();
+        match self.vm().static_call(&Call::new(), ECRECOVER, &input) {
+            Ok(output) if output.len() == 32 => Ok(Address::from_word(B256::from_slice(&output))),
+            _ => Ok(Address::ZERO),
+        }
+    }
+
+    fn pull_tokens(
+        &mut self,
+        token: Address,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), OrderError> {
+        let erc20 = IERC20::new(token);
+        let call = Call::new_mutating(self);
+        match erc20.transfer_from(self.vm(), call, from, to, amount) {
+            Ok(true) => Ok(()),
+            _ => Err(OrderError::TransferFailed(TransferFailed { token })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use alloy_sol_types::{SolCall, SolEvent};
+    use stylus_sdk::{alloy_primitives::keccak256, testing::*};
+
+    const MAKER: Address = Address::repeat_byte(0x11);
+    const TAKER: Address = Address::repeat_byte(0x22);
+    const SELL_TOKEN: Address = Address::repeat_byte(0x0a);
+    const BUY_TOKEN: Address = Address::repeat_byte(0x0b);
+
+    sol! {
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    fn order() -> Order {
+        Order {
+            maker: MAKER,
+            taker: Address::ZERO,
+            sellToken: SELL_TOKEN,
+            sellAmount: U256::from(100),
+            buyToken: BUY_TOKEN,
+            buyAmount: U256::from(250),
+            nonce: U256::from(7),
+            deadline: U256::MAX,
+        }
+    }
+
+    fn encode_bool(value: bool) -> Vec<u8> {
+        U256::from(value as u8).to_be_bytes::<32>().to_vec()
+    }
+
+    /// Mocks the ecrecover precompile so that order recovers signer, and
+    /// returns the signature to submit.
+    fn mock_signature(
+        vm: &TestVM,
+        contract: &OrderSettlement,
+        order: &Order,
+        signer: Address,
+    ) -> Bytes {
+        let (v, r, s) = (27u8, B256::repeat_byte(0x0a), B256::repeat_byte(0x0b));
+        let digest = contract.hash_order(order.clone());
+        vm.mock_static_call(
+            ECRECOVER,
+            (digest, U256::from(v), r, s).abi_encode(),
+            Ok(signer.into_word().to_vec()),
+        );
+
+        let mut signature = Vec::with_capacity(65);
+        signature.extend_from_slice(r.as_slice());
+        signature.extend_from_slice(s.as_slice());
+        signature.push(v);
+        Bytes(signature)
+    }
+
+    /// Makes token.transferFrom(from, to, value) succeed
+    fn mock_transfer(vm: &TestVM, token: Address, from: Address, to: Address, value: U256) {
+        let data = transferFromCall { from, to, value }.abi_encode();
+        vm.mock_call(token, data, U256::ZERO, Ok(encode_bool(true)));
+    }
+
+    fn mock_transfers(vm: &TestVM, order: &Order) {
+        mock_transfer(vm, SELL_TOKEN, MAKER, TAKER, order.sellAmount);
+        mock_transfer(vm, BUY_TOKEN, TAKER, MAKER, order.buyAmount);
+    }
+
+    // The alloy_sol_types hashes must match the encoding the Solidity
+    // version does by hand
+    #[test]
+    fn test_domain_separator() {
+        let vm = TestVM::default();
+        let contract = OrderSettlement::from(&vm);
+
+        let encoded = (
+            keccak256(
+                "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+            ),
+            keccak256("OrderSettlement"),
+            keccak256("1"),
+            U256::from(contract.vm().chain_id()),
+            contract.vm().contract_address(),
+        )
+            .abi_encode();
+        assert_eq!(keccak256(encoded), contract.domain_separator());
     }
 
-    pub fn remove_liquidity(&mut self, amount: U256) -> bool {
-        let current = self.total_liquidity.get();
-        if current < amount {
-            return false;
+    #[test]
+    fn test_hash_order() {
+        let vm = TestVM::default();
+        let contract = OrderSettlement::from(&vm);
+        let order = order();
+
+        let typehash = keccak256(concat!(
+            "Order(address maker,address taker,address sellToken,uint256 sellAmount,",
+            "address buyToken,uint256 buyAmount,uint256 nonce,uint256 deadline)",
+        ));
+        let struct_hash = keccak256(
+            (
+                typehash,
+                order.maker,
+                order.taker,
+                order.sellToken,
+                order.sellAmount,
+                order.buyToken,
+                order.buyAmount,
+                order.nonce,
+                order.deadline,
+            )
+                .abi_encode(),
+        );
+
+        let mut message = Vec::with_capacity(66);
+        message.extend_from_slice(&[0x19, 0x01]);
+        message.extend_from_slice(contract.domain_separator().as_slice());
+        message.extend_from_slice(struct_hash.as_slice());
+        assert_eq!(keccak256(message), contract.hash_order(order));
+    }
+
+    #[test]
+    fn test_verify_and_execute() {
+        let vm = TestVM::default();
+        let mut contract = OrderSettlement::from(&vm);
+        let order = order();
+        let signature = mock_signature(&vm, &contract, &order, MAKER);
+        mock_transfers(&vm, &order);
+
+        vm.set_sender(TAKER);
+        let order_hash = contract.verify_and_execute(order.clone(), signature).unwrap();
+        assert_eq!(contract.hash_order(order), order_hash);
+        assert!(contract.is_nonce_used(MAKER, U256::from(7)));
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(OrderFilled::SIGNATURE_HASH, topics[0]);
+        assert_eq!(order_hash, topics[1]);
+        assert_eq!(MAKER.into_word(), topics[2]);
+        assert_eq!(TAKER.into_word(), topics[3]);
+    }
+
+    #[test]
+    fn test_verify_and_execute_rejects_replay() {
+        let vm = TestVM::default();
+        let mut contract = OrderSettlement::from(&vm);
+        let order = order();
+        let signature = mock_signature(&vm, &contract, &order, MAKER);
+        mock_transfers(&vm, &order);
+
+        vm.set_sender(TAKER);
+        contract
+            .verify_and_execute(order.clone(), signature.clone())
+            .unwrap();
+        let err = contract.verify_and_execute(order, signature).unwrap_err();
+        assert!(matches!(err, OrderError::NonceAlreadyUsed(_)));
+    }
+
+    #[test]
+    fn test_verify_and_execute_rejects_wrong_signer() {
+        let vm = TestVM::default();
+        let mut contract = OrderSettlement::from(&vm);
+        let order = order();
+        let signature = mock_signature(&vm, &contract, &order, TAKER);
+
+        vm.set_sender(TAKER);
+        let err = contract.verify_and_execute(order, signature).unwrap_err();
+        assert!(matches!(
+            err,
+            OrderError::InvalidSigner(InvalidSigner { signer, .. }) if signer == TAKER
+        ));
+        assert!(!contract.is_nonce_used(MAKER, U256::from(7)));
+    }
+
+    #[test]
+    fn test_verify_and_execute_rejects_invalid_signatures() {
+        let vm = TestVM::default();
+        let mut contract = OrderSettlement::from(&vm);
+        vm.set_sender(TAKER);
+
+        let err = contract
+            .verify_and_execute(order(), Bytes(vec![0; 64]))
+            .unwrap_err();
+        assert!(matches!(err, OrderError::InvalidSignatureLength(_)));
+
+        // s in the upper half of the curve order is malleable
+        let mut signature = vec![0; 65];
+        signature[32..64].fill(0xff);
+        signature[64] = 27;
+        let err = contract
+            .verify_and_execute(order(), Bytes(signature))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            OrderError::InvalidSigner(InvalidSigner { signer, .. }) if signer == Address::ZERO
+        ));
+    }
+
+    #[test]
+    fn test_verify_and_execute_checks_deadline_and_taker() {
+        let vm = TestVM::default();
+        let mut contract = OrderSettlement::from(&vm);
+        vm.set_block_timestamp(1_000);
+        vm.set_sender(TAKER);
+
+        let mut expired = order();
+        expired.deadline = U256::from(999);
+        let signature = mock_signature(&vm, &contract, &expired, MAKER);
+        let err = contract.verify_and_execute(expired, signature).unwrap_err();
+        assert!(matches!(err, OrderError::OrderExpired(_)));
+
+        let mut private = order();
+        private.taker = MAKER;
+        let signature = mock_signature(&vm, &contract, &private, MAKER);
+        let err = contract.verify_and_execute(private, signature).unwrap_err();
+        assert!(matches!(
+            err,
+            OrderError::InvalidTaker(InvalidTaker { sender, .. }) if sender == TAKER
+        ));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let vm = TestVM::default();
+        let mut contract = OrderSettlement::from(&vm);
+        let order = order();
+        let signature = mock_signature(&vm, &contract, &order, MAKER);
+
+        vm.set_sender(MAKER);
+        contract.cancel(order.nonce).unwrap();
+        assert!(contract.is_nonce_used(MAKER, order.nonce));
+
+        vm.set_sender(TAKER);
+        let err = contract.verify_and_execute(order, signature).unwrap_err();
+        assert!(matches!(err, OrderError::NonceAlreadyUsed(_)));
+    }
+}
+`,
+    solidity: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+interface IERC20 {
+    function transferFrom(address from, address to, uint256 value) external returns (bool);
+}
+
+// Settles orders signed off-chain as EIP-712 typed data. The signer is
+// recovered through the ecrecover precompile. Both tokens move directly
+// between maker and taker, so each has to approve this contract first.
+contract OrderSettlement {
+    // A maker's offer to sell sellAmount of sellToken for buyAmount of
+    // buyToken. A zero taker lets anyone fill it.
+    struct Order {
+        address maker;
+        address taker;
+        address sellToken;
+        uint256 sellAmount;
+        address buyToken;
+        uint256 buyAmount;
+        uint256 nonce;
+        uint256 deadline;
+    }
+
+    bytes32 private constant DOMAIN_TYPEHASH =
+        keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)");
+    bytes32 private constant ORDER_TYPEHASH =
+        keccak256(
+            "Order(address maker,address taker,address sellToken,uint256 sellAmount,"
+            "address buyToken,uint256 buyAmount,uint256 nonce,uint256 deadline)"
+        );
+
+    mapping(address => mapping(uint256 => bool)) private usedNonces;
+
+    event OrderFilled(bytes32 indexed orderHash, address indexed maker, address indexed taker);
+    event OrderCancelled(address indexed maker, uint256 nonce);
+
+    error OrderExpired(uint256 deadline);
+    error InvalidTaker(address taker, address sender);
+    error NonceAlreadyUsed(address maker, uint256 nonce);
+    error InvalidSignatureLength(uint256 length);
+    error InvalidSigner(address signer, address maker);
+    error TransferFailed(address token);
+
+    function DOMAIN_SEPARATOR() public view returns (bytes32) {
+        return keccak256(
+            abi.encode(
+                DOMAIN_TYPEHASH,
+                keccak256("OrderSettlement"),
+                keccak256("1"),
+                block.chainid,
+                address(this)
+            )
+        );
+    }
+
+    // The EIP-712 digest the maker signs for order
+    function hashOrder(Order calldata order) public view returns (bytes32) {
+        bytes32 structHash = keccak256(
+            abi.encode(
+                ORDER_TYPEHASH,
+                order.maker,
+                order.taker,
+                order.sellToken,
+                order.sellAmount,
+                order.buyToken,
+                order.buyAmount,
+                order.nonce,
+                order.deadline
+            )
+        );
+        return keccak256(abi.encodePacked(bytes2(0x1901), DOMAIN_SEPARATOR(), structHash));
+    }
+
+    function isNonceUsed(address maker, uint256 nonce) public view returns (bool) {
+        return usedNonces[maker][nonce];
+    }
+
+    // Invalidates every order the sender signed with nonce
+    function cancel(uint256 nonce) public {
+        _useNonce(msg.sender, nonce);
+        emit OrderCancelled(msg.sender, nonce);
+    }
+
+    // Fills order for the sender, who pays buyAmount of buyToken to the maker
+    // and receives sellAmount of sellToken. signature is the maker's 65-byte
+    // r, s, v signature of hashOrder(order). Returns that hash.
+    function verifyAndExecute(
+        Order calldata order,
+        bytes calldata signature
+    ) public returns (bytes32 orderHash) {
+        if (block.timestamp > order.deadline) {
+            revert OrderExpired(order.deadline);
         }
-        self.total_liquidity.set(current - amount);
-        true
+        if (order.taker != address(0) && order.taker != msg.sender) {
+            revert InvalidTaker(order.taker, msg.sender);
+        }
+
+        orderHash = hashOrder(order);
+        address signer = _recover(orderHash, signature);
+        if (signer == address(0) || signer != order.maker) {
+            revert InvalidSigner(signer, order.maker);
+        }
+
+        // The nonce is spent before the transfers, so a token calling back
+        // into this contract cannot fill the same order twice
+        _useNonce(order.maker, order.nonce);
+        _pullTokens(order.sellToken, order.maker, msg.sender, order.sellAmount);
+        _pullTokens(order.buyToken, msg.sender, order.maker, order.buyAmount);
+
+        emit OrderFilled(orderHash, order.maker, msg.sender);
+    }
+
+    function _useNonce(address maker, uint256 nonce) internal {
+        if (usedNonces[maker][nonce]) {
+            revert NonceAlreadyUsed(maker, nonce);
+        }
+        usedNonces[maker][nonce] = true;
+    }
+
+    // Recovers the signer of digest through the ecrecover precompile
+    function _recover(bytes32 digest, bytes calldata signature) internal pure returns (address) {
+        if (signature.length != 65) {
+            revert InvalidSignatureLength(signature.length);
+        }
+
+        bytes32 r = bytes32(signature[0:32]);
+        bytes32 s = bytes32(signature[32:64]);
+        uint8 v = uint8(signature[64]);
+        // Malleable signatures (s in the upper half of the curve order) are rejected
+        if (uint256(s) > 0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF5D576E7357A4501DDFE92F46681B20A0) {
+            return address(0);
+        }
+        return ecrecover(digest, v, r, s);
+    }
+
+    function _pullTokens(address token, address from, address to, uint256 amount) internal {
+        if (!IERC20(token).transferFrom(from, to, amount)) {
+            revert TransferFailed(token);
+        }
+    }
+}
+`,
+  },
+  'merkle-airdrop': {
+    name: 'MerkleDistributor',
+    rust: `#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::vec::Vec;
+use alloy_sol_types::{sol, SolValue};
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256},
+    call::Call,
+    prelude::*,
+    storage::{StorageAddress, StorageB256, StorageMap, StorageU256},
+    stylus_core::log,
+};
+
+sol_interface! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+    }
+}
+
+/// Airdrop of an ERC-20 token to a list of (index, account, amount) entries
+/// committed to by a Merkle root. Anyone can submit an account's claim, and
+/// the tokens always go to the account. Each index can be claimed once.
+#[storage]
+#[entrypoint]
+pub struct MerkleDistributor {
+    token: StorageAddress,
+    merkle_root: StorageB256,
+    /// One bit per index, 256 indexes per word
+    claimed_bit_map: StorageMap<U256, StorageU256>,
+}
+
+sol! {
+    #![sol(all_derives)]
+
+    event Claimed(uint256 index, address account, uint256 amount);
+
+    error AlreadyClaimed(uint256 index);
+    error InvalidProof();
+    error TransferFailed(address token);
+}
+
+#[derive(SolidityError, Debug)]
+pub enum AirdropError {
+    AlreadyClaimed(AlreadyClaimed),
+    InvalidProof(InvalidProof),
+    TransferFailed(TransferFailed),
+}
+
+#[public]
+impl MerkleDistributor {
+    /// The contract has to hold enough of token to pay out every entry
+    /// under merkle_root.
+    #[constructor]
+    pub fn constructor(&mut self, token: Address, merkle_root: B256) {
+        self.token.set(token);
+        self.merkle_root.set(merkle_root);
+    }
+
+    pub fn token(&self) -> Address {
+        self.token.get()
+    }
+
+    pub fn merkle_root(&self) -> B256 {
+        self.merkle_root.get()
+    }
+
+    pub fn is_claimed(&self, index: U256) -> bool {
+        let (word, mask) = Self::bit(index);
+        (self.claimed_bit_map.get(word) & mask) != U256::ZERO
+    }
+
+    /// Pays amount to account if merkle_proof shows that (index, account,
+    /// amount) is a leaf of the tree. Leaves are
+    /// keccak256(abi.encodePacked(index, account, amount)), and each pair of
+    /// nodes is hashed in sorted order.
+    pub fn claim(
+        &mut self,
+        index: U256,
+        account: Address,
+        amount: U256,
+        merkle_proof: Vec<B256>,
+    ) -> Result<(), AirdropError> {
+        if self.is_claimed(index) {
+            return Err(AirdropError::AlreadyClaimed(AlreadyClaimed { index }));
+        }
+
+        let leaf = self
+            .vm()
+            .native_keccak256(&(index, account, amount).abi_encode_packed());
+        if !self.verify(&merkle_proof, leaf) {
+            return Err(AirdropError::InvalidProof(InvalidProof {}));
+        }
+
+        // The index is marked before the transfer, so a token calling back
+        // into this contract cannot claim it twice
+        let (word, mask) = Self::bit(index);
+        let claimed = self.claimed_bit_map.get(word);
+        self.claimed_bit_map.insert(word, claimed | mask);
+
+        let token = self.token.get();
+        let erc20 = IERC20::new(token);
+        let call = Call::new_mutating(self);
+        if !matches!(erc20.transfer(self.vm(), call, account, amount), Ok(true)) {
+            return Err(AirdropError::TransferFailed(TransferFailed { token }));
+        }
+
+        log(
+            self.vm(),
+            Claimed {
+                index,
+                account,
+                amount,
+            },
+        );
+        Ok(())
+    }
+}
+
+impl MerkleDistributor {
+    /// The bit map word holding index, and the mask of its bit
+    fn bit(index: U256) -> (U256, U256) {
+        let word = index >> 8;
+        let bit = index.as_limbs()[0] as usize % 256;
+        (word, U256::from(1) << bit)
+    }
+
+    /// Whether proof leads from leaf to the Merkle root, one keccak256 per
+    /// level of the tree
+    fn verify(&self, proof: &[B256], leaf: B256) -> bool {
+        let mut node = leaf;
+        for sibling in proof {
+            let mut pair = [0u8; 64];
+            let (first, second) = if node <= *sibling {
+                (node, *sibling)
+            } else {
+                (*sibling, node)
+            };
+            pair[..32].copy_from_slice(first.as_slice());
+            pair[32..].copy_from_slice(second.as_slice());
+            node = self.vm().native_keccak256(&pair);
+        }
+        node == self.merkle_root.get()
+    }
+}
+
+/// Off-chain side of the airdrop, for whoever publishes the root and hands
+/// out proofs. The contract never calls it, and the tests build their trees
+/// with it.
+pub mod merkle {
+    use alloc::vec::Vec;
+    use alloy_sol_types::SolValue;
+    use stylus_sdk::alloy_primitives::{keccak256, Address, B256, U256};
+
+    /// An (index, account, amount) entry of the airdrop
+    pub type Entry = (U256, Address, U256);
+
+    /// The tree over a list of entries, hashed the same way claim does. A
+    /// node without a sibling moves up a level unchanged.
+    pub struct MerkleTree {
+        entries: Vec<Entry>,
+        layers: Vec<Vec<B256>>,
+    }
+
+    impl MerkleTree {
+        pub fn new(entries: Vec<Entry>) -> Self {
+            assert!(!entries.is_empty(), "a Merkle tree needs at least one entry");
+
+            let mut layers = vec![entries.iter().map(Self::leaf).collect::<Vec<_>>()];
+            while layers.last().unwrap().len() > 1 {
+                let next = layers
+                    .last()
+                    .unwrap()
+                    .chunks(2)
+                    .map(|nodes| match nodes {
+                        [a, b] => Self::hash_pair(*a, *b),
+                        _ => nodes[0],
+                    })
+                    .collect();
+                layers.push(next);
+            }
+            Self { entries, layers }
+        }
+
+        /// keccak256(abi.encodePacked(index, account, amount))
+        pub fn leaf(entry: &Entry) -> B256 {
+            keccak256(entry.abi_encode_packed())
+        }
+
+        /// Hashes two nodes in sorted order, so proofs need no left/right flags
+        pub fn hash_pair(a: B256, b: B256) -> B256 {
+            let (first, second) = if a <= b { (a, b) } else { (b, a) };
+            keccak256([first.as_slice(), second.as_slice()].concat())
+        }
+
+        pub fn root(&self) -> B256 {
+            self.layers.last().unwrap()[0]
+        }
+
+        /// The nth entry and its proof, ready to pass to claim
+        pub fn claim(&self, n: usize) -> (U256, Address, U256, Vec<B256>) {
+            let (index, account, amount) = self.entries[n];
+            let mut proof = Vec::new();
+            let mut position = n;
+            for layer in &self.layers[..self.layers.len() - 1] {
+                if let Some(sibling) = layer.get(position ^ 1) {
+                    proof.push(*sibling);
+                }
+                position /= 2;
+            }
+            (index, account, amount, proof)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_sol_types::{SolCall, SolEvent};
+    use stylus_sdk::testing::*;
+
+    const TOKEN: Address = Address::repeat_byte(0x0a);
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+    const CAROL: Address = Address::repeat_byte(0xca);
+
+    sol! {
+        function transfer(address to, uint256 value) external returns (bool);
+    }
+
+    fn entry(index: u64, account: Address, amount: u64) -> merkle::Entry {
+        (U256::from(index), account, U256::from(amount))
+    }
+
+    /// Indexes 255 and 256 fall in different bit map words. The Solidity
+    /// tests build the same tree.
+    fn tree() -> merkle::MerkleTree {
+        merkle::MerkleTree::new(vec![
+            entry(0, ALICE, 100),
+            entry(1, BOB, 200),
+            entry(255, CAROL, 300),
+            entry(256, ALICE, 400),
+            entry(1000, BOB, 500),
+        ])
+    }
+
+    fn deploy(vm: &TestVM, tree: &merkle::MerkleTree) -> MerkleDistributor {
+        let mut contract = MerkleDistributor::from(vm);
+        contract.constructor(TOKEN, tree.root());
+        contract
+    }
+
+    fn encode_bool(value: bool) -> Vec<u8> {
+        U256::from(value as u8).to_be_bytes::<32>().to_vec()
+    }
+
+    /// Makes TOKEN.transfer(to, value) return result
+    fn mock_transfer(vm: &TestVM, to: Address, value: U256, result: bool) {
+        let data = transferCall { to, value }.abi_encode();
+        vm.mock_call(TOKEN, data, U256::ZERO, Ok(encode_bool(result)));
+    }
+
+    #[test]
+    fn test_tree_root() {
+        // Pinned here and in the Solidity tests, so both sides build the
+        // same tree
+        let root = "0x17d67de2b18acfe3fcea7a445ac93596f42ffffa9b7ac4735002c009f372e229";
+        assert_eq!(root.parse::<B256>().unwrap(), tree().root());
+    }
+
+    #[test]
+    fn test_claim() {
+        let vm = TestVM::default();
+        let tree = tree();
+        let mut contract = deploy(&vm, &tree);
+        assert_eq!(TOKEN, contract.token());
+        assert_eq!(tree.root(), contract.merkle_root());
+
+        // anyone may submit a claim, and the tokens go to the account
+        vm.set_sender(CAROL);
+        for n in 0..5 {
+            let (index, account, amount, proof) = tree.claim(n);
+            mock_transfer(&vm, account, amount, true);
+            assert!(!contract.is_claimed(index));
+            contract.claim(index, account, amount, proof).unwrap();
+            assert!(contract.is_claimed(index));
+        }
+
+        let logs = vm.get_emitted_logs();
+        assert_eq!(5, logs.len());
+        let (topics, data) = logs.last().unwrap();
+        let claimed = Claimed::decode_raw_log(topics.iter().copied(), data, true).unwrap();
+        assert_eq!(U256::from(1000), claimed.index);
+        assert_eq!(BOB, claimed.account);
+        assert_eq!(U256::from(500), claimed.amount);
+    }
+
+    #[test]
+    fn test_is_claimed_tracks_each_index() {
+        let vm = TestVM::default();
+        let tree = tree();
+        let mut contract = deploy(&vm, &tree);
+
+        let (index, account, amount, proof) = tree.claim(2);
+        mock_transfer(&vm, account, amount, true);
+        contract.claim(index, account, amount, proof).unwrap();
+
+        assert!(contract.is_claimed(U256::from(255)));
+        assert!(!contract.is_claimed(U256::from(254)));
+        assert!(!contract.is_claimed(U256::from(256)));
+        assert!(!contract.is_claimed(U256::from(511)));
+    }
+
+    #[test]
+    fn test_claim_rejects_second_claim() {
+        let vm = TestVM::default();
+        let tree = tree();
+        let mut contract = deploy(&vm, &tree);
+
+        let (index, account, amount, proof) = tree.claim(1);
+        mock_transfer(&vm, account, amount, true);
+        contract.claim(index, account, amount, proof.clone()).unwrap();
+
+        let err = contract.claim(index, account, amount, proof).unwrap_err();
+        assert!(matches!(
+            err,
+            AirdropError::AlreadyClaimed(AlreadyClaimed { index: claimed }) if claimed == index
+        ));
+    }
+
+    #[test]
+    fn test_claim_rejects_invalid_proof() {
+        let vm = TestVM::default();
+        let tree = tree();
+        let mut contract = deploy(&vm, &tree);
+        let (index, account, amount, proof) = tree.claim(0);
+
+        let err = contract
+            .claim(index, account, amount + U256::from(1), proof.clone())
+            .unwrap_err();
+        assert!(matches!(err, AirdropError::InvalidProof(_)));
+
+        let err = contract.claim(index, BOB, amount, proof.clone()).unwrap_err();
+        assert!(matches!(err, AirdropError::InvalidProof(_)));
+
+        let err = contract
+            .claim(U256::from(1), account, amount, proof)
+            .unwrap_err();
+        assert!(matches!(err, AirdropError::InvalidProof(_)));
+
+        let err = contract.claim(index, account, amount, Vec::new()).unwrap_err();
+        assert!(matches!(err, AirdropError::InvalidProof(_)));
+        assert!(!contract.is_claimed(index));
+    }
+
+    #[test]
+    fn test_claim_with_single_entry_tree() {
+        let vm = TestVM::default();
+        let tree = merkle::MerkleTree::new(vec![entry(7, ALICE, 100)]);
+        let mut contract = deploy(&vm, &tree);
+
+        // the root is the leaf itself, so the proof is empty
+        let (index, account, amount, proof) = tree.claim(0);
+        assert!(proof.is_empty());
+        mock_transfer(&vm, account, amount, true);
+        contract.claim(index, account, amount, proof).unwrap();
+        assert!(contract.is_claimed(index));
+    }
+
+    #[test]
+    fn test_claim_rejects_failed_transfer() {
+        let vm = TestVM::default();
+        let tree = tree();
+        let mut contract = deploy(&vm, &tree);
+
+        let (index, account, amount, proof) = tree.claim(3);
+        mock_transfer(&vm, account, amount, false);
+        let err = contract.claim(index, account, amount, proof).unwrap_err();
+        assert!(matches!(
+            err,
+            AirdropError::TransferFailed(TransferFailed { token }) if token == TOKEN
+        ));
     }
 }
 `,
     solidity: `// SPDX-License-Identifier: MIT
 pragma solidity ^0.8.20;
 
-contract SimpleDEX {
-    mapping(address => uint256) private liquidity;
-    uint256 private reserves;
+interface IERC20 {
+    function transfer(address to, uint256 value) external returns (bool);
+}
+
+// Airdrop of an ERC-20 token to a list of (index, account, amount) entries
+// committed to by a Merkle root. Anyone can submit an account's claim, and
+// the tokens always go to the account. Each index can be claimed once.
+contract MerkleDistributor {
+    // Kept in storage rather than immutable, like the Rust version's fields,
+    // so both versions pay for a storage read on every claim
+    address public token;
+    bytes32 public merkleRoot;
+
+    // One bit per index, 256 indexes per word
+    mapping(uint256 => uint256) private claimedBitMap;
+
+    event Claimed(uint256 index, address account, uint256 amount);
+
+    error AlreadyClaimed(uint256 index);
+    error InvalidProof();
+    error TransferFailed(address token);
+
+    // The contract has to hold enough of token to pay out every entry under
+    // merkleRoot.
+    constructor(address token_, bytes32 merkleRoot_) {
+        token = token_;
+        merkleRoot = merkleRoot_;
+    }
+
+    function isClaimed(uint256 index) public view returns (bool) {
+        return (claimedBitMap[index >> 8] & (1 << (index & 0xff))) != 0;
+    }
+
+    // Pays amount to account if merkleProof shows that (index, account,
+    // amount) is a leaf of the tree. Leaves are
+    // keccak256(abi.encodePacked(index, account, amount)), and each pair of
+    // nodes is hashed in sorted order.
+    function claim(
+        uint256 index,
+        address account,
+        uint256 amount,
+        bytes32[] calldata merkleProof
+    ) public {
+        if (isClaimed(index)) {
+            revert AlreadyClaimed(index);
+        }
+
+        bytes32 leaf = keccak256(abi.encodePacked(index, account, amount));
+        if (!_verify(merkleProof, leaf)) {
+            revert InvalidProof();
+        }
+
+        // The index is marked before the transfer, so a token calling back
+        // into this contract cannot claim it twice
+        claimedBitMap[index >> 8] |= 1 << (index & 0xff);
+
+        if (!IERC20(token).transfer(account, amount)) {
+            revert TransferFailed(token);
+        }
 
-    function addLiquidity(uint256 amount) public {
-        liquidity[msg.sender] += amount;
-        reserves += amount;
+        emit Claimed(index, account, amount);
     }
 
-    function removeLiquidity(uint256 amount) public returns (bool) {
-        require(liquidity[msg.sender] >= amount, "Insufficient liquidity");
+    // Whether proof leads from leaf to the Merkle root, one keccak256 per
+    // level of the tree
+    function _verify(bytes32[] calldata proof, bytes32 leaf) internal view returns (bool) {
+        bytes32 node = leaf;
+        for (uint256 i = 0; i < proof.length; ++i) {
+            bytes32 sibling = proof[i];
+            node = node <= sibling
+                ? keccak256(abi.encodePacked(node, sibling))
+                : keccak256(abi.encodePacked(sibling, node));
+        }
+        return node == merkleRoot;
+    }
+}
+`,
+    solidityTest: `// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {MerkleDistributor} from "../MerkleDistributor.sol";
+
+// Run with forge test from contracts-solidity. The tree mirrors the Rust
+// tests in contracts-rust/src/lib.rs, and both pin the same root.
+
+// Builds the tree the same way as the merkle module in contracts-rust: a
+// node without a sibling moves up a level unchanged.
+library MerkleTree {
+    struct Entry {
+        uint256 index;
+        address account;
+        uint256 amount;
+    }
+
+    function leaf(Entry memory entry) internal pure returns (bytes32) {
+        return keccak256(abi.encodePacked(entry.index, entry.account, entry.amount));
+    }
+
+    function hashPair(bytes32 a, bytes32 b) internal pure returns (bytes32) {
+        return a <= b ? keccak256(abi.encodePacked(a, b)) : keccak256(abi.encodePacked(b, a));
+    }
+
+    function root(Entry[] memory entries) internal pure returns (bytes32) {
+        bytes32[] memory nodes = _leaves(entries);
+        while (nodes.length > 1) {
+            nodes = _next(nodes);
+        }
+        return nodes[0];
+    }
 
-        liquidity[msg.sender] -= amount;
-        reserves -= amount;
+    // The proof for entries[n], ready to pass to claim
+    function proof(Entry[] memory entries, uint256 n) internal pure returns (bytes32[] memory) {
+        // Count the levels where the node has a sibling, then collect them
+        uint256 depth = 0;
+        uint256 position = n;
+        for (uint256 size = entries.length; size > 1; size = (size + 1) / 2) {
+            if ((position ^ 1) < size) {
+                ++depth;
+            }
+            position >>= 1;
+        }
+
+        bytes32[] memory result = new bytes32[](depth);
+        bytes32[] memory nodes = _leaves(entries);
+        uint256 filled = 0;
+        position = n;
+        while (nodes.length > 1) {
+            if ((position ^ 1) < nodes.length) {
+                result[filled++] = nodes[position ^ 1];
+            }
+            position >>= 1;
+            nodes = _next(nodes);
+        }
+        return result;
+    }
+
+    function _leaves(Entry[] memory entries) private pure returns (bytes32[] memory nodes) {
+        require(entries.length > 0, "a Merkle tree needs at least one entry");
+        nodes = new bytes32[](entries.length);
+        for (uint256 i = 0; i < entries.length; ++i) {
+            nodes[i] = leaf(entries[i]);
+        }
+    }
+
+    function _next(bytes32[] memory nodes) private pure returns (bytes32[] memory next) {
+        next = new bytes32[]((nodes.length + 1) / 2);
+        for (uint256 i = 0; i < next.length; ++i) {
+            next[i] = 2 * i + 1 < nodes.length
+                ? hashPair(nodes[2 * i], nodes[2 * i + 1])
+                : nodes[2 * i];
+        }
+    }
+}
+
+contract MockToken {
+    mapping(address => uint256) public balanceOf;
+    bool public succeed = true;
 
+    function setSucceed(bool succeed_) external {
+        succeed = succeed_;
+    }
+
+    function transfer(address to, uint256 value) external returns (bool) {
+        if (!succeed) {
+            return false;
+        }
+        balanceOf[to] += value;
         return true;
     }
+}
 
-    function getLiquidity(address provider) public view returns (uint256) {
-        return liquidity[provider];
+contract MerkleDistributorTest {
+    address private constant ALICE = 0xA1A1a1a1A1A1A1A1A1a1a1a1a1a1A1A1a1A1a1a1;
+    address private constant BOB = 0xB0B0b0B0B0B0B0b0B0B0B0b0b0b0b0B0b0b0B0B0;
+    address private constant CAROL = 0xcAcacaCacacACaCACaCaCACAcacAcaCACacAcAcA;
+
+    MockToken private token;
+    MerkleDistributor private distributor;
+
+    function setUp() public {
+        token = new MockToken();
+        distributor = new MerkleDistributor(address(token), MerkleTree.root(_entries()));
+    }
+
+    // Indexes 255 and 256 fall in different bit map words
+    function _entries() private pure returns (MerkleTree.Entry[] memory entries) {
+        entries = new MerkleTree.Entry[](5);
+        entries[0] = MerkleTree.Entry(0, ALICE, 100);
+        entries[1] = MerkleTree.Entry(1, BOB, 200);
+        entries[2] = MerkleTree.Entry(255, CAROL, 300);
+        entries[3] = MerkleTree.Entry(256, ALICE, 400);
+        entries[4] = MerkleTree.Entry(1000, BOB, 500);
+    }
+
+    function _claim(MerkleTree.Entry memory entry, bytes32[] memory proof) private {
+        distributor.claim(entry.index, entry.account, entry.amount, proof);
+    }
+
+    function _claimReason(
+        uint256 index,
+        address account,
+        uint256 amount,
+        bytes32[] memory proof
+    ) private returns (bytes memory) {
+        try distributor.claim(index, account, amount, proof) {
+            revert("expected claim to revert");
+        } catch (bytes memory reason) {
+            return reason;
+        }
+    }
+
+    function testTreeRoot() public pure {
+        // Pinned here and in the Rust tests, so both sides build the same tree
+        bytes32 root = 0x17d67de2b18acfe3fcea7a445ac93596f42ffffa9b7ac4735002c009f372e229;
+        require(MerkleTree.root(_entries()) == root, "root");
+    }
+
+    function testClaim() public {
+        MerkleTree.Entry[] memory entries = _entries();
+        require(distributor.token() == address(token), "token");
+        require(distributor.merkleRoot() == MerkleTree.root(entries), "merkleRoot");
+
+        // anyone may submit a claim, and the tokens go to the account
+        for (uint256 n = 0; n < entries.length; ++n) {
+            require(!distributor.isClaimed(entries[n].index), "claimed early");
+            _claim(entries[n], MerkleTree.proof(entries, n));
+            require(distributor.isClaimed(entries[n].index), "not claimed");
+        }
+
+        require(token.balanceOf(ALICE) == 500, "alice balance");
+        require(token.balanceOf(BOB) == 700, "bob balance");
+        require(token.balanceOf(CAROL) == 300, "carol balance");
+        require(token.balanceOf(address(this)) == 0, "submitter balance");
+    }
+
+    function testIsClaimedTracksEachIndex() public {
+        MerkleTree.Entry[] memory entries = _entries();
+        _claim(entries[2], MerkleTree.proof(entries, 2));
+
+        require(distributor.isClaimed(255), "255");
+        require(!distributor.isClaimed(254), "254");
+        require(!distributor.isClaimed(256), "256");
+        require(!distributor.isClaimed(511), "511");
+    }
+
+    function testClaimRejectsSecondClaim() public {
+        MerkleTree.Entry[] memory entries = _entries();
+        bytes32[] memory proof = MerkleTree.proof(entries, 1);
+        _claim(entries[1], proof);
+
+        bytes memory reason = _claimReason(1, BOB, 200, proof);
+        bytes memory expected =
+            abi.encodeWithSelector(MerkleDistributor.AlreadyClaimed.selector, uint256(1));
+        require(keccak256(reason) == keccak256(expected), "wrong error");
+    }
+
+    function testClaimRejectsInvalidProof() public {
+        MerkleTree.Entry[] memory entries = _entries();
+        bytes32[] memory proof = MerkleTree.proof(entries, 0);
+        bytes4 invalidProof = MerkleDistributor.InvalidProof.selector;
+
+        require(bytes4(_claimReason(0, ALICE, 101, proof)) == invalidProof, "amount");
+        require(bytes4(_claimReason(0, BOB, 100, proof)) == invalidProof, "account");
+        require(bytes4(_claimReason(1, ALICE, 100, proof)) == invalidProof, "index");
+        bytes32[] memory empty = new bytes32[](0);
+        require(bytes4(_claimReason(0, ALICE, 100, empty)) == invalidProof, "empty proof");
+        require(!distributor.isClaimed(0), "claimed");
     }
 
-    function getReserves() public view returns (uint256) {
-        return reserves;
+    function testClaimWithSingleEntryTree() public {
+        MerkleTree.Entry[] memory entries = new MerkleTree.Entry[](1);
+        entries[0] = MerkleTree.Entry(7, ALICE, 100);
+        distributor = new MerkleDistributor(address(token), MerkleTree.root(entries));
+
+        // the root is the leaf itself, so the proof is empty
+        bytes32[] memory proof = MerkleTree.proof(entries, 0);
+        require(proof.length == 0, "proof length");
+        _claim(entries[0], proof);
+        require(distributor.isClaimed(7), "not claimed");
+    }
+
+    function testClaimRejectsFailedTransfer() public {
+        MerkleTree.Entry[] memory entries = _entries();
+        token.setSucceed(false);
+
+        bytes memory reason = _claimReason(256, ALICE, 400, MerkleTree.proof(entries, 3));
+        bytes memory expected =
+            abi.encodeWithSelector(MerkleDistributor.TransferFailed.selector, address(token));
+        require(keccak256(reason) == keccak256(expected), "wrong error");
     }
 }
 `,
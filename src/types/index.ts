@@ -2,6 +2,7 @@ export interface ProjectConfig {
   name: string;
   version: string;
   template: string;
+  extensions?: string[];
   hasRust: boolean;
   hasSolidity: boolean;
   createdAt: string;
@@ -54,9 +55,54 @@ export interface ComparisonResult {
   solidityProfile: GasProfile;
   savings: GasSavings;
   tco: TCOAnalysis;
+  functionMatches?: FunctionMatchResult;
   timestamp: string;
 }
 
+export interface FunctionMatch {
+  rustFunction: string;
+  solidityFunction: string;
+  matchedBy: 'selector' | 'mapping';
+}
+
+export interface FunctionMatchResult {
+  matches: FunctionMatch[];
+  unmatchedRust: string[];
+  unmatchedSolidity: string[];
+}
+
+export interface ProfileCase {
+  label: string;
+  args: any[];
+  reverts?: boolean;
+}
+
+export interface ProfileConfig {
+  cases?: Record<string, ProfileCase[]>;
+  // Rows reporting the gas of one function less that of another
+  differences?: Record<string, [string, string]>;
+}
+
+export type ParityIssueKind =
+  | 'missing-in-rust'
+  | 'missing-in-solidity'
+  | 'signature-mismatch'
+  | 'mutability-mismatch';
+
+export interface ParityIssue {
+  kind: ParityIssueKind;
+  rustFunction?: string;
+  solidityFunction?: string;
+  rust?: string;
+  solidity?: string;
+}
+
+export interface ParityReport {
+  equivalent: boolean;
+  matched: number;
+  issues: ParityIssue[];
+}
+
 export interface GasSavings {
   deploymentSavings: {
     absolute: number;
@@ -71,6 +117,7 @@ export interface GasSavings {
 
 export interface FunctionSavings {
   functionName: string;
+  solidityFunctionName?: string;
   absolute: number;
   percentage: number;
   rustGas: number;
@@ -119,18 +166,29 @@ export interface ProfileOptions {
   network: string;
   rustPath?: string;
   solidityPath?: string;
+  rustAddress?: string;
+  solidityAddress?: string;
+  privateKey?: string;
+  privateKeyPath?: string;
   export: string;
   compile: boolean;
   detailed: boolean;
+  parityCheck: boolean;
 }
 
 export interface InitOptions {
   name?: string;
   template: string;
+  extensions?: string[];
   rustOnly: boolean;
   solidityOnly: boolean;
 }
 
+export interface ParityOptions {
+  rustPath?: string;
+  solidityPath?: string;
+}
+
 export interface BenchmarkOptions {
   contract?: string;
   iterations: string;
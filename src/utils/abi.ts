@@ -0,0 +1,74 @@
+import { ethers } from 'ethers';
+
+// Converts the Solidity interface printed by a Stylus export-abi binary into a JSON ABI.
+export function solidityInterfaceToAbi(source: string, constructorSignature?: string | null): any[] {
+  const fragments = solidityInterfaceToFragments(source);
+
+  if (constructorSignature) {
+    fragments.unshift(constructorSignature);
+  }
+
+  return JSON.parse(new ethers.Interface(fragments).formatJson());
+}
+
+// Extracts human-readable ABI fragments (functions, events, errors) from a Solidity interface.
+// Struct types are inlined as tuples so ethers can parse the fragments on their own.
+export function solidityInterfaceToFragments(source: string): string[] {
+  const code = source.replace(/\/\*[\s\S]*?\*\//g, '').replace(/\/\/.*$/gm, '');
+
+  const interfaceMatch = code.match(/interface\s+\w+[^{]*\{([\s\S]*)\}/);
+  if (!interfaceMatch) {
+    throw new Error('No Solidity interface found in export-abi output');
+  }
+
+  const structs = new Map<string, string>();
+  const body = interfaceMatch[1].replace(
+    /struct\s+(\w+)\s*\{([^}]*)\}/g,
+    (_match: string, name: string, fields: string) => {
+      const members = fields
+        .split(';')
+        .map((field) => field.replace(/\s+/g, ' ').trim())
+        .filter((field) => field.length > 0);
+      structs.set(name, `tuple(${members.join(', ')})`);
+      return '';
+    }
+  );
+
+  return body
+    .split(';')
+    .map((statement) => statement.replace(/\s+/g, ' ').trim())
+    .filter((statement) => /^(function|event|error)\b/.test(statement))
+    .map((statement) => inlineStructs(stripSolidityOnlyKeywords(statement), structs));
+}
+
+function stripSolidityOnlyKeywords(fragment: string): string {
+  return fragment
+    .replace(/\b(external|memory|calldata|storage)\b/g, '')
+    .replace(/\s+/g, ' ')
+    .replace(/\s+([,)])/g, '$1')
+    .trim();
+}
+
+function inlineStructs(fragment: string, structs: Map<string, string>): string {
+  let result = fragment;
+
+  // Structs may reference other structs, so substitute until nothing changes
+  for (let depth = 0; depth <= structs.size; depth++) {
+    let changed = false;
+
+    for (const [name, tuple] of structs) {
+      const pattern = new RegExp(`\\b${name}\\b`, 'g');
+      const next = result.replace(pattern, tuple);
+      if (next !== result) {
+        result = next;
+        changed = true;
+      }
+    }
+
+    if (!changed) {
+      break;
+    }
+  }
+
+  return result;
+}
@@ -5,27 +5,393 @@
 extern crate alloc;
 
 use alloc::vec::Vec;
-use stylus_sdk::{alloy_primitives::U256, prelude::*};
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    prelude::*,
+    stylus_core::log,
+};
 
 sol_storage! {
     #[entrypoint]
     pub struct Counter {
         uint256 number;
+        address owner;
     }
 }
 
+sol! {
+    #![sol(all_derives)]
+
+    event NumberSet(uint256 oldValue, uint256 newValue);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    error CounterOverflow(uint256 value);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+}
+
+#[derive(SolidityError, Debug)]
+pub enum CounterError {
+    CounterOverflow(CounterOverflow),
+    Unauthorized(Unauthorized),
+    InvalidOwner(InvalidOwner),
+}
+
 #[public]
 impl Counter {
+    /// Sets the initial number and makes the deploying account the owner.
+    #[constructor]
+    pub fn constructor(&mut self, initial_number: U256) {
+        self.number.set(initial_number);
+        let deployer = self.vm().tx_origin();
+        self.write_owner(deployer);
+    }
+
     pub fn number(&self) -> U256 {
         self.number.get()
     }
 
-    pub fn set_number(&mut self, new_number: U256) {
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    pub fn set_number(&mut self, new_number: U256) -> Result<(), CounterError> {
+        self.only_owner()?;
+        self.write_number(new_number);
+        Ok(())
+    }
+
+    /// set_number without the NumberSet event. Profiling the two measures
+    /// what the log costs.
+    pub fn set_number_unlogged(&mut self, new_number: U256) -> Result<(), CounterError> {
+        self.only_owner()?;
         self.number.set(new_number);
+        Ok(())
+    }
+
+    pub fn increment(&mut self) -> Result<(), CounterError> {
+        self.add_number(U256::from(1))
+    }
+
+    pub fn add_number(&mut self, new_number: U256) -> Result<(), CounterError> {
+        let number = self.number.get();
+        let next = number
+            .checked_add(new_number)
+            .ok_or(CounterError::CounterOverflow(CounterOverflow { value: number }))?;
+        self.write_number(next);
+        Ok(())
     }
 
-    pub fn increment(&mut self) {
+    pub fn mul_number(&mut self, new_number: U256) -> Result<(), CounterError> {
         let number = self.number.get();
-        self.set_number(number + U256::from(1));
+        let next = number
+            .checked_mul(new_number)
+            .ok_or(CounterError::CounterOverflow(CounterOverflow { value: number }))?;
+        self.write_number(next);
+        Ok(())
+    }
+
+    #[payable]
+    pub fn add_from_msg_value(&mut self) -> Result<(), CounterError> {
+        let value = self.vm().msg_value();
+        self.add_number(value)
+    }
+
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), CounterError> {
+        self.only_owner()?;
+        if new_owner == Address::ZERO {
+            return Err(CounterError::InvalidOwner(InvalidOwner { owner: new_owner }));
+        }
+        self.write_owner(new_owner);
+        Ok(())
+    }
+
+    pub fn renounce_ownership(&mut self) -> Result<(), CounterError> {
+        self.only_owner()?;
+        self.write_owner(Address::ZERO);
+        Ok(())
+    }
+}
+
+impl Counter {
+    fn only_owner(&self) -> Result<(), CounterError> {
+        let sender = self.vm().msg_sender();
+        if sender != self.owner.get() {
+            return Err(CounterError::Unauthorized(Unauthorized { account: sender }));
+        }
+        Ok(())
+    }
+
+    fn write_number(&mut self, new_number: U256) {
+        let old_number = self.number.get();
+        self.number.set(new_number);
+        log(
+            self.vm(),
+            NumberSet {
+                oldValue: old_number,
+                newValue: new_number,
+            },
+        );
+    }
+
+    fn write_owner(&mut self, new_owner: Address) {
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+        log(
+            self.vm(),
+            OwnershipTransferred {
+                previousOwner: previous_owner,
+                newOwner: new_owner,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use stylus_sdk::testing::*;
+
+    const OWNER: Address = Address::repeat_byte(0x11);
+    const ALICE: Address = Address::repeat_byte(0xa1);
+
+    /// Deploys a counter owned by OWNER, with OWNER as the caller.
+    fn deploy(vm: &TestVM, initial_number: U256) -> Counter {
+        let mut contract = Counter::from(vm);
+        contract.constructor(initial_number);
+        vm.set_sender(contract.owner());
+        contract.transfer_ownership(OWNER).unwrap();
+        vm.set_sender(OWNER);
+        contract
+    }
+
+    #[test]
+    fn test_number_starts_at_zero() {
+        let vm = TestVM::default();
+        let contract = Counter::from(&vm);
+
+        assert_eq!(U256::ZERO, contract.number());
+    }
+
+    #[test]
+    fn test_constructor_sets_initial_number() {
+        let vm = TestVM::default();
+        let mut contract = Counter::from(&vm);
+
+        contract.constructor(U256::from(10));
+        assert_eq!(U256::from(10), contract.number());
+    }
+
+    #[test]
+    fn test_set_number() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+
+        contract.set_number(U256::from(42)).unwrap();
+        assert_eq!(U256::from(42), contract.number());
+
+        contract.set_number(U256::ZERO).unwrap();
+        assert_eq!(U256::ZERO, contract.number());
+    }
+
+    #[test]
+    fn test_increment() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+
+        contract.increment().unwrap();
+        assert_eq!(U256::from(1), contract.number());
+
+        contract.set_number(U256::from(99)).unwrap();
+        contract.increment().unwrap();
+        assert_eq!(U256::from(100), contract.number());
+    }
+
+    #[test]
+    fn test_add_number() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(1));
+
+        contract.add_number(U256::from(3)).unwrap();
+        assert_eq!(U256::from(4), contract.number());
+
+        let err = contract.add_number(U256::MAX).unwrap_err();
+        assert!(matches!(err, CounterError::CounterOverflow(_)));
+        assert_eq!(U256::from(4), contract.number());
+    }
+
+    #[test]
+    fn test_mul_number() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(4));
+
+        contract.mul_number(U256::from(2)).unwrap();
+        assert_eq!(U256::from(8), contract.number());
+
+        let err = contract.mul_number(U256::MAX).unwrap_err();
+        assert!(matches!(err, CounterError::CounterOverflow(_)));
+        assert_eq!(U256::from(8), contract.number());
+
+        contract.mul_number(U256::ZERO).unwrap();
+        assert_eq!(U256::ZERO, contract.number());
+    }
+
+    #[test]
+    fn test_add_from_msg_value() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(100));
+
+        vm.set_value(U256::from(2));
+        contract.add_from_msg_value().unwrap();
+        assert_eq!(U256::from(102), contract.number());
+
+        // any caller may pay into the counter
+        vm.set_sender(ALICE);
+        vm.set_value(U256::from(8));
+        contract.add_from_msg_value().unwrap();
+        assert_eq!(U256::from(110), contract.number());
+    }
+
+    #[test]
+    fn test_set_number_emits_number_set() {
+        use alloy_sol_types::SolEvent;
+
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+        let logs_before = vm.get_emitted_logs().len();
+
+        contract.set_number(U256::from(7)).unwrap();
+        contract.increment().unwrap();
+
+        let logs = vm.get_emitted_logs();
+        assert_eq!(logs_before + 2, logs.len());
+
+        let expected = [
+            NumberSet { oldValue: U256::ZERO, newValue: U256::from(7) },
+            NumberSet { oldValue: U256::from(7), newValue: U256::from(8) },
+        ];
+        for ((topics, data), event) in logs[logs_before..].iter().zip(expected.iter()) {
+            assert_eq!(NumberSet::SIGNATURE_HASH, topics[0]);
+            assert_eq!(event.encode_data(), *data);
+        }
+    }
+
+    #[test]
+    fn test_set_number_unlogged() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+        let logs_before = vm.get_emitted_logs().len();
+
+        contract.set_number_unlogged(U256::from(7)).unwrap();
+        assert_eq!(U256::from(7), contract.number());
+        assert_eq!(logs_before, vm.get_emitted_logs().len());
+
+        vm.set_sender(ALICE);
+        let err = contract.set_number_unlogged(U256::from(1)).unwrap_err();
+        assert!(matches!(err, CounterError::Unauthorized(_)));
+        assert_eq!(U256::from(7), contract.number());
+    }
+
+    #[test]
+    fn test_increment_at_max_reverts() {
+        use alloy_sol_types::SolError;
+
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::MAX);
+
+        let logs_before = vm.get_emitted_logs().len();
+        let err = contract.increment().unwrap_err();
+        assert_eq!(U256::MAX, contract.number());
+        assert_eq!(logs_before, vm.get_emitted_logs().len());
+
+        let revert_data: Vec<u8> = err.into();
+        assert_eq!(
+            CounterOverflow { value: U256::MAX }.abi_encode(),
+            revert_data
+        );
+    }
+
+    #[test]
+    fn test_constructor_emits_ownership_transferred() {
+        use alloy_sol_types::SolEvent;
+
+        let vm = TestVM::default();
+        let mut contract = Counter::from(&vm);
+
+        contract.constructor(U256::ZERO);
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(OwnershipTransferred::SIGNATURE_HASH, topics[0]);
+        assert_eq!(Address::ZERO.into_word(), topics[1]);
+        assert_eq!(contract.owner().into_word(), topics[2]);
+    }
+
+    #[test]
+    fn test_set_number_rejects_non_owner() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::from(5));
+
+        vm.set_sender(ALICE);
+        let err = contract.set_number(U256::from(42)).unwrap_err();
+        assert!(matches!(
+            err,
+            CounterError::Unauthorized(Unauthorized { account }) if account == ALICE
+        ));
+        assert_eq!(U256::from(5), contract.number());
+
+        // increment stays open to everyone
+        contract.increment().unwrap();
+        assert_eq!(U256::from(6), contract.number());
+    }
+
+    #[test]
+    fn test_transfer_ownership() {
+        use alloy_sol_types::SolEvent;
+
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+
+        contract.transfer_ownership(ALICE).unwrap();
+        assert_eq!(ALICE, contract.owner());
+
+        let logs = vm.get_emitted_logs();
+        let (topics, _) = logs.last().unwrap();
+        assert_eq!(OwnershipTransferred::SIGNATURE_HASH, topics[0]);
+        assert_eq!(OWNER.into_word(), topics[1]);
+        assert_eq!(ALICE.into_word(), topics[2]);
+
+        // the previous owner is locked out
+        assert!(contract.set_number(U256::from(1)).is_err());
+        assert!(contract.transfer_ownership(OWNER).is_err());
+
+        vm.set_sender(ALICE);
+        contract.set_number(U256::from(1)).unwrap();
+        assert_eq!(U256::from(1), contract.number());
+    }
+
+    #[test]
+    fn test_transfer_ownership_rejects_zero_address() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+
+        let err = contract.transfer_ownership(Address::ZERO).unwrap_err();
+        assert!(matches!(err, CounterError::InvalidOwner(_)));
+        assert_eq!(OWNER, contract.owner());
+    }
+
+    #[test]
+    fn test_renounce_ownership() {
+        let vm = TestVM::default();
+        let mut contract = deploy(&vm, U256::ZERO);
+
+        vm.set_sender(ALICE);
+        assert!(contract.renounce_ownership().is_err());
+
+        vm.set_sender(OWNER);
+        contract.renounce_ownership().unwrap();
+        assert_eq!(Address::ZERO, contract.owner());
+        assert!(contract.set_number(U256::from(1)).is_err());
     }
 }
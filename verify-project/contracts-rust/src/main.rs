@@ -0,0 +1,14 @@
+// Binary target for cargo-stylus constructor detection and ABI export
+// Run with the export-abi feature to print the contract interface, or pass
+// "constructor" to print the constructor signature.
+
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+
+#[cfg(not(any(test, feature = "export-abi")))]
+#[no_mangle]
+pub extern "C" fn main() {}
+
+#[cfg(feature = "export-abi")]
+fn main() {
+    counter::print_from_args();
+}
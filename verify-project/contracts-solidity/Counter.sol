@@ -3,16 +3,86 @@ pragma solidity ^0.8.20;
 
 contract Counter {
     uint256 private count;
+    address private _owner;
+
+    event NumberSet(uint256 oldValue, uint256 newValue);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    error CounterOverflow(uint256 value);
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+
+    modifier onlyOwner() {
+        if (msg.sender != _owner) {
+            revert Unauthorized(msg.sender);
+        }
+        _;
+    }
+
+    constructor(uint256 initialNumber) {
+        count = initialNumber;
+        _transferOwnership(msg.sender);
+    }
 
     function increment() public {
-        count += 1;
+        addNumber(1);
+    }
+
+    function addNumber(uint256 newNumber) public {
+        if (newNumber > type(uint256).max - count) {
+            revert CounterOverflow(count);
+        }
+        _setCount(count + newNumber);
+    }
+
+    function mulNumber(uint256 newNumber) public {
+        if (newNumber != 0 && count > type(uint256).max / newNumber) {
+            revert CounterOverflow(count);
+        }
+        _setCount(count * newNumber);
+    }
+
+    function addFromMsgValue() public payable {
+        addNumber(msg.value);
     }
 
     function getCount() public view returns (uint256) {
         return count;
     }
 
-    function setCount(uint256 _count) public {
+    function setCount(uint256 _count) public onlyOwner {
+        _setCount(_count);
+    }
+
+    // setCount without the NumberSet event. Profiling the two measures what
+    // the log costs.
+    function setCountUnlogged(uint256 _count) public onlyOwner {
+        count = _count;
+    }
+
+    function owner() public view returns (address) {
+        return _owner;
+    }
+
+    function transferOwnership(address newOwner) public onlyOwner {
+        if (newOwner == address(0)) {
+            revert InvalidOwner(newOwner);
+        }
+        _transferOwnership(newOwner);
+    }
+
+    function renounceOwnership() public onlyOwner {
+        _transferOwnership(address(0));
+    }
+
+    function _setCount(uint256 _count) internal {
+        emit NumberSet(count, _count);
         count = _count;
     }
+
+    function _transferOwnership(address newOwner) internal {
+        address previousOwner = _owner;
+        _owner = newOwner;
+        emit OwnershipTransferred(previousOwner, newOwner);
+    }
 }